@@ -93,10 +93,32 @@
           };
 
           imports = [
+            "${self}/src/adguard/default.nix"
+            "${self}/src/azure/default.nix"
+            "${self}/src/bind/default.nix"
             "${self}/src/cloudflare/default.nix"
+            "${self}/src/desec/default.nix"
+            "${self}/src/digitalocean/default.nix"
+            "${self}/src/dnsmasq/default.nix"
+            "${self}/src/dnsserver/default.nix"
+            "${self}/src/etcd/default.nix"
+            "${self}/src/exec/default.nix"
+            "${self}/src/export/default.nix"
+            "${self}/src/gandi/default.nix"
+            "${self}/src/gcloud/default.nix"
             "${self}/src/headscale/default.nix"
+            "${self}/src/hetzner/default.nix"
+            "${self}/src/hosts/default.nix"
             "${self}/src/jsonfile/default.nix"
             "${self}/src/machinectl/default.nix"
+            "${self}/src/magicdns/default.nix"
+            "${self}/src/mikrotik/default.nix"
+            "${self}/src/pihole/default.nix"
+            "${self}/src/powerdns/default.nix"
+            "${self}/src/rfc2136/default.nix"
+            "${self}/src/route53/default.nix"
+            "${self}/src/technitium/default.nix"
+            "${self}/src/unbound/default.nix"
           ];
 
           config = lib.mkIf cfg.enable {
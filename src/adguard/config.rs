@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    /// The AdGuard Home web interface, e.g. http://192.168.1.2:3000
+    pub base_url: url::Url,
+    pub username: String,
+    pub password: String,
+    pub domain: String,
+    /// Where to keep the list of rewrites this instance created.
+    pub state_path: PathBuf,
+}
@@ -0,0 +1,45 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.adguard;
+in
+{
+  options.dnssync.frontends.adguard = {
+    enable = lib.mkEnableOption "AdGuard Home DNS rewrite frontend for records";
+    baseUrl = mkOption {
+      type = types.str;
+      description = "The AdGuard Home web interface, e.g. http://192.168.1.2:3000";
+    };
+    username = mkOption {
+      type = types.str;
+      description = "The AdGuard Home user to log in as";
+    };
+    passwordFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the AdGuard Home password. Must be owned by the dnssync user";
+    };
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write DNS rewrites for";
+    };
+    statePath = mkOption {
+      type = types.str;
+      default = "/var/lib/dnssync/adguard.json";
+      description = "Where to keep the list of rewrites created by dnssync";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "adguard";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.serviceConfig.StateDirectory = [ "dnssync" ];
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.statePath) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_ADGUARD_BASE_URL" = cfg.baseUrl;
+      "DNSSYNC_ADGUARD_USERNAME" = cfg.username;
+      "DNSSYNC_ADGUARD_PASSWORD" = "@${cfg.passwordFile}";
+      "DNSSYNC_ADGUARD_DOMAIN" = cfg.domain;
+      "DNSSYNC_ADGUARD_STATE_PATH" = cfg.statePath;
+    };
+  };
+}
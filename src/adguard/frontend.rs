@@ -0,0 +1,230 @@
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, diff_records, key_file_or_string, request_error_text, FrontendSnafu,
+    OwnedEntry, OwnershipFile, Record, Result, RECORD_KIND_A, RECORD_KIND_AAAA,
+};
+
+use super::models::{Rewrite, RewriteRecord, RewriteUpdate, RECORD_KIND_CNAME};
+
+pub const FRONTEND_NAME: &str = "AdGuardHome";
+
+pub struct AdGuard {
+    base_url: url::Url,
+    username: String,
+    password: String,
+    domain: String,
+    ownership: OwnershipFile,
+}
+
+impl AdGuard {
+    fn api_url(&self, path: &[&str]) -> url::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .push("control")
+            .extend(path);
+        url
+    }
+
+    fn request(&self, method: &str, url: &url::Url) -> ureq::Request {
+        tracing::debug!(
+            url = url.as_str(),
+            method = method,
+            frontend = "adguard",
+            "Sending request"
+        );
+        let credentials = base64::engine::general_purpose::STANDARD
+            .encode(format!("{}:{}", self.username, self.password));
+        ureq::request(method, url.as_str()).set("Authorization", &format!("Basic {credentials}"))
+    }
+
+    fn api_call<T: DeserializeOwned>(&self, method: &str, url: url::Url) -> Result<T> {
+        self.request(method, &url)
+            .call()
+            .map_err(|err| request_error_text(err, url.as_str(), method, Some))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    /// Sends a change, which AdGuard Home answers with an empty body.
+    fn send(&self, method: &str, path: &[&str], body: impl serde::Serialize) -> Result<()> {
+        let url = self.api_url(path);
+        self.request(method, &url)
+            .send_json(body)
+            .map_err(|err| request_error_text(err, url.as_str(), method, Some))?;
+        Ok(())
+    }
+
+    fn read_records(&self) -> Result<Vec<RewriteRecord>> {
+        let rewrites: Vec<Rewrite> = self.api_call("GET", self.api_url(&["rewrite", "list"]))?;
+        Ok(rewrites
+            .into_iter()
+            .filter_map(RewriteRecord::from_api)
+            .collect())
+    }
+
+    fn load_ownership(&self) -> Result<Vec<OwnedEntry>> {
+        self.ownership.load().boxed_local().context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: format!("Failed to read {}", self.ownership.path().display()),
+        })
+    }
+
+    fn save_ownership(&self, owned: Vec<OwnedEntry>) -> Result<()> {
+        self.ownership
+            .save(owned)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.ownership.path().display()),
+            })
+    }
+}
+
+impl common::Frontend for AdGuard {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.load_ownership()?;
+        check_writable(self.ownership.path())
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.ownership.path().display()),
+            })?;
+        self.read_records().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        // Rewrites can only answer with addresses and CNAMEs
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = [RECORD_KIND_A, RECORD_KIND_AAAA, RECORD_KIND_CNAME]
+                    .iter()
+                    .any(|kind| r.kind.eq_ignore_ascii_case(kind));
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        let owned = self.load_ownership()?;
+        let current: Vec<RewriteRecord> = self
+            .read_records()?
+            .into_iter()
+            .map(|record| record.with_owner(&owned))
+            .collect();
+        let diff = diff_records(current.clone(), authority);
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        // Claim the records about to be created first, so they are
+        // still recognised as ours if a later change fails.
+        let owned_after: Vec<OwnedEntry> = diff
+            .apply(current)
+            .iter()
+            .filter(|record| record.managed)
+            .map(RewriteRecord::owned_entry)
+            .collect();
+        let mut claimed = owned.clone();
+        claimed.extend(owned_after.iter().filter(|e| !owned.contains(e)).cloned());
+        self.save_ownership(claimed)?;
+
+        for record in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            let target = record.current.clone().unwrap_or_else(|| record.to_api());
+            self.send("POST", &["rewrite", "delete"], target)?;
+        }
+        for record in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+            let update = RewriteUpdate {
+                target: record.current.clone().unwrap_or_else(|| record.to_api()),
+                update: record.to_api(),
+            };
+            self.send("PUT", &["rewrite", "update"], update)?;
+        }
+        for record in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            self.send("POST", &["rewrite", "add"], record.to_api())?;
+        }
+
+        self.save_ownership(owned_after)
+    }
+}
+
+impl From<super::Config> for AdGuard {
+    fn from(value: super::Config) -> Self {
+        let password = key_file_or_string(value.password, FRONTEND_NAME.into()).unwrap();
+
+        Self {
+            base_url: value.base_url,
+            username: value.username,
+            password,
+            domain: value.domain,
+            ownership: OwnershipFile::new(value.state_path),
+        }
+    }
+}
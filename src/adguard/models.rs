@@ -0,0 +1,117 @@
+use std::net::IpAddr;
+
+use crate::common::{Manage, Match, OwnedEntry, Record, Update, RECORD_KIND_A, RECORD_KIND_AAAA};
+
+pub(super) const RECORD_KIND_CNAME: &str = "CNAME";
+
+/// A DNS rewrite, as used by the rewrite API.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct Rewrite {
+    pub domain: String,
+    pub answer: String,
+}
+
+#[derive(serde::Serialize)]
+pub(super) struct RewriteUpdate {
+    pub target: Rewrite,
+    pub update: Rewrite,
+}
+
+/// A rewrite, with its kind worked out from the answer.
+#[derive(Clone)]
+pub(super) struct RewriteRecord {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    /// The rewrite as it currently is, None if not yet created.
+    pub current: Option<Rewrite>,
+    pub managed: bool,
+}
+
+impl RewriteRecord {
+    /// Returns None for rewrites which keep the upstream answer,
+    /// which are written with `A` or `AAAA` as their answer.
+    pub(super) fn from_api(rewrite: Rewrite) -> Option<Self> {
+        if rewrite.answer == RECORD_KIND_A || rewrite.answer == RECORD_KIND_AAAA {
+            return None;
+        }
+        let kind = match rewrite.answer.parse::<IpAddr>() {
+            Ok(IpAddr::V4(_)) => RECORD_KIND_A,
+            Ok(IpAddr::V6(_)) => RECORD_KIND_AAAA,
+            Err(_) => RECORD_KIND_CNAME,
+        };
+        let content = match kind {
+            RECORD_KIND_CNAME => rewrite.answer.trim_end_matches('.').to_lowercase(),
+            _ => rewrite.answer.clone(),
+        };
+        Some(Self {
+            name: rewrite.domain.trim_end_matches('.').to_lowercase(),
+            kind: kind.to_string(),
+            content,
+            current: Some(rewrite),
+            managed: false,
+        })
+    }
+
+    pub(super) fn to_api(&self) -> Rewrite {
+        Rewrite {
+            domain: self.name.clone(),
+            answer: self.content.clone(),
+        }
+    }
+
+    pub(super) fn owned_entry(&self) -> OwnedEntry {
+        OwnedEntry {
+            name: self.name.clone(),
+            kind: self.kind.clone(),
+            content: self.content.clone(),
+        }
+    }
+
+    pub(super) fn with_owner(mut self, owned: &[OwnedEntry]) -> Self {
+        self.managed = owned.contains(&self.owned_entry());
+        self
+    }
+}
+
+impl Manage for RewriteRecord {
+    fn is_managed(&self) -> bool {
+        self.managed
+    }
+}
+
+impl Match for RewriteRecord {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RewriteRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = RewriteRecord::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for RewriteRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<Record> for RewriteRecord {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = match kind.as_str() {
+            RECORD_KIND_CNAME => value.content.trim_end_matches('.').to_lowercase(),
+            _ => value.content,
+        };
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            current: None,
+            managed: true,
+        }
+    }
+}
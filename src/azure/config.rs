@@ -0,0 +1,20 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    /// The directory (tenant) ID of the app registration.
+    pub tenant_id: String,
+    /// The application (client) ID of the app registration.
+    pub client_id: String,
+    /// The client secret, or @ followed by a path to read it from.
+    pub client_secret: String,
+    pub subscription_id: String,
+    pub resource_group: String,
+    /// The name of the DNS zone, which is also its DNS name.
+    pub domain: String,
+    pub instance_id: String,
+    /// Override the Microsoft Entra ID authority, e.g. for a national cloud.
+    pub authority_url: Option<url::Url>,
+    /// Override the Resource Manager URL, e.g. for a national cloud.
+    /// Access tokens are requested for this URL.
+    pub base_url: Option<url::Url>,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,73 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.azure;
+in
+{
+  options.dnssync.frontends.azure = {
+    enable = lib.mkEnableOption "Azure DNS frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The name of the DNS zone";
+    };
+    tenantId = mkOption {
+      type = types.str;
+      description = "The directory (tenant) ID of the app registration";
+    };
+    clientId = mkOption {
+      type = types.str;
+      description = "The application (client) ID of the app registration";
+    };
+    clientSecretFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the client secret. Must be owned by the dnssync user";
+    };
+    subscriptionId = mkOption {
+      type = types.str;
+      description = "The subscription containing the DNS zone";
+    };
+    resourceGroup = mkOption {
+      type = types.str;
+      description = "The resource group containing the DNS zone";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    authorityUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "The Microsoft Entra ID authority, for national clouds";
+    };
+    baseUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "The Resource Manager URL, for national clouds";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "azure";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_AZURE_DOMAIN" = cfg.domain;
+      "DNSSYNC_AZURE_TENANT_ID" = cfg.tenantId;
+      "DNSSYNC_AZURE_CLIENT_ID" = cfg.clientId;
+      "DNSSYNC_AZURE_CLIENT_SECRET" = "@${cfg.clientSecretFile}";
+      "DNSSYNC_AZURE_SUBSCRIPTION_ID" = cfg.subscriptionId;
+      "DNSSYNC_AZURE_RESOURCE_GROUP" = cfg.resourceGroup;
+      "DNSSYNC_AZURE_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_AZURE_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.authorityUrl != null) {
+      "DNSSYNC_AZURE_AUTHORITY_URL" = cfg.authorityUrl;
+    } // lib.optionalAttrs (cfg.baseUrl != null) {
+      "DNSSYNC_AZURE_BASE_URL" = cfg.baseUrl;
+    };
+  };
+}
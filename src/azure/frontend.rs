@@ -0,0 +1,327 @@
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, diff_records, group_rrsets, key_file_or_string, request_error, AccessToken,
+    FrontendSnafu, Record, ResponseSnafu, Result,
+};
+
+use super::models::{
+    is_supported, APIError, APIRecordSet, ListResponse, RecordSet, Zone, API_VERSION, DEFAULT_TTL,
+};
+
+pub const API_BASE_URL: &str = "https://management.azure.com";
+pub const AUTHORITY_URL: &str = "https://login.microsoftonline.com";
+
+pub const FRONTEND_NAME: &str = "AzureDNS";
+
+const GRANT_TYPE: &str = "client_credentials";
+
+pub struct AzureDNS {
+    client_id: String,
+    client_secret: String,
+    token_url: url::Url,
+    scope: String,
+    token: Option<AccessToken>,
+    base_url: url::Url,
+    subscription_id: String,
+    resource_group: String,
+    domain: String,
+    instance_id: String,
+    ttl: u32,
+}
+
+impl AzureDNS {
+    /// Requests an access token with the client credentials,
+    /// unless the current token is still fresh.
+    fn authorize(&mut self) -> Result<()> {
+        if self.token.as_ref().is_some_and(AccessToken::is_fresh) {
+            return Ok(());
+        }
+
+        let token = AccessToken::request(
+            FRONTEND_NAME,
+            self.token_url.as_str(),
+            &[
+                ("grant_type", GRANT_TYPE),
+                ("client_id", &self.client_id),
+                ("client_secret", &self.client_secret),
+                ("scope", &self.scope),
+            ],
+        )?;
+        self.token = Some(token);
+        Ok(())
+    }
+
+    fn zone_url(&self, path: &[&str]) -> url::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .extend(&[
+                "subscriptions",
+                &self.subscription_id,
+                "resourceGroups",
+                &self.resource_group,
+                "providers",
+                "Microsoft.Network",
+                "dnsZones",
+                &self.domain,
+            ])
+            .extend(path);
+        url.query_pairs_mut()
+            .append_pair("api-version", API_VERSION);
+        url
+    }
+
+    /// The URL of a record set, which is named relative to the zone.
+    fn record_url(&self, record: &RecordSet) -> url::Url {
+        let name = match record.name == self.domain {
+            true => "@",
+            false => record
+                .name
+                .strip_suffix(&format!(".{}", self.domain))
+                .unwrap_or(&record.name),
+        };
+        self.zone_url(&[&record.kind, name])
+    }
+
+    fn request(&self, method: &str, url: &url::Url) -> ureq::Request {
+        tracing::debug!(
+            url = url.as_str(),
+            method = method,
+            frontend = "azure",
+            "Sending request"
+        );
+        let token = self.token.as_ref().map(|t| t.value.as_str());
+        ureq::request(method, url.as_str()).set(
+            "Authorization",
+            &format!("Bearer {}", token.unwrap_or_default()),
+        )
+    }
+
+    fn api_call<T: DeserializeOwned>(&self, url: url::Url) -> Result<T> {
+        self.request("GET", &url)
+            .call()
+            .map_err(|err| request_error(err, url.as_str(), "GET", APIError::detail))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    fn read_records(&self) -> Result<Vec<RecordSet>> {
+        let mut records = Vec::new();
+        let mut url = self.zone_url(&["recordsets"]);
+        loop {
+            let resp: ListResponse<APIRecordSet> = self.api_call(url)?;
+            records.extend(resp.value.into_iter().filter_map(RecordSet::from_api));
+
+            // The next link is absolute, and already has the API version
+            match resp.next_link {
+                Some(next) => {
+                    url = url::Url::parse(&next)
+                        .boxed_local()
+                        .context(FrontendSnafu {
+                            frontend: FRONTEND_NAME,
+                            message: "Invalid next page link",
+                        })?
+                }
+                None => return Ok(records),
+            }
+        }
+    }
+
+    /// Writes a record set, failing if it was changed since it was read.
+    fn write_record(&self, record: &RecordSet) -> Result<()> {
+        let url = self.record_url(record);
+        let body = record.to_api().ok_or_else(|| Self::content_error(record))?;
+        let req = match &record.etag {
+            Some(etag) => self.request("PUT", &url).set("If-Match", etag),
+            None => self.request("PUT", &url).set("If-None-Match", "*"),
+        };
+        req.send_json(body)
+            .map_err(|err| request_error(err, url.as_str(), "PUT", APIError::detail))?;
+        Ok(())
+    }
+
+    fn delete_record(&self, record: &RecordSet) -> Result<()> {
+        let url = self.record_url(record);
+        let mut req = self.request("DELETE", &url);
+        if let Some(etag) = &record.etag {
+            req = req.set("If-Match", etag);
+        }
+        req.call()
+            .map_err(|err| request_error(err, url.as_str(), "DELETE", APIError::detail))?;
+        Ok(())
+    }
+
+    fn content_error(record: &RecordSet) -> common::Error {
+        ResponseSnafu {
+            message: format!(
+                "Cannot write {} record {} with content {}",
+                record.kind, record.name, record.content
+            ),
+        }
+        .build()
+    }
+}
+
+impl common::Frontend for AzureDNS {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.authorize()?;
+        let zone: Zone = self.api_call(self.zone_url(&[]))?;
+        tracing::debug!(frontend = FRONTEND_NAME, zone = zone.name, "Found zone");
+        self.read_records().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = is_supported(&r.kind);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        self.authorize()?;
+        let current = self.read_records()?;
+        let diff = diff_records::<RecordSet>(current, group_rrsets(authority));
+
+        // Evaluate deletions more precisely based on instance ID
+        let diff_len = diff.len() - diff.delete.len();
+        let deletions: Vec<RecordSet> = diff
+            .delete
+            .into_iter()
+            .filter(|record| record.get_instance_id().eq(&Some(&self.instance_id)))
+            .collect();
+        let diff_len = diff_len + deletions.len();
+
+        // Short circuit on no changes
+        if diff_len == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = deletions.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = deletions.len(),
+            "Applying changes",
+        );
+
+        // Deletes first - to avoid conflicts with CNAMEs
+        for record in deletions {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            self.delete_record(&record)?;
+        }
+
+        for mut record in diff.update {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+
+            if record.get_instance_id().ne(&Some(&self.instance_id)) {
+                tracing::warn!(
+                    frontend = FRONTEND_NAME,
+                    name = record.name,
+                    kind = record.kind,
+                    content = record.content,
+                    "Record is not managed by this instance but we will update anyway"
+                )
+            }
+            record.set_instance_id(&self.instance_id);
+            self.write_record(&record)?;
+        }
+
+        for mut record in diff.create {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            record.ttl = self.ttl;
+            record.set_instance_id(&self.instance_id);
+            self.write_record(&record)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for AzureDNS {
+    fn from(value: super::Config) -> Self {
+        let client_secret = key_file_or_string(value.client_secret, FRONTEND_NAME.into()).unwrap();
+
+        let base_url = value
+            .base_url
+            .unwrap_or_else(|| url::Url::parse(API_BASE_URL).unwrap());
+        let scope = format!("{}/.default", base_url.as_str().trim_end_matches('/'));
+
+        let mut token_url = value
+            .authority_url
+            .unwrap_or_else(|| url::Url::parse(AUTHORITY_URL).unwrap());
+        token_url
+            .path_segments_mut()
+            .expect("authority_url should be a HTTP URL")
+            .pop_if_empty()
+            .extend(&[&value.tenant_id, "oauth2", "v2.0", "token"]);
+
+        Self {
+            client_id: value.client_id,
+            client_secret,
+            token_url,
+            scope,
+            token: None,
+            base_url,
+            subscription_id: value.subscription_id,
+            resource_group: value.resource_group,
+            domain: value.domain.trim_end_matches('.').to_lowercase(),
+            instance_id: value.instance_id,
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
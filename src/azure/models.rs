@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::common::{join_values, normalise_content, split_txt, Manage, Match, Record, Update};
+
+pub(super) const API_VERSION: &str = "2018-05-01";
+pub(super) const METADATA_INSTANCE: &str = "dnssync-instance";
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+/// Fields which the API expects as numbers.
+const NUMBER_FIELDS: [&str; 5] = ["preference", "priority", "weight", "port", "flags"];
+
+/// The record kinds DNSSync can write, the property holding their values,
+/// and the fields of each value, in presentation order.
+const RECORD_PROPERTIES: [(&str, &str, &[&str]); 9] = [
+    ("A", "ARecords", &["ipv4Address"]),
+    ("AAAA", "AAAARecords", &["ipv6Address"]),
+    ("CNAME", "CNAMERecord", &["cname"]),
+    ("NS", "NSRecords", &["nsdname"]),
+    ("PTR", "PTRRecords", &["ptrdname"]),
+    ("TXT", "TXTRecords", &["value"]),
+    ("MX", "MXRecords", &["preference", "exchange"]),
+    (
+        "SRV",
+        "SRVRecords",
+        &["priority", "weight", "port", "target"],
+    ),
+    ("CAA", "caaRecords", &["flags", "tag", "value"]),
+];
+
+fn record_properties(kind: &str) -> Option<(&'static str, &'static [&'static str])> {
+    RECORD_PROPERTIES
+        .iter()
+        .find(|(k, _, _)| k.eq_ignore_ascii_case(kind))
+        .map(|(_, property, fields)| (*property, *fields))
+}
+
+pub(super) fn is_supported(kind: &str) -> bool {
+    record_properties(kind).is_some()
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub error: ErrorDetail,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ErrorDetail {
+    pub code: String,
+    pub message: String,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        Some(format!("{}: {}", self.error.code, self.error.message))
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Zone {
+    pub name: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ListResponse<T> {
+    pub value: Vec<T>,
+    pub next_link: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIRecordSet {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub etag: Option<String>,
+    pub properties: APIProperties,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIProperties {
+    #[serde(rename = "TTL")]
+    pub ttl: u32,
+    pub fqdn: String,
+    pub metadata: Option<BTreeMap<String, String>>,
+    /// The record values, under a property named for their kind.
+    #[serde(flatten)]
+    pub records: Map<String, Value>,
+}
+
+/// Converts one value of a record set to DNSSync content.
+fn value_to_content(kind: &str, fields: &[&str], value: &Value) -> Option<String> {
+    if kind == "TXT" {
+        return value["value"]
+            .as_array()?
+            .iter()
+            .map(|chunk| chunk.as_str())
+            .collect();
+    }
+    let values = fields
+        .iter()
+        .map(|field| match &value[*field] {
+            Value::String(value) => Some(value.clone()),
+            Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        })
+        .collect::<Option<Vec<String>>>()?;
+    match kind {
+        "CAA" => Some(format!("{} {} \"{}\"", values[0], values[1], values[2])),
+        _ => Some(normalise_content(kind, &values.join(" "))),
+    }
+}
+
+/// Converts DNSSync content to one value of a record set.
+/// The last field takes the rest of the content, as CAA values may contain spaces.
+fn content_to_value(kind: &str, fields: &[&str], content: &str) -> Option<Value> {
+    if kind == "TXT" {
+        return Some(json!({ "value": split_txt(content) }));
+    }
+
+    let mut parts = content.splitn(fields.len(), ' ');
+    let mut value = Map::new();
+    for field in fields {
+        let part = parts.next().filter(|p| !p.is_empty())?;
+        let part = match NUMBER_FIELDS.contains(field) {
+            true => json!(part.parse::<u64>().ok()?),
+            // CAA values are quoted in presentation format, but not in the API
+            false if kind == "CAA" && *field == "value" => json!(part.trim_matches('"')),
+            false => json!(part),
+        };
+        value.insert(field.to_string(), part);
+    }
+    Some(Value::Object(value))
+}
+
+/// A record set, with its values as one record separated by newlines.
+#[derive(Clone)]
+pub(super) struct RecordSet {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: u32,
+    /// Used to make sure the record set hasn't changed since it was read.
+    /// None if not yet created.
+    pub etag: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl RecordSet {
+    /// Returns None for record sets DNSSync doesn't manage, such as SOA
+    /// or alias record sets.
+    pub(super) fn from_api(set: APIRecordSet) -> Option<Self> {
+        let kind = set.kind.rsplit('/').next()?.to_uppercase();
+        let (property, fields) = record_properties(&kind)?;
+        let target = set.properties.records.get("targetResource");
+        if target
+            .and_then(|t| t.get("id"))
+            .is_some_and(|id| !id.is_null())
+        {
+            return None;
+        }
+
+        let values = match set.properties.records.get(property)? {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        let content = join_values(
+            values
+                .into_iter()
+                .map(|value| value_to_content(&kind, fields, value))
+                .collect::<Option<Vec<String>>>()?,
+        );
+        Some(Self {
+            name: set.properties.fqdn.trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: set.properties.ttl,
+            etag: set.etag,
+            metadata: set.properties.metadata.unwrap_or_default(),
+        })
+    }
+
+    /// The body of a request writing the record set,
+    /// or None if the content can't be represented.
+    pub(super) fn to_api(&self) -> Option<Value> {
+        let (property, fields) = record_properties(&self.kind)?;
+        let values = self
+            .content
+            .split('\n')
+            .map(|content| content_to_value(&self.kind, fields, content))
+            .collect::<Option<Vec<Value>>>()?;
+        let values = match self.kind.as_str() {
+            // A CNAME record set holds exactly one value
+            "CNAME" if values.len() == 1 => values.into_iter().next()?,
+            "CNAME" => return None,
+            _ => Value::Array(values),
+        };
+        Some(json!({
+            "properties": {
+                "TTL": self.ttl,
+                "metadata": self.metadata,
+                property: values,
+            }
+        }))
+    }
+
+    pub(super) fn get_instance_id(&self) -> Option<&String> {
+        self.metadata.get(METADATA_INSTANCE)
+    }
+
+    /// Marks the record set as managed by instance_id.
+    /// Any other metadata is kept as is.
+    pub(super) fn set_instance_id(&mut self, instance_id: &str) {
+        self.metadata
+            .insert(METADATA_INSTANCE.to_string(), instance_id.to_string());
+    }
+}
+
+impl Manage for RecordSet {
+    fn is_managed(&self) -> bool {
+        self.metadata.contains_key(METADATA_INSTANCE)
+    }
+}
+
+impl Match for RecordSet {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RecordSet {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = RecordSet::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for RecordSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<Record> for RecordSet {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = join_values(
+            value
+                .content
+                .split('\n')
+                .map(|content| normalise_content(&kind, content))
+                .collect(),
+        );
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: DEFAULT_TTL,
+            etag: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// The zone file to write. It is entirely managed by DNSSync.
+    pub path: PathBuf,
+    /// Write only the records, for use with $INCLUDE from a zone file managed elsewhere.
+    #[serde(default)]
+    pub fragment: bool,
+    /// SOA MNAME. Required unless writing a fragment.
+    pub primary_ns: Option<String>,
+    /// SOA RNAME, either as an email address or in DNS form. Defaults to hostmaster.<domain>.
+    pub hostmaster: Option<String>,
+    /// Comma separated list of nameservers for the zone. Defaults to primary_ns.
+    pub nameservers: Option<String>,
+    /// Shell command run after the file changes, e.g. `rndc reload example.com`.
+    pub reload_command: Option<String>,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,70 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.bind;
+in
+{
+  options.dnssync.frontends.bind = {
+    enable = lib.mkEnableOption "BIND zone file frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The zone to write records for";
+    };
+    path = mkOption {
+      type = types.str;
+      example = "/var/lib/bind/internal.example.com.zone";
+      description = "The zone file to write. Must be writable by the dnssync user";
+    };
+    fragment = mkOption {
+      type = types.bool;
+      default = false;
+      description = "Write only the records, without SOA and NS, for use with $INCLUDE";
+    };
+    primaryNs = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "ns1.example.com";
+      description = "The SOA primary nameserver. Required unless fragment is set";
+    };
+    hostmaster = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "hostmaster@example.com";
+      description = "The SOA contact. Defaults to hostmaster at the domain";
+    };
+    nameservers = mkOption {
+      type = types.listOf types.str;
+      default = [ ];
+      description = "NS records for the zone apex. Defaults to primaryNs";
+    };
+    reloadCommand = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "rndc reload internal.example.com";
+      description = "Command to run after the zone file changes";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "bind";
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.path) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_BIND_DOMAIN" = cfg.domain;
+      "DNSSYNC_BIND_PATH" = cfg.path;
+      "DNSSYNC_BIND_FRAGMENT" = lib.boolToString cfg.fragment;
+      "DNSSYNC_BIND_NAMESERVERS" = lib.concatStringsSep "," cfg.nameservers;
+      "DNSSYNC_BIND_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.primaryNs != null) {
+      "DNSSYNC_BIND_PRIMARY_NS" = cfg.primaryNs;
+    } // lib.optionalAttrs (cfg.hostmaster != null) {
+      "DNSSYNC_BIND_HOSTMASTER" = cfg.hostmaster;
+    } // lib.optionalAttrs (cfg.reloadCommand != null) {
+      "DNSSYNC_BIND_RELOAD_COMMAND" = cfg.reloadCommand;
+    };
+  };
+}
@@ -0,0 +1,269 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, diff_records, read_to_string_or_empty, run_shell, write_atomic,
+    ConfigSnafu, FrontendSnafu, Record, Result,
+};
+
+use super::models::{ZoneFile, ZoneRecord, DEFAULT_TTL};
+
+pub const FRONTEND_NAME: &str = "BIND";
+
+const HEADER: &str = "; Managed by DNSSync. Changes made by hand will be overwritten.";
+
+// SOA timers, in seconds
+const SOA_REFRESH: u32 = 3600;
+const SOA_RETRY: u32 = 900;
+const SOA_EXPIRE: u32 = 1209600;
+
+/// The SOA and NS records written at the top of a full zone file.
+struct Apex {
+    primary_ns: String,
+    hostmaster: String,
+    nameservers: Vec<String>,
+}
+
+pub struct Bind {
+    domain: String,
+    path: PathBuf,
+    apex: Option<Apex>,
+    reload_command: Option<String>,
+    ttl: u32,
+}
+
+/// Returns a date based serial (YYYYMMDDnn) greater than the current one.
+fn next_serial(current: Option<u32>) -> u32 {
+    let today: u32 = humantime::format_rfc3339(SystemTime::now()).to_string()[..10]
+        .replace('-', "")
+        .parse()
+        .expect("RFC 3339 dates are numeric");
+    let today = today * 100;
+    match current {
+        Some(serial) if serial >= today => serial + 1,
+        _ => today,
+    }
+}
+
+fn to_fqdn(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+fn config_error(message: String) -> common::Error {
+    ConfigSnafu {
+        message,
+        prefix: FRONTEND_NAME,
+    }
+    .build()
+}
+
+impl Bind {
+    fn read_zone(&self) -> Result<(String, ZoneFile)> {
+        let text = read_to_string_or_empty(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read {}", self.path.display()),
+            })?;
+        let mut zone = ZoneFile::parse(&text);
+        // Apex NS records are written from config, not synced
+        if self.apex.is_some() {
+            zone.records.retain(|r| {
+                !(r.kind == "NS"
+                    && r.name
+                        .eq_ignore_ascii_case(self.domain.trim_end_matches('.')))
+            });
+        }
+        Ok((text, zone))
+    }
+
+    fn render(&self, records: &[ZoneRecord], serial: u32) -> String {
+        let mut lines = vec![HEADER.to_string()];
+
+        if let Some(apex) = &self.apex {
+            let domain = self.domain.trim_end_matches('.').to_lowercase();
+            lines.push(format!("$TTL {}", self.ttl));
+            lines.push(format!(
+                "{}.\t{}\tIN\tSOA\t{} {} {serial} {SOA_REFRESH} {SOA_RETRY} {SOA_EXPIRE} {}",
+                domain,
+                self.ttl,
+                to_fqdn(&apex.primary_ns),
+                to_fqdn(&apex.hostmaster),
+                self.ttl,
+            ));
+            for ns in apex.nameservers.iter() {
+                lines.push(format!("{domain}.\t{}\tIN\tNS\t{}", self.ttl, to_fqdn(ns)));
+            }
+        }
+
+        lines.extend(records.iter().map(|r| r.to_line()));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn reload(&self, command: &str) -> Result<()> {
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            command = command,
+            "Running reload command"
+        );
+        run_shell(command).boxed_local().context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: "Reload command failed",
+        })
+    }
+}
+
+impl common::Frontend for Bind {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.read_zone()?;
+
+        check_writable(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.path.display()),
+            })
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let (text, zone) = self.read_zone()?;
+
+        let mut diff = diff_records::<ZoneRecord>(zone.records.clone(), authority);
+        for record in diff.create.iter_mut() {
+            record.ttl = self.ttl;
+        }
+        let mut records = diff.apply(zone.records);
+        records.sort_by(|a, b| (&a.name, &a.kind).cmp(&(&b.name, &b.kind)));
+
+        // Comparing the rendered file also catches changes to the
+        // SOA and NS settings, or a file that was edited by hand.
+        let current_serial = zone.serial.unwrap_or_default();
+        if self.render(&records, current_serial) == text {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        for record in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+        }
+        for record in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+        }
+        for record in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+        }
+
+        let serial = next_serial(zone.serial);
+        write_atomic(&self.path, &self.render(&records, serial))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.path.display()),
+            })?;
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            path = self.path.display().to_string(),
+            serial = serial,
+            "Wrote zone file"
+        );
+
+        if let Some(command) = &self.reload_command {
+            self.reload(command)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for Bind {
+    fn from(value: super::Config) -> Self {
+        let apex = match value.fragment {
+            true => None,
+            false => {
+                let primary_ns = value
+                    .primary_ns
+                    .ok_or_else(|| config_error("primary_ns is required for a full zone".into()))
+                    .unwrap();
+                // Email addresses are converted to DNS form
+                let hostmaster = value
+                    .hostmaster
+                    .map(|h| h.replacen('@', ".", 1))
+                    .unwrap_or_else(|| format!("hostmaster.{}", value.domain));
+                // Unfortunately config-rs makes it difficult to mix
+                // strings and vec of strings, so we have to parse ourselves
+                let mut nameservers: Vec<String> = value
+                    .nameservers
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|ns| ns.trim().to_string())
+                    .filter(|ns| !ns.is_empty())
+                    .collect();
+                if nameservers.is_empty() {
+                    nameservers.push(primary_ns.clone());
+                }
+                Some(Apex {
+                    primary_ns,
+                    hostmaster,
+                    nameservers,
+                })
+            }
+        };
+
+        Self {
+            domain: value.domain,
+            path: value.path,
+            apex,
+            reload_command: value.reload_command,
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
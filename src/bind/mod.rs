@@ -0,0 +1,7 @@
+mod config;
+mod frontend;
+mod models;
+
+pub use config::*;
+pub use frontend::*;
+pub(crate) use models::ZoneRecord;
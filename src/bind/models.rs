@@ -0,0 +1,156 @@
+use crate::common::{
+    txt_from_zone_value, txt_to_zone_value, Manage, Match, Record, Update, RECORD_KIND_TXT,
+};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+/// Record kinds whose content is a domain name, which must be
+/// fully qualified to not be read relative to the zone origin.
+const NAME_KINDS: [&str; 4] = ["CNAME", "NS", "PTR", "DNAME"];
+
+#[derive(Clone)]
+pub(crate) struct ZoneRecord {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: u32,
+}
+
+/// The parts of a zone file written by DNSSync that are read back.
+#[derive(Default)]
+pub(super) struct ZoneFile {
+    pub serial: Option<u32>,
+    pub records: Vec<ZoneRecord>,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+fn is_name_kind(kind: &str) -> bool {
+    NAME_KINDS.iter().any(|k| k.eq_ignore_ascii_case(kind))
+}
+
+impl ZoneRecord {
+    /// Formats the record as a zone file line, with an absolute owner name.
+    pub(crate) fn to_line(&self) -> String {
+        let rdata = if self.kind.eq_ignore_ascii_case(RECORD_KIND_TXT) {
+            txt_to_zone_value(&self.content)
+        } else if is_name_kind(&self.kind) {
+            format!("{}.", self.content)
+        } else {
+            self.content.clone()
+        };
+        format!("{}.\t{}\tIN\t{}\t{}", self.name, self.ttl, self.kind, rdata)
+    }
+
+    /// Parses a line written by to_line.
+    pub(crate) fn from_line(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(5, '\t');
+        let name = normalize_name(parts.next()?);
+        let ttl = parts.next()?.parse().ok()?;
+        let _class = parts.next()?;
+        let kind = parts.next()?.to_uppercase();
+        let rdata = parts.next()?.trim();
+
+        let content = if kind == RECORD_KIND_TXT {
+            txt_from_zone_value(rdata)
+        } else if is_name_kind(&kind) {
+            normalize_name(rdata)
+        } else {
+            rdata.to_string()
+        };
+
+        Some(Self {
+            name,
+            kind,
+            content,
+            ttl,
+        })
+    }
+}
+
+impl ZoneFile {
+    pub(super) fn parse(text: &str) -> Self {
+        let mut zone = Self::default();
+        for line in text.lines() {
+            // Skip comments and directives
+            if line.trim().is_empty() || line.starts_with(';') || line.starts_with('$') {
+                continue;
+            }
+            let Some(record) = ZoneRecord::from_line(line) else {
+                tracing::warn!(
+                    frontend = super::FRONTEND_NAME,
+                    line = line,
+                    "Skipping unrecognized zone file line"
+                );
+                continue;
+            };
+            if record.kind == "SOA" {
+                // MNAME RNAME SERIAL REFRESH RETRY EXPIRE MINIMUM
+                zone.serial = record
+                    .content
+                    .split_whitespace()
+                    .nth(2)
+                    .and_then(|serial| serial.parse().ok());
+            } else {
+                zone.records.push(record);
+            }
+        }
+        zone
+    }
+}
+
+impl Manage for ZoneRecord {
+    fn is_managed(&self) -> bool {
+        // The file is only written by DNSSync
+        true
+    }
+}
+
+impl Match for ZoneRecord {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for ZoneRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = ZoneRecord::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for ZoneRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<ZoneRecord> for Record {
+    fn from(value: ZoneRecord) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for ZoneRecord {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = if is_name_kind(&kind) {
+            normalize_name(&value.content)
+        } else {
+            value.content
+        };
+        Self {
+            name: normalize_name(&value.name.to_string()),
+            kind,
+            content,
+            ttl: DEFAULT_TTL,
+        }
+    }
+}
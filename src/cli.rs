@@ -4,6 +4,7 @@ use std::env;
 use std::io::Write;
 use std::process::exit;
 
+use crate::common::{Backend, Frontend};
 use crate::Config;
 
 fn set_logger_level(b: &mut Builder) {
@@ -43,6 +44,39 @@ fn setup_logger() {
     };
 }
 
+/// Runs the live check of every component and prints a pass/fail table.
+/// Returns true if all checks passed.
+fn run_checks(backends: &[Box<dyn Backend>], frontends: &mut [Box<dyn Frontend>]) -> bool {
+    let mut rows = Vec::with_capacity(backends.len() + frontends.len());
+    for backend in backends {
+        rows.push((backend.get_name().to_string(), "backend", backend.check()));
+    }
+    for frontend in frontends.iter_mut() {
+        let name = format!("{} ({})", frontend.get_name(), frontend.get_domain());
+        rows.push((name, "frontend", frontend.check()));
+    }
+
+    let width = rows
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .chain(["COMPONENT".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:width$}  {:8}  {:6}  DETAIL",
+        "COMPONENT", "KIND", "RESULT"
+    );
+    for (name, kind, result) in rows.iter() {
+        match result {
+            Ok(_) => println!("{name:width$}  {kind:8}  PASS"),
+            Err(err) => println!("{name:width$}  {kind:8}  {:6}  {err}", "FAIL"),
+        }
+    }
+
+    rows.iter().all(|(_, _, result)| result.is_ok())
+}
+
 pub(crate) fn main() {
     let cli = Command::new("DNSSync")
         .about(format!(
@@ -56,7 +90,7 @@ pub(crate) fn main() {
                 .action(ArgAction::SetTrue)
                 .short('t')
                 .long("test")
-                .help("Check the configuration"),
+                .help("Check the configuration, credentials and connectivity"),
         )
         .arg(
             Arg::new("dry-run")
@@ -64,6 +98,12 @@ pub(crate) fn main() {
                 .long("dry-run")
                 .help("Show changes without applying them"),
         )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .value_parser(|s: &str| humantime::parse_duration(s))
+                .help("Keep running, syncing at this interval (e.g. 1m)"),
+        )
         .arg(
             Arg::new("backends")
                 .action(ArgAction::Append)
@@ -104,14 +144,31 @@ pub(crate) fn main() {
     };
 
     if args.get_flag("check") {
-        let (backends, frontends) = config.into_impls();
+        let (backends, mut frontends) = config.into_impls();
         tracing::info!(
             backends = backends.len(),
             frontends = frontends.len(),
             "Configuration is valid."
         );
-        exit(0);
+        match run_checks(&backends, &mut frontends) {
+            true => exit(0),
+            false => exit(1),
+        }
     }
 
-    config.get_service().sync(args.get_flag("dry-run")).unwrap();
+    let mut service = config.get_service();
+    let dry_run = args.get_flag("dry-run");
+    let Some(interval) = args.get_one::<std::time::Duration>("interval") else {
+        service.sync(dry_run).unwrap();
+        return;
+    };
+
+    // Failures are retried on the next sync rather than ending the process,
+    // as frontends like the built-in DNS server live as long as it does
+    loop {
+        if let Err(err) = service.sync(dry_run) {
+            tracing::error!(error = err.to_string(), "Sync failed");
+        }
+        std::thread::sleep(*interval);
+    }
 }
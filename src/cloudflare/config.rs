@@ -3,4 +3,14 @@ pub struct Config {
     pub api_key: String,
     pub domain: String,
     pub instance_id: String,
+    /// Override the API base URL, e.g. to use a proxy.
+    pub base_url: Option<url::Url>,
+    /// Track ownership using record tags instead of the record comment.
+    /// Existing comment-managed records are migrated on the next sync.
+    #[serde(default)]
+    pub use_tags: bool,
+    /// Appended to the comment of managed records. Supports the
+    /// {source} and {instance_id} placeholders. Records are
+    /// rewritten on the next sync when it changes.
+    pub comment_template: Option<String>,
 }
@@ -19,6 +19,19 @@ in
       description = "A unique identifier for this deployment of dnssync."
         + " Used to avoid collisions with other instances on the same domain";
     };
+    baseUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "Override the Cloudflare API base URL, e.g. to use a proxy";
+    };
+    commentTemplate = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "from {source}";
+      description = "Text appended to the comment of managed records."
+        + " Supports {source} and {instance_id} placeholders";
+    };
+    useTags = lib.mkEnableOption "storing record ownership in Cloudflare tags instead of comments";
   };
 
   config = lib.mkIf (cfg.enable) {
@@ -28,6 +41,11 @@ in
       "DNSSYNC_CLOUDFLARE_DOMAIN" = cfg.domain;
       "DNSSYNC_CLOUDFLARE_INSTANCE_ID" = cfg.instanceId;
       "DNSSYNC_CLOUDFLARE_API_KEY" = "@${cfg.keyFile}";
+      "DNSSYNC_CLOUDFLARE_USE_TAGS" = lib.boolToString cfg.useTags;
+    } // lib.optionalAttrs (cfg.baseUrl != null) {
+      "DNSSYNC_CLOUDFLARE_BASE_URL" = cfg.baseUrl;
+    } // lib.optionalAttrs (cfg.commentTemplate != null) {
+      "DNSSYNC_CLOUDFLARE_COMMENT_TEMPLATE" = cfg.commentTemplate;
     };
   };
 }
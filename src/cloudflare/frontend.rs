@@ -2,16 +2,20 @@ use serde::de::DeserializeOwned;
 use snafu::prelude::*;
 
 use crate::common::{
-    self, diff_records, key_file_or_string, FrontendSnafu, Record, RequestSnafu, ResponseSnafu,
-    Result,
+    self, diff_records, key_file_or_string, FrontendSnafu, Manage, Match, Record, RequestSnafu,
+    ResponseSnafu, Result, Update,
 };
 
-use super::models::{APIError, DNSRecord, DeleteResponse, PaginatedResponse, WriteResponse, Zone};
+use super::models::{
+    APIError, DNSRecord, DeleteResponse, PaginatedResponse, TokenStatus, WriteResponse, Zone,
+};
 
-const API_BASE_URL: &str = "https://api.cloudflare.com/client/v4";
+pub const API_BASE_URL: &str = "https://api.cloudflare.com/client/v4";
 
 pub const FRONTEND_NAME: &str = "Cloudflare";
 
+const PERMISSION_DNS_EDIT: &str = "#dns_records:edit";
+
 enum WriteMethod {
     Create,
     Delete,
@@ -51,8 +55,11 @@ fn process_errors(success: bool, errors: Vec<APIError>) -> Result<()> {
 
 pub struct Cloudflare {
     api_key: String,
+    base_url: String,
     domain: String,
     instance_id: String,
+    use_tags: bool,
+    comment_template: Option<String>,
     zone_id: Option<String>,
 }
 
@@ -103,6 +110,32 @@ impl Cloudflare {
         }
     }
 
+    fn api_get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        tracing::debug!(
+            url = url,
+            method = "GET",
+            frontend = "cloudflare",
+            "Sending request"
+        );
+        let resp: WriteResponse<T> = self
+            .with_headers(ureq::get(url))
+            .call()
+            .context(RequestSnafu {
+                url,
+                method: "Read",
+            })?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })?;
+
+        process_errors(resp.success, resp.errors)?;
+
+        Ok(resp.result)
+    }
+
     fn api_write<T: DeserializeOwned>(
         &self,
         url: &str,
@@ -135,7 +168,7 @@ impl Cloudflare {
             return Ok(zone_id.clone());
         }
 
-        let url = format!("{API_BASE_URL}/zones");
+        let url = format!("{}/zones", self.base_url);
 
         let response: Vec<Zone> = self.api_get_paginated(&url, 50)?;
 
@@ -153,21 +186,107 @@ impl Cloudflare {
     }
 
     pub(super) fn read_records(&self, zone_id: String) -> Result<Vec<DNSRecord>> {
-        let url = format!("{API_BASE_URL}/zones/{zone_id}/dns_records");
+        let url = format!("{}/zones/{zone_id}/dns_records", self.base_url);
         let records: Vec<DNSRecord> = self.api_get_paginated(&url, 1000)?;
         Ok(records.into_iter().collect())
     }
+
+    /// Finds records owned by this instance which are still in the authority
+    /// but store their ownership in the wrong place for the configured mode,
+    /// or whose comment differs from what would be written now. These are returned
+    /// updated from the authority, ready to be rewritten.
+    fn find_rewrites(&self, current: &[DNSRecord], authority: &[Record]) -> Vec<DNSRecord> {
+        current
+            .iter()
+            .filter(|record| {
+                record.is_managed() && record.get_instance_id() == Some(&self.instance_id)
+            })
+            .filter_map(|record| {
+                authority
+                    .iter()
+                    .find(|&r| record.matches(&r.to_owned().into()))
+                    .map(|r| record.to_owned().update(r.to_owned()))
+            })
+            .filter(|record| {
+                let mut expected = record.clone();
+                self.mark_managed(&mut expected);
+                record.needs_migration(self.use_tags) || record.comment != expected.comment
+            })
+            .collect()
+    }
+
+    /// Marks the record as managed by this instance,
+    /// with the comment template filled in.
+    fn mark_managed(&self, record: &mut DNSRecord) {
+        let note = self.comment_template.as_ref().map(|template| {
+            template
+                .replace("{source}", record.source.as_deref().unwrap_or_default())
+                .replace("{instance_id}", &self.instance_id)
+        });
+        record.set_instance_id(&self.instance_id, self.use_tags, note.as_deref());
+    }
 }
 
 impl common::Frontend for Cloudflare {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
     fn get_domain(&self) -> &str {
         return &self.domain;
     }
 
+    fn check(&mut self) -> Result<()> {
+        let token: TokenStatus = self.api_get(&format!("{}/user/tokens/verify", self.base_url))?;
+        if token.status != "active" {
+            return ResponseSnafu {
+                message: format!("API token is {}", token.status),
+            }
+            .fail();
+        }
+
+        // Zone read permission is required to find the zone at all
+        let zone_id = self.get_zone_id()?;
+        let zone: Zone = self.api_get(&format!("{}/zones/{zone_id}", self.base_url))?;
+        self.read_records(zone_id)?;
+
+        // Older zones and some token types don't report permissions
+        if zone.permissions.is_empty() {
+            tracing::warn!(
+                frontend = FRONTEND_NAME,
+                zone = zone.name,
+                "Zone permissions not reported, unable to check for DNS edit permission"
+            );
+        } else if !zone.permissions.iter().any(|p| p == PERMISSION_DNS_EDIT) {
+            return ResponseSnafu {
+                message: format!("API token lacks DNS edit permission on zone {}", zone.name),
+            }
+            .fail();
+        }
+
+        Ok(())
+    }
+
     fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
         let zone_id = self.get_zone_id()?;
         let current = self.read_records(zone_id.clone())?;
-        let diff = diff_records::<DNSRecord>(current, authority);
+        let rewrites = self.find_rewrites(&current, &authority);
+        let mut diff = diff_records::<DNSRecord>(current, authority);
+
+        // Rewrite ownership and comments of otherwise unchanged records
+        for record in rewrites {
+            if !diff.update.iter().any(|r| r.id == record.id) {
+                tracing::info!(
+                    frontend = FRONTEND_NAME,
+                    name = record.name,
+                    kind = record.kind,
+                    record_id = record.id,
+                    use_tags = self.use_tags,
+                    "Rewriting record ownership"
+                );
+                diff.update.push(record);
+            }
+        }
 
         // Evaluate deletions more precisely based on instance ID
         let diff_len = diff.len() - diff.delete.len();
@@ -216,7 +335,10 @@ impl common::Frontend for Cloudflare {
             );
 
             self.api_write::<DeleteResponse>(
-                &format!("{API_BASE_URL}/zones/{zone_id}/dns_records/{}", record.id),
+                &format!(
+                    "{}/zones/{zone_id}/dns_records/{}",
+                    self.base_url, record.id
+                ),
                 WriteMethod::Delete,
                 record.clone(),
             )?;
@@ -250,10 +372,13 @@ impl common::Frontend for Cloudflare {
                     "Record is not managed by this instance but we will update anyway"
                 )
             }
-            record.set_instance_id(&self.instance_id);
+            self.mark_managed(&mut record);
 
             let resp: DNSRecord = self.api_write(
-                &format!("{API_BASE_URL}/zones/{zone_id}/dns_records/{}", record.id),
+                &format!(
+                    "{}/zones/{zone_id}/dns_records/{}",
+                    self.base_url, record.id
+                ),
                 WriteMethod::Update,
                 record,
             )?;
@@ -275,10 +400,10 @@ impl common::Frontend for Cloudflare {
                 content = record.content,
                 "Creating record"
             );
-            record.set_instance_id(&self.instance_id);
+            self.mark_managed(&mut record);
 
             let resp: DNSRecord = self.api_write(
-                &format!("{API_BASE_URL}/zones/{zone_id}/dns_records"),
+                &format!("{}/zones/{zone_id}/dns_records", self.base_url),
                 WriteMethod::Create,
                 record,
             )?;
@@ -300,10 +425,18 @@ impl From<super::Config> for Cloudflare {
     fn from(value: super::Config) -> Self {
         let api_key = key_file_or_string(value.api_key, FRONTEND_NAME.into()).unwrap();
 
+        let base_url = value
+            .base_url
+            .map(|url| url.as_str().trim_end_matches('/').to_string())
+            .unwrap_or_else(|| API_BASE_URL.to_string());
+
         Self {
             api_key,
+            base_url,
             domain: value.domain,
             instance_id: value.instance_id,
+            use_tags: value.use_tags,
+            comment_template: value.comment_template,
             zone_id: None,
         }
     }
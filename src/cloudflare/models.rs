@@ -1,7 +1,11 @@
-use crate::common::{Manage, Match, Record, Update};
+use crate::common::{
+    format_watermark, merge_watermark, parse_watermark, Manage, Match, Record, Update,
+    COMMENT_WATERMARK,
+};
 
-pub(super) const COMMENT_WATERMARK: &str = "Managed by DNSSync";
-pub(super) const COMMENT_INSTANCE_PREFIX: &str = "instance:";
+pub(super) const TAG_WATERMARK: &str = "dnssync:managed";
+pub(super) const TAG_INSTANCE_PREFIX: &str = "dnssync:instance=";
+pub(super) const TAG_PREFIX: &str = "dnssync:";
 
 #[derive(serde::Deserialize)]
 pub(super) struct APIError {
@@ -35,6 +39,13 @@ pub(super) struct ResultInfo {
 pub(super) struct Zone {
     pub name: String,
     pub id: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct TokenStatus {
+    pub status: String,
 }
 
 #[derive(serde::Deserialize)]
@@ -47,34 +58,70 @@ pub(super) struct DNSRecord {
     pub name: String,
     pub content: String,
     pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     pub ttl: usize,
     // id is used to construct the URL, not part of the body.
     #[serde(skip_serializing)]
     pub id: String,
+    // The backend which supplied the record, if it came from the authority.
+    #[serde(skip)]
+    pub source: Option<String>,
 }
 
 impl DNSRecord {
     pub(super) fn get_instance_id(&self) -> Option<&str> {
-        self.comment.as_ref().and_then(|comment| {
-            comment.find(COMMENT_INSTANCE_PREFIX).and_then(|pos| {
-                let (_, instance_id) = comment.split_at(pos + COMMENT_INSTANCE_PREFIX.len());
-                Some(instance_id.trim())
-            })
-        })
+        self.tags
+            .iter()
+            .find_map(|tag| tag.strip_prefix(TAG_INSTANCE_PREFIX))
+            .or_else(|| self.get_comment_instance_id())
+    }
+
+    fn get_comment_instance_id(&self) -> Option<&str> {
+        self.comment.as_deref().and_then(parse_watermark)
+    }
+
+    /// Marks the record as managed by instance_id. note is written after
+    /// the watermark, which begins the managed part of the comment.
+    /// Any text before the watermark is kept as is.
+    pub(super) fn set_instance_id(
+        &mut self,
+        instance_id: &str,
+        use_tags: bool,
+        note: Option<&str>,
+    ) {
+        self.tags.retain(|tag| !tag.starts_with(TAG_PREFIX));
+
+        let note = note.filter(|note| !note.is_empty());
+        let managed = match use_tags {
+            true => {
+                self.tags.push(TAG_WATERMARK.to_string());
+                self.tags
+                    .push(format!("{TAG_INSTANCE_PREFIX}{instance_id}"));
+                note.map(|note| format_watermark(None, Some(note)))
+            }
+            false => Some(format_watermark(Some(instance_id), note)),
+        };
+        self.comment = merge_watermark(self.comment.as_deref(), managed.as_deref());
     }
 
-    pub(super) fn set_instance_id(&mut self, instance_id: &str) {
-        self.comment = Some(format!(
-            "{COMMENT_WATERMARK} {COMMENT_INSTANCE_PREFIX}{instance_id}"
-        ));
+    /// Returns whether the tags are wrong for the configured mode, i.e. the
+    /// record needs rewriting to migrate it. A leftover instance ID in the
+    /// comment can't be told apart from a note which looks like one, so
+    /// comments are instead compared with what would be written.
+    pub(super) fn needs_migration(&self, use_tags: bool) -> bool {
+        let has_tag = self.tags.iter().any(|tag| tag == TAG_WATERMARK);
+        has_tag != use_tags
     }
 }
 
 impl Manage for DNSRecord {
     fn is_managed(&self) -> bool {
-        self.comment
-            .as_ref()
-            .is_some_and(|c| c.contains(COMMENT_WATERMARK))
+        self.tags.iter().any(|tag| tag == TAG_WATERMARK)
+            || self
+                .comment
+                .as_ref()
+                .is_some_and(|c| c.contains(COMMENT_WATERMARK))
     }
 }
 
@@ -87,6 +134,7 @@ impl Match for DNSRecord {
 impl Update for DNSRecord {
     fn update(mut self, authority: Record) -> Self {
         self.content = authority.content;
+        self.source = Some(authority.source);
         self
     }
 }
@@ -117,8 +165,10 @@ impl From<Record> for DNSRecord {
             name: value.name.to_string(),
             content: value.content,
             comment: Some(COMMENT_WATERMARK.to_string()),
+            tags: Vec::new(),
             ttl: 1,
             id: String::new(),
+            source: Some(value.source),
         }
     }
 }
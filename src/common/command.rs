@@ -0,0 +1,16 @@
+/// Runs the command with `sh -c`, failing with its stderr if it exits unsuccessfully.
+pub(crate) fn run_shell(command: &str) -> std::io::Result<()> {
+    let output = std::process::Command::new("sh")
+        .args(["-c", command])
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(std::io::Error::other(format!(
+            "{command} exited with {}: {stderr}",
+            output.status
+        )));
+    }
+
+    Ok(())
+}
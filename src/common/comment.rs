@@ -0,0 +1,43 @@
+pub(crate) const COMMENT_WATERMARK: &str = "Managed by DNSSync";
+pub(crate) const COMMENT_INSTANCE_PREFIX: &str = "instance:";
+
+/// Formats the managed part of a comment, which begins with the watermark.
+/// The instance ID goes last, as everything after its prefix is the ID.
+pub(crate) fn format_watermark(instance_id: Option<&str>, note: Option<&str>) -> String {
+    let mut managed = vec![COMMENT_WATERMARK.to_string()];
+    if let Some(note) = note.filter(|note| !note.is_empty()) {
+        managed.push(note.to_string());
+    }
+    if let Some(instance_id) = instance_id {
+        managed.push(format!("{COMMENT_INSTANCE_PREFIX}{instance_id}"));
+    }
+    managed.join(" ")
+}
+
+/// Finds the instance ID in a comment, which is the rest of the comment
+/// after the last instance prefix in the managed part. Notes can't be
+/// mistaken for the ID, as they are written before it.
+pub(crate) fn parse_watermark(comment: &str) -> Option<&str> {
+    let (_, managed) = comment.split_once(COMMENT_WATERMARK)?;
+    managed
+        .rfind(COMMENT_INSTANCE_PREFIX)
+        .map(|pos| managed[pos + COMMENT_INSTANCE_PREFIX.len()..].trim())
+}
+
+/// Replaces the managed part of a comment, keeping any text before the
+/// watermark as is. Returns None if that leaves the comment empty.
+pub(crate) fn merge_watermark(comment: Option<&str>, managed: Option<&str>) -> Option<String> {
+    let notes = comment
+        .map(|comment| match comment.find(COMMENT_WATERMARK) {
+            Some(pos) => comment.split_at(pos).0.trim(),
+            None => comment.trim(),
+        })
+        .unwrap_or_default();
+
+    match (notes.is_empty(), managed) {
+        (true, None) => None,
+        (true, Some(managed)) => Some(managed.to_string()),
+        (false, None) => Some(notes.to_string()),
+        (false, Some(managed)) => Some(format!("{notes} {managed}")),
+    }
+}
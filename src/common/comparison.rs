@@ -12,6 +12,22 @@ impl<R> DiffResult<R> {
     }
 }
 
+impl<R: Clone + Match> DiffResult<R> {
+    /// Applies the changes to the current records, for frontends
+    /// which write out their entire state at once.
+    pub fn apply(&self, current: Vec<R>) -> Vec<R> {
+        current
+            .into_iter()
+            .filter(|r| {
+                !self.delete.iter().any(|d| d.matches(r))
+                    && !self.update.iter().any(|u| u.matches(r))
+            })
+            .chain(self.update.iter().cloned())
+            .chain(self.create.iter().cloned())
+            .collect()
+    }
+}
+
 pub(crate) fn diff_records<R: Clone + Manage + Match + Update + PartialEq + From<Record>>(
     current: Vec<R>,
     authority: Vec<Record>,
@@ -59,3 +75,30 @@ pub(crate) fn diff_records<R: Clone + Manage + Match + Update + PartialEq + From
         delete,
     }
 }
+
+/// Merges records of the same name and kind into one record, with the
+/// contents separated by newlines, for frontends which write whole RRsets.
+/// The source of the first record is kept.
+pub(crate) fn group_rrsets(records: Vec<Record>) -> Vec<Record> {
+    let mut grouped: Vec<Record> = Vec::with_capacity(records.len());
+    for record in records {
+        match grouped.iter_mut().find(|r| r.matches(&record)) {
+            Some(existing) => {
+                if !existing.content.split('\n').any(|c| c == record.content) {
+                    existing.content.push('\n');
+                    existing.content.push_str(&record.content);
+                }
+            }
+            None => grouped.push(record),
+        }
+    }
+    grouped
+}
+
+/// Sorts the values of an RRset and joins them with newlines,
+/// so the same set always has the same content.
+pub(crate) fn join_values(mut values: Vec<String>) -> String {
+    values.sort();
+    values.dedup();
+    values.join("\n")
+}
@@ -1,4 +1,4 @@
-use snafu::prelude::*;
+use snafu::{prelude::*, IntoError};
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
@@ -30,3 +30,39 @@ pub enum Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+/// Converts a failed request into an error, including the detail
+/// from the JSON response body if there is one.
+pub(crate) fn request_error<E: serde::de::DeserializeOwned>(
+    err: ureq::Error,
+    url: &str,
+    method: &str,
+    detail: impl FnOnce(E) -> Option<String>,
+) -> Error {
+    request_error_text(err, url, method, |body| {
+        serde_json::from_str(&body).ok().and_then(detail)
+    })
+}
+
+/// Converts a failed request into an error, including
+/// the detail found in the response body if there is one.
+pub(crate) fn request_error_text(
+    err: ureq::Error,
+    url: &str,
+    method: &str,
+    detail: impl FnOnce(String) -> Option<String>,
+) -> Error {
+    match err {
+        ureq::Error::Status(code, resp) => {
+            let detail = detail(resp.into_string().unwrap_or_default());
+            let message = match detail.as_deref().map(str::trim) {
+                Some(detail) if !detail.is_empty() => {
+                    format!("{method} {url} failed with status {code}: {detail}")
+                }
+                _ => format!("{method} {url} failed with status {code}"),
+            };
+            ResponseSnafu { message }.build()
+        }
+        err => RequestSnafu { url, method }.into_error(err),
+    }
+}
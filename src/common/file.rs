@@ -0,0 +1,40 @@
+use std::io::Write;
+use std::path::Path;
+
+/// Reads the file, treating a missing file as empty.
+pub(crate) fn read_to_string_or_empty(path: &Path) -> std::io::Result<String> {
+    match std::fs::read_to_string(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+/// Replaces the file's content by writing a temporary file beside
+/// it and renaming it into place, keeping the original permissions.
+pub(crate) fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{file_name}.dnssync"));
+
+    let mut file = std::fs::File::create(&temp_path)?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&temp_path, path)
+}
+
+/// Checks that write_atomic can replace the file, by creating
+/// and removing a file in the same directory.
+pub(crate) fn check_writable(path: &Path) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let probe = dir.join(format!(".dnssync-check-{}", uuid::Uuid::new_v4()));
+    std::fs::File::create(&probe)?;
+    std::fs::remove_file(&probe)
+}
@@ -1,9 +1,23 @@
+mod command;
+mod comment;
 mod comparison;
 mod config;
 mod error;
+mod file;
 mod models;
+mod oauth;
+mod rdata;
+mod registry;
+mod state;
 
+pub(crate) use command::*;
+pub(crate) use comment::*;
 pub(crate) use comparison::*;
 pub(crate) use config::*;
 pub use error::*;
+pub(crate) use file::*;
 pub use models::*;
+pub(crate) use oauth::*;
+pub(crate) use rdata::*;
+pub use registry::*;
+pub(crate) use state::*;
@@ -1,5 +1,6 @@
 pub const RECORD_KIND_A: &str = "A";
 pub const RECORD_KIND_AAAA: &str = "AAAA";
+pub const RECORD_KIND_TXT: &str = "TXT";
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Record {
@@ -22,11 +23,18 @@ impl Match for Record {
 }
 
 pub trait Frontend {
+    fn get_name(&self) -> &str;
     fn get_domain(&self) -> &str;
+    /// Check that the frontend is reachable and has the access
+    /// it needs to manage records, without changing anything.
+    fn check(&mut self) -> super::Result<()>;
     fn set_records(&mut self, records: Vec<Record>, dry_run: bool) -> super::Result<()>;
 }
 
 pub trait Backend {
+    fn get_name(&self) -> &str;
+    /// Check that the backend is reachable and can be read from.
+    fn check(&self) -> super::Result<()>;
     fn read_records(&self) -> super::Result<Vec<Record>>;
 }
 
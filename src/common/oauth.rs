@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+use snafu::prelude::*;
+
+use super::{request_error, FrontendSnafu, Result};
+
+/// Tokens are refreshed this long before they expire.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(serde::Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct TokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// An OAuth access token, for frontends which authenticate with one.
+pub(crate) struct AccessToken {
+    pub value: String,
+    expires: Instant,
+}
+
+impl AccessToken {
+    /// Requests a token by posting the form to the token endpoint at url.
+    pub fn request(frontend: &str, url: &str, form: &[(&str, &str)]) -> Result<Self> {
+        tracing::debug!(
+            url = url,
+            method = "POST",
+            frontend = frontend,
+            "Sending request"
+        );
+        let resp: TokenResponse = ureq::post(url)
+            .send_form(form)
+            // The token endpoint reports errors in the OAuth format
+            .map_err(|err| {
+                request_error(err, url, "POST", |e: TokenError| {
+                    Some(e.error_description.unwrap_or(e.error))
+                })
+            })?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend,
+                message: "Failed to deserialize response",
+            })?;
+        Ok(Self {
+            value: resp.access_token,
+            expires: Instant::now() + Duration::from_secs(resp.expires_in),
+        })
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        Instant::now() + EXPIRY_MARGIN < self.expires
+    }
+}
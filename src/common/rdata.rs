@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use hickory_proto::rr::{rdata::TXT, RData, RecordType};
+use hickory_proto::serialize::txt::RDataParser;
+
+use super::RECORD_KIND_TXT;
+
+/// Record kinds whose content is a domain name.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+/// Record kinds whose content ends with a domain name.
+const TARGET_KINDS: [&str; 2] = ["MX", "SRV"];
+
+/// The most bytes a TXT character-string may hold.
+const TXT_STRING_LEN: usize = 255;
+
+/// Splits TXT content into character-strings of at most 255 bytes,
+/// ending each at a character boundary so every string is valid UTF-8.
+/// Empty content is a single empty string.
+pub(crate) fn split_txt(content: &str) -> Vec<&str> {
+    let mut strings = Vec::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(TXT_STRING_LEN);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (string, tail) = rest.split_at(end);
+        strings.push(string);
+        rest = tail;
+    }
+    if strings.is_empty() {
+        strings.push("");
+    }
+    strings
+}
+
+/// Normalises names in content, which APIs may or may not return
+/// fully qualified, to how they appear in DNSSync records.
+pub(crate) fn normalise_content(kind: &str, content: &str) -> String {
+    if NAME_KINDS.contains(&kind) {
+        return content.trim_end_matches('.').to_lowercase();
+    }
+    if TARGET_KINDS.contains(&kind) {
+        if let Some((fields, target)) = content.rsplit_once(' ') {
+            return format!("{fields} {}", target.trim_end_matches('.').to_lowercase());
+        }
+    }
+    content.to_string()
+}
+
+/// Converts record data to DNSSync's record content format.
+pub(crate) fn rdata_to_content(rdata: &RData) -> String {
+    match rdata {
+        // Strings written by others may split a character, so decode them together
+        RData::TXT(txt) => String::from_utf8_lossy(&txt.txt_data().concat()).into_owned(),
+        RData::CNAME(_) | RData::NS(_) | RData::PTR(_) => {
+            rdata.to_string().trim_end_matches('.').to_string()
+        }
+        _ => rdata.to_string(),
+    }
+}
+
+/// Parses DNSSync record content into record data of the given kind.
+pub(crate) fn content_to_rdata(
+    kind: &str,
+    content: &str,
+) -> std::result::Result<RData, Box<dyn std::error::Error>> {
+    // TXT content is taken literally, split into character-strings
+    if kind.eq_ignore_ascii_case(RECORD_KIND_TXT) {
+        let strings = split_txt(content).into_iter().map(str::as_bytes).collect();
+        return Ok(RData::TXT(TXT::from_bytes(strings)));
+    }
+
+    let record_type = RecordType::from_str(&kind.to_uppercase())?;
+    Ok(RData::try_from_str(record_type, content)?)
+}
+
+/// Quotes TXT content as zone file character-strings, as many
+/// HTTP APIs expect TXT values to be written.
+pub(crate) fn txt_to_zone_value(content: &str) -> String {
+    split_txt(content)
+        .into_iter()
+        .map(|s| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Concatenates the quoted character-strings of a TXT value.
+/// Values without quotes are taken literally.
+pub(crate) fn txt_from_zone_value(value: &str) -> String {
+    if !value.trim_start().starts_with('"') {
+        return value.to_string();
+    }
+    let mut content = String::new();
+    let mut quoted = false;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' if quoted => content.extend(chars.next()),
+            c if quoted => content.push(c),
+            _ => {}
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_txt_at_character_boundaries() {
+        // Two bytes each, so 255 bytes falls within a character
+        let content = "é".repeat(200);
+
+        let strings = split_txt(&content);
+        assert_eq!(strings.len(), 2);
+        assert!(strings.iter().all(|s| s.len() <= TXT_STRING_LEN));
+        assert_eq!(strings[0].len(), 254);
+        assert_eq!(strings.concat(), content);
+
+        assert_eq!(txt_from_zone_value(&txt_to_zone_value(&content)), content);
+        let rdata = content_to_rdata(RECORD_KIND_TXT, &content).unwrap();
+        assert_eq!(rdata_to_content(&rdata), content);
+    }
+
+    #[test]
+    fn decodes_characters_split_across_txt_strings() {
+        let content = "é".repeat(200);
+        let strings: Vec<&[u8]> = content.as_bytes().chunks(TXT_STRING_LEN).collect();
+
+        let rdata = RData::TXT(TXT::from_bytes(strings));
+        assert_eq!(rdata_to_content(&rdata), content);
+    }
+}
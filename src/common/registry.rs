@@ -0,0 +1,449 @@
+use super::{diff_records, DiffResult, Manage, Match, Record, Update, RECORD_KIND_TXT};
+
+/// Default first label of ownership record names.
+pub const DEFAULT_REGISTRY_PREFIX: &str = "_dnssync";
+
+const OWNER_HERITAGE: &str = "heritage=dnssync";
+const OWNER_INSTANCE_KEY: &str = "dnssync/instance=";
+const OWNER_SOURCE_KEY: &str = "dnssync/source=";
+
+/// The owner of a record, as stored in its companion TXT record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Owner {
+    pub instance_id: String,
+    pub source: String,
+}
+
+impl Owner {
+    pub fn to_txt(&self) -> String {
+        format!(
+            "{OWNER_HERITAGE},{OWNER_INSTANCE_KEY}{},{OWNER_SOURCE_KEY}{}",
+            self.instance_id, self.source
+        )
+    }
+
+    /// Parses the content of an ownership TXT record. Surrounding quotes
+    /// are ignored. Returns None if the content was not written by DNSSync.
+    pub fn from_txt(content: &str) -> Option<Self> {
+        let content = content.trim().trim_matches('"');
+        let mut fields = content.split(',').map(str::trim);
+        if fields.next() != Some(OWNER_HERITAGE) {
+            return None;
+        }
+
+        let mut instance_id = None;
+        let mut source = String::new();
+        for field in fields {
+            if let Some(value) = field.strip_prefix(OWNER_INSTANCE_KEY) {
+                instance_id = Some(value.to_string());
+            } else if let Some(value) = field.strip_prefix(OWNER_SOURCE_KEY) {
+                source = value.to_string();
+            }
+        }
+
+        instance_id.map(|instance_id| Self {
+            instance_id,
+            source,
+        })
+    }
+}
+
+/// A frontend record paired with its owner, if any.
+/// Records without an owner are treated as unmanaged.
+#[derive(Clone)]
+pub struct Owned<R> {
+    pub record: R,
+    pub owner: Option<Owner>,
+}
+
+impl<R> Manage for Owned<R> {
+    fn is_managed(&self) -> bool {
+        self.owner.is_some()
+    }
+}
+
+impl<R: Match> Match for Owned<R> {
+    fn matches(&self, other: &Self) -> bool {
+        self.record.matches(&other.record)
+    }
+}
+
+impl<R: Update> Update for Owned<R> {
+    fn update(self, authority: Record) -> Self {
+        Self {
+            record: self.record.update(authority),
+            owner: self.owner,
+        }
+    }
+}
+
+impl<R: PartialEq> PartialEq for Owned<R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.record == other.record
+    }
+}
+
+impl<R: From<Record>> From<Record> for Owned<R> {
+    fn from(value: Record) -> Self {
+        Self {
+            record: value.into(),
+            owner: None,
+        }
+    }
+}
+
+pub(crate) struct RegistryDiff<R> {
+    /// Changes to the records themselves. Deletions are limited
+    /// to records owned by this instance.
+    pub records: DiffResult<Owned<R>>,
+    /// Ownership records to create, for new or re-owned records.
+    pub create_owners: Vec<R>,
+    /// Ownership records to delete, for deleted, re-owned or orphaned records.
+    pub delete_owners: Vec<R>,
+}
+
+impl<R> RegistryDiff<R> {
+    pub fn len(&self) -> usize {
+        self.records.len() + self.create_owners.len() + self.delete_owners.len()
+    }
+}
+
+/// Tracks record ownership in companion TXT records, for frontends
+/// which have no comment or metadata field to store it in.
+///
+/// The ownership record for `host.example.com` of kind A is a TXT record
+/// named `_dnssync.a.host.example.com` containing
+/// `heritage=dnssync,dnssync/instance=<id>,dnssync/source=<backend>`.
+pub struct TxtRegistry {
+    prefix: String,
+    instance_id: String,
+}
+
+impl TxtRegistry {
+    pub fn new(prefix: Option<String>, instance_id: String) -> Self {
+        Self {
+            prefix: prefix.unwrap_or_else(|| DEFAULT_REGISTRY_PREFIX.to_string()),
+            instance_id,
+        }
+    }
+
+    pub fn owner_name(&self, name: &str, kind: &str) -> String {
+        format!("{}.{}.{}", self.prefix, kind.to_lowercase(), name)
+    }
+
+    fn owner_record(&self, record: &Record) -> Record {
+        let owner = Owner {
+            instance_id: self.instance_id.clone(),
+            source: record.source.clone(),
+        };
+        Record {
+            name: url::Host::Domain(self.owner_name(&record.name.to_string(), &record.kind)),
+            kind: RECORD_KIND_TXT.to_string(),
+            content: owner.to_txt(),
+            source: record.source.clone(),
+        }
+    }
+
+    fn is_owner_name(&self, name: &str) -> bool {
+        name.split('.')
+            .next()
+            .is_some_and(|label| label.eq_ignore_ascii_case(&self.prefix))
+    }
+
+    /// Diffs the current frontend records against the authority,
+    /// resolving ownership from the TXT records found in current.
+    pub(crate) fn diff<R>(&self, current: Vec<R>, authority: Vec<Record>) -> RegistryDiff<R>
+    where
+        R: Clone + Match + Update + PartialEq + From<Record> + Into<Record>,
+    {
+        // Split ownership records from everything else
+        let mut owners: Vec<(String, Owner, R)> = Vec::new();
+        let mut records: Vec<R> = Vec::with_capacity(current.len());
+        for record in current {
+            let view: Record = record.clone().into();
+            let name = view.name.to_string();
+            match view.kind.eq_ignore_ascii_case(RECORD_KIND_TXT) && self.is_owner_name(&name) {
+                true => match Owner::from_txt(&view.content) {
+                    Some(owner) => owners.push((name.to_lowercase(), owner, record)),
+                    None => records.push(record),
+                },
+                false => records.push(record),
+            }
+        }
+
+        // Pair each record with its owner
+        let mut claimed = vec![false; owners.len()];
+        let records: Vec<Owned<R>> = records
+            .into_iter()
+            .map(|record| {
+                let view: Record = record.clone().into();
+                let owner_name = self
+                    .owner_name(&view.name.to_string(), &view.kind)
+                    .to_lowercase();
+                let owner = owners
+                    .iter()
+                    .position(|(name, _, _)| name == &owner_name)
+                    .map(|i| {
+                        claimed[i] = true;
+                        owners[i].1.clone()
+                    });
+                Owned { record, owner }
+            })
+            .collect();
+
+        // Never let the authority manage ownership records directly
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|record| {
+                let is_owner = self.is_owner_name(&record.name.to_string());
+                if is_owner {
+                    tracing::warn!(
+                        name = record.name.to_string(),
+                        kind = record.kind,
+                        backend = record.source,
+                        "Skipping record which conflicts with the ownership registry"
+                    );
+                }
+                !is_owner
+            })
+            .collect();
+
+        let mut diff = diff_records::<Owned<R>>(records, authority.clone());
+        let mut create_owners: Vec<R> = Vec::with_capacity(diff.create.len());
+        let mut delete_owners: Vec<R> = Vec::new();
+
+        let find_owner_record = |record: &R| -> Option<R> {
+            let view: Record = record.clone().into();
+            let owner_name = self
+                .owner_name(&view.name.to_string(), &view.kind)
+                .to_lowercase();
+            owners
+                .iter()
+                .find(|(name, _, _)| name == &owner_name)
+                .map(|(_, _, r)| r.clone())
+        };
+
+        let find_source = |record: &R| -> Option<&Record> {
+            authority
+                .iter()
+                .find(|&r| record.matches(&r.to_owned().into()))
+        };
+
+        for record in diff.create.iter() {
+            if let Some(source) = find_source(&record.record) {
+                create_owners.push(self.owner_record(source).into());
+            }
+        }
+
+        // Re-own updated records if the owner or source has changed
+        for record in diff.update.iter() {
+            let Some(source) = find_source(&record.record) else {
+                continue;
+            };
+            let wanted = Owner {
+                instance_id: self.instance_id.clone(),
+                source: source.source.clone(),
+            };
+            if record.owner.as_ref() != Some(&wanted) {
+                if record
+                    .owner
+                    .as_ref()
+                    .is_some_and(|o| o.instance_id != self.instance_id)
+                {
+                    tracing::warn!(
+                        name = source.name.to_string(),
+                        kind = source.kind,
+                        "Record is not managed by this instance but we will update anyway"
+                    );
+                }
+                delete_owners.extend(find_owner_record(&record.record));
+                create_owners.push(self.owner_record(source).into());
+            }
+        }
+
+        // Only delete records which this instance owns
+        diff.delete.retain(|record| {
+            record
+                .owner
+                .as_ref()
+                .is_some_and(|o| o.instance_id == self.instance_id)
+        });
+        for record in diff.delete.iter() {
+            delete_owners.extend(find_owner_record(&record.record));
+        }
+
+        // Clean up ownership records left behind by this instance
+        for (i, (_, owner, record)) in owners.iter().enumerate() {
+            if !claimed[i] && owner.instance_id == self.instance_id {
+                delete_owners.push(record.clone());
+            }
+        }
+
+        RegistryDiff {
+            records: diff,
+            create_owners,
+            delete_owners,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTANCE_ID: &str = "test";
+
+    /// A minimal frontend record, managed only through the registry.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Entry {
+        name: String,
+        kind: String,
+        content: String,
+    }
+
+    impl Match for Entry {
+        fn matches(&self, other: &Self) -> bool {
+            self.name == other.name && self.kind == other.kind
+        }
+    }
+
+    impl Update for Entry {
+        fn update(mut self, authority: Record) -> Self {
+            self.content = authority.content;
+            self
+        }
+    }
+
+    impl From<Record> for Entry {
+        fn from(value: Record) -> Self {
+            Self {
+                name: value.name.to_string(),
+                kind: value.kind,
+                content: value.content,
+            }
+        }
+    }
+
+    impl From<Entry> for Record {
+        fn from(value: Entry) -> Self {
+            Record {
+                name: url::Host::Domain(value.name),
+                kind: value.kind,
+                content: value.content,
+                source: String::new(),
+            }
+        }
+    }
+
+    fn entry(name: &str, kind: &str, content: &str) -> Entry {
+        Entry {
+            name: name.into(),
+            kind: kind.into(),
+            content: content.into(),
+        }
+    }
+
+    fn owner_entry(name: &str, instance_id: &str) -> Entry {
+        let owner = Owner {
+            instance_id: instance_id.into(),
+            source: "docker".into(),
+        };
+        entry(&format!("_dnssync.a.{name}"), "TXT", &owner.to_txt())
+    }
+
+    fn record(name: &str, content: &str) -> Record {
+        Record {
+            name: url::Host::Domain(name.into()),
+            kind: "A".into(),
+            content: content.into(),
+            source: "docker".into(),
+        }
+    }
+
+    fn registry() -> TxtRegistry {
+        TxtRegistry::new(None, INSTANCE_ID.into())
+    }
+
+    #[test]
+    fn creates_owners_for_new_records() {
+        let diff =
+            registry().diff::<Entry>(Vec::new(), vec![record("host.example.com", "10.0.0.1")]);
+
+        assert_eq!(diff.records.create.len(), 1);
+        assert_eq!(
+            diff.create_owners,
+            vec![owner_entry("host.example.com", INSTANCE_ID)]
+        );
+        assert!(diff.delete_owners.is_empty());
+    }
+
+    #[test]
+    fn deletes_owners_with_their_records() {
+        let current = vec![
+            entry("host.example.com", "A", "10.0.0.1"),
+            owner_entry("host.example.com", INSTANCE_ID),
+            // Left behind without its record
+            owner_entry("gone.example.com", INSTANCE_ID),
+        ];
+
+        let diff = registry().diff(current, Vec::new());
+
+        assert_eq!(diff.records.delete.len(), 1);
+        assert_eq!(diff.records.delete[0].record.name, "host.example.com");
+        assert_eq!(
+            diff.delete_owners,
+            vec![
+                owner_entry("host.example.com", INSTANCE_ID),
+                owner_entry("gone.example.com", INSTANCE_ID),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_unowned_records() {
+        let current = vec![
+            entry("host.example.com", "A", "10.0.0.1"),
+            entry("other.example.com", "A", "10.0.0.1"),
+        ];
+
+        let diff = registry().diff(current, vec![record("host.example.com", "10.0.0.2")]);
+
+        assert_eq!(diff.len(), 0);
+    }
+
+    #[test]
+    fn ignores_owners_of_other_instances() {
+        let current = vec![
+            entry("host.example.com", "A", "10.0.0.1"),
+            owner_entry("host.example.com", "other"),
+            owner_entry("gone.example.com", "other"),
+        ];
+
+        let diff = registry().diff(current, Vec::new());
+
+        assert_eq!(diff.len(), 0);
+    }
+
+    #[test]
+    fn parses_owner_txt() {
+        let owner = Owner::from_txt(
+            "\"heritage=dnssync, dnssync/instance=test,dnssync/source=docker,future=1\"",
+        );
+        assert_eq!(
+            owner,
+            Some(Owner {
+                instance_id: "test".into(),
+                source: "docker".into(),
+            })
+        );
+
+        assert_eq!(
+            Owner::from_txt("heritage=external-dns,external-dns/owner=test"),
+            None
+        );
+        assert_eq!(
+            Owner::from_txt("heritage=dnssync,dnssync/source=docker"),
+            None
+        );
+    }
+}
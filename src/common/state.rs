@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use super::{read_to_string_or_empty, write_atomic};
+
+/// A record this instance created, identified by everything the API knows about it.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub(crate) struct OwnedEntry {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+}
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+struct State {
+    #[serde(default)]
+    records: Vec<OwnedEntry>,
+}
+
+/// Tracks record ownership in a local JSON file, for frontends whose
+/// API has nowhere to store it, not even companion TXT records.
+///
+/// Frontends should save the records they are about to create before
+/// creating them, so a run which fails partway doesn't leave records
+/// behind that no instance will manage.
+pub(crate) struct OwnershipFile {
+    path: PathBuf,
+}
+
+impl OwnershipFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the owned records, treating a missing file as owning nothing.
+    pub fn load(&self) -> std::io::Result<Vec<OwnedEntry>> {
+        let content = read_to_string_or_empty(&self.path)?;
+        if content.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let state: State = serde_json::from_str(&content)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        Ok(state.records)
+    }
+
+    pub fn save(&self, records: Vec<OwnedEntry>) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(&State { records })
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        write_atomic(&self.path, &content)
+    }
+}
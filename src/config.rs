@@ -1,5 +1,9 @@
 use crate::common::{Backend, Frontend};
-use crate::{cloudflare, headscale, jsonfile, machinectl};
+use crate::{
+    adguard, azure, bind, cloudflare, desec, digitalocean, dnsmasq, dnsserver, etcd, exec, export,
+    gandi, gcloud, headscale, hetzner, hosts, jsonfile, machinectl, magicdns, mikrotik, pihole,
+    powerdns, rfc2136, route53, technitium, unbound,
+};
 
 #[cfg(feature = "cli")]
 const ENV_PREFIX: &str = "DNSSYNC";
@@ -9,7 +13,29 @@ pub struct Config {
     backends: Vec<String>,
     frontends: Vec<String>,
 
+    pub adguard: Option<adguard::Config>,
+    pub azure: Option<azure::Config>,
+    pub bind: Option<bind::Config>,
     pub cloudflare: Option<cloudflare::Config>,
+    pub desec: Option<desec::Config>,
+    pub digitalocean: Option<digitalocean::Config>,
+    pub dnsmasq: Option<dnsmasq::Config>,
+    pub dnsserver: Option<dnsserver::Config>,
+    pub etcd: Option<etcd::Config>,
+    pub exec: Option<exec::Config>,
+    pub export: Option<export::Config>,
+    pub gandi: Option<gandi::Config>,
+    pub gcloud: Option<gcloud::Config>,
+    pub hetzner: Option<hetzner::Config>,
+    pub hosts: Option<hosts::Config>,
+    pub magicdns: Option<magicdns::Config>,
+    pub mikrotik: Option<mikrotik::Config>,
+    pub pihole: Option<pihole::Config>,
+    pub powerdns: Option<powerdns::Config>,
+    pub rfc2136: Option<rfc2136::Config>,
+    pub route53: Option<route53::Config>,
+    pub technitium: Option<technitium::Config>,
+    pub unbound: Option<unbound::Config>,
 
     pub headscale: Option<headscale::Config>,
     pub jsonfile: Option<jsonfile::Config>,
@@ -40,10 +66,98 @@ impl Config {
 
         let mut frontends: Vec<Box<dyn Frontend>> = Vec::new();
 
+        if let Some(cfg) = self.adguard {
+            frontends.push(Box::new(adguard::AdGuard::from(cfg)));
+            tracing::info!(frontend = adguard::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.azure {
+            frontends.push(Box::new(azure::AzureDNS::from(cfg)));
+            tracing::info!(frontend = azure::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.bind {
+            frontends.push(Box::new(bind::Bind::from(cfg)));
+            tracing::info!(frontend = bind::FRONTEND_NAME, "Loaded frontend");
+        }
         if let Some(cfg) = self.cloudflare {
             frontends.push(Box::new(cloudflare::Cloudflare::from(cfg)));
             tracing::info!(frontend = cloudflare::FRONTEND_NAME, "Loaded frontend");
         }
+        if let Some(cfg) = self.desec {
+            frontends.push(Box::new(desec::DeSEC::from(cfg)));
+            tracing::info!(frontend = desec::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.digitalocean {
+            frontends.push(Box::new(digitalocean::DigitalOcean::from(cfg)));
+            tracing::info!(frontend = digitalocean::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.dnsmasq {
+            frontends.push(Box::new(dnsmasq::Dnsmasq::from(cfg)));
+            tracing::info!(frontend = dnsmasq::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.dnsserver {
+            frontends.push(Box::new(dnsserver::DnsServer::from(cfg)));
+            tracing::info!(frontend = dnsserver::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.etcd {
+            frontends.push(Box::new(etcd::Etcd::from(cfg)));
+            tracing::info!(frontend = etcd::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.exec {
+            frontends.push(Box::new(exec::Exec::from(cfg)));
+            tracing::info!(frontend = exec::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.export {
+            frontends.push(Box::new(export::Export::from(cfg)));
+            tracing::info!(frontend = export::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.gandi {
+            frontends.push(Box::new(gandi::Gandi::from(cfg)));
+            tracing::info!(frontend = gandi::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.gcloud {
+            frontends.push(Box::new(gcloud::GoogleCloudDNS::from(cfg)));
+            tracing::info!(frontend = gcloud::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.hetzner {
+            frontends.push(Box::new(hetzner::Hetzner::from(cfg)));
+            tracing::info!(frontend = hetzner::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.hosts {
+            frontends.push(Box::new(hosts::Hosts::from(cfg)));
+            tracing::info!(frontend = hosts::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.magicdns {
+            frontends.push(Box::new(magicdns::MagicDNS::from(cfg)));
+            tracing::info!(frontend = magicdns::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.mikrotik {
+            frontends.push(Box::new(mikrotik::MikroTik::from(cfg)));
+            tracing::info!(frontend = mikrotik::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.pihole {
+            frontends.push(Box::new(pihole::PiHole::from(cfg)));
+            tracing::info!(frontend = pihole::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.powerdns {
+            frontends.push(Box::new(powerdns::PowerDNS::from(cfg)));
+            tracing::info!(frontend = powerdns::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.rfc2136 {
+            frontends.push(Box::new(rfc2136::Rfc2136::from(cfg)));
+            tracing::info!(frontend = rfc2136::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.route53 {
+            frontends.push(Box::new(route53::Route53::from(cfg)));
+            tracing::info!(frontend = route53::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.technitium {
+            frontends.push(Box::new(technitium::Technitium::from(cfg)));
+            tracing::info!(frontend = technitium::FRONTEND_NAME, "Loaded frontend");
+        }
+        if let Some(cfg) = self.unbound {
+            frontends.push(Box::new(unbound::Unbound::from(cfg)));
+            tracing::info!(frontend = unbound::FRONTEND_NAME, "Loaded frontend");
+        }
 
         (backends, frontends)
     }
@@ -52,7 +166,29 @@ impl Config {
         Self {
             backends,
             frontends,
+            adguard: None,
+            azure: None,
+            bind: None,
             cloudflare: None,
+            desec: None,
+            digitalocean: None,
+            dnsmasq: None,
+            dnsserver: None,
+            etcd: None,
+            exec: None,
+            export: None,
+            gandi: None,
+            gcloud: None,
+            hetzner: None,
+            hosts: None,
+            magicdns: None,
+            mikrotik: None,
+            pihole: None,
+            powerdns: None,
+            rfc2136: None,
+            route53: None,
+            technitium: None,
+            unbound: None,
             headscale: None,
             machinectl: None,
             jsonfile: None,
@@ -85,9 +221,43 @@ impl Config {
         }
         for imp in self.frontends.iter() {
             match imp.to_lowercase().as_str() {
+                "adguard" => self.adguard = Some(parse_config(&format!("{ENV_PREFIX}_ADGUARD"))?),
+                "azure" => self.azure = Some(parse_config(&format!("{ENV_PREFIX}_AZURE"))?),
+                "bind" => self.bind = Some(parse_config(&format!("{ENV_PREFIX}_BIND"))?),
                 "cloudflare" => {
                     self.cloudflare = Some(parse_config(&format!("{ENV_PREFIX}_CLOUDFLARE"))?)
                 }
+                "desec" => self.desec = Some(parse_config(&format!("{ENV_PREFIX}_DESEC"))?),
+                "digitalocean" => {
+                    self.digitalocean = Some(parse_config(&format!("{ENV_PREFIX}_DIGITALOCEAN"))?)
+                }
+                "dnsmasq" => self.dnsmasq = Some(parse_config(&format!("{ENV_PREFIX}_DNSMASQ"))?),
+                "dnsserver" => {
+                    self.dnsserver = Some(parse_config(&format!("{ENV_PREFIX}_DNSSERVER"))?)
+                }
+                "etcd" => self.etcd = Some(parse_config(&format!("{ENV_PREFIX}_ETCD"))?),
+                "exec" => self.exec = Some(parse_config(&format!("{ENV_PREFIX}_EXEC"))?),
+                "export" => self.export = Some(parse_config(&format!("{ENV_PREFIX}_EXPORT"))?),
+                "gandi" => self.gandi = Some(parse_config(&format!("{ENV_PREFIX}_GANDI"))?),
+                "gcloud" => self.gcloud = Some(parse_config(&format!("{ENV_PREFIX}_GCLOUD"))?),
+                "hetzner" => self.hetzner = Some(parse_config(&format!("{ENV_PREFIX}_HETZNER"))?),
+                "hosts" => self.hosts = Some(parse_config(&format!("{ENV_PREFIX}_HOSTS"))?),
+                "magicdns" => {
+                    self.magicdns = Some(parse_config(&format!("{ENV_PREFIX}_MAGICDNS"))?)
+                }
+                "mikrotik" => {
+                    self.mikrotik = Some(parse_config(&format!("{ENV_PREFIX}_MIKROTIK"))?)
+                }
+                "pihole" => self.pihole = Some(parse_config(&format!("{ENV_PREFIX}_PIHOLE"))?),
+                "powerdns" => {
+                    self.powerdns = Some(parse_config(&format!("{ENV_PREFIX}_POWERDNS"))?)
+                }
+                "rfc2136" => self.rfc2136 = Some(parse_config(&format!("{ENV_PREFIX}_RFC2136"))?),
+                "route53" => self.route53 = Some(parse_config(&format!("{ENV_PREFIX}_ROUTE53"))?),
+                "technitium" => {
+                    self.technitium = Some(parse_config(&format!("{ENV_PREFIX}_TECHNITIUM"))?)
+                }
+                "unbound" => self.unbound = Some(parse_config(&format!("{ENV_PREFIX}_UNBOUND"))?),
                 be => {
                     return Err(ConfigSnafu {
                         prefix: format!("frontends"),
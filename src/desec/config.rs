@@ -0,0 +1,13 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub api_key: String,
+    pub domain: String,
+    pub instance_id: String,
+    /// Override the API base URL, e.g. to use a proxy.
+    pub base_url: Option<url::Url>,
+    pub registry_prefix: Option<String>,
+    /// Raised to the minimum TTL of the domain if lower.
+    pub ttl: Option<u32>,
+    /// How many times to retry a rate limited request before giving up.
+    pub max_retries: Option<u32>,
+}
@@ -0,0 +1,46 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.desec;
+in
+{
+  options.dnssync.frontends.desec = {
+    enable = lib.mkEnableOption "deSEC frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The deSEC domain to manage";
+    };
+    keyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the deSEC API token. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    baseUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "Override the deSEC API base URL, e.g. to use a proxy";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 3600;
+      description = "TTL of created records, raised to the minimum TTL of the domain if lower";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "desec";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_DESEC_DOMAIN" = cfg.domain;
+      "DNSSYNC_DESEC_API_KEY" = "@${cfg.keyFile}";
+      "DNSSYNC_DESEC_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_DESEC_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.baseUrl != null) {
+      "DNSSYNC_DESEC_BASE_URL" = cfg.baseUrl;
+    };
+  };
+}
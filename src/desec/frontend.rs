@@ -0,0 +1,306 @@
+use std::time::Duration;
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use snafu::prelude::*;
+
+use crate::common::{
+    self, group_rrsets, key_file_or_string, request_error_text, FrontendSnafu, Record, Result,
+    TxtRegistry,
+};
+
+use super::models::{APIError, Domain, RRSet, RecordSet, DEFAULT_TTL};
+
+pub const API_BASE_URL: &str = "https://desec.io/api/v1";
+
+pub const FRONTEND_NAME: &str = "deSEC";
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Rate limits which would block for longer than this are reported as errors
+/// instead, as they are usually the daily limits.
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(300);
+
+/// Finds the cursor of the next page in a Link header.
+fn next_cursor(link: Option<&str>) -> Option<String> {
+    let next = link?
+        .split(',')
+        .find(|part| part.contains("rel=\"next\""))?;
+    let url = next.split_once('<')?.1.split_once('>')?.0;
+    url::Url::parse(url)
+        .ok()?
+        .query_pairs()
+        .find(|(key, _)| key == "cursor")
+        .map(|(_, cursor)| cursor.to_string())
+}
+
+pub struct DeSEC {
+    api_key: String,
+    base_url: String,
+    domain: String,
+    max_retries: u32,
+    minimum_ttl: Option<u32>,
+    registry: TxtRegistry,
+    ttl: u32,
+}
+
+impl DeSEC {
+    fn domain_url(&self) -> String {
+        format!("{}/domains/{}/", self.base_url, self.domain)
+    }
+
+    fn rrsets_url(&self) -> String {
+        format!("{}rrsets/", self.domain_url())
+    }
+
+    /// Sends a request, waiting out any rate limits.
+    fn send(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(&str, &str)],
+        body: Option<&[RRSet]>,
+    ) -> Result<ureq::Response> {
+        let mut attempt = 0;
+        loop {
+            tracing::debug!(
+                url = url,
+                method = method,
+                frontend = "desec",
+                "Sending request"
+            );
+            let mut req =
+                ureq::request(method, url).set("Authorization", &format!("Token {}", self.api_key));
+            for (key, value) in query {
+                req = req.query(key, value);
+            }
+            let result = match body {
+                Some(body) => req.send_json(body),
+                None => req.call(),
+            };
+            let err = match result {
+                Ok(resp) => return Ok(resp),
+                Err(ureq::Error::Status(429, resp)) => {
+                    let wait = resp
+                        .header("Retry-After")
+                        .and_then(|s| s.parse().ok())
+                        .map(Duration::from_secs)
+                        .unwrap_or(Duration::from_secs(1));
+                    if attempt < self.max_retries && wait <= MAX_RETRY_WAIT {
+                        attempt += 1;
+                        tracing::warn!(
+                            frontend = FRONTEND_NAME,
+                            url = url,
+                            attempt = attempt,
+                            wait = ?wait,
+                            "Rate limited, retrying"
+                        );
+                        std::thread::sleep(wait);
+                        continue;
+                    }
+                    ureq::Error::Status(429, resp)
+                }
+                Err(err) => err,
+            };
+            // Validation errors are returned as the fields with problems,
+            // which are more useful as they are than summarised.
+            return Err(request_error_text(err, url, method, |body| {
+                Some(
+                    serde_json::from_str::<APIError>(&body)
+                        .map(|e| e.detail)
+                        .unwrap_or(body),
+                )
+            }));
+        }
+    }
+
+    fn api_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<&[RRSet]>,
+    ) -> Result<T> {
+        self.send(method, url, &[], body)?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    /// The TTL to write records with, raised to the minimum the domain allows.
+    fn get_ttl(&mut self) -> Result<u32> {
+        let minimum_ttl = match self.minimum_ttl {
+            Some(minimum_ttl) => minimum_ttl,
+            None => {
+                let domain: Domain = self.api_call("GET", &self.domain_url(), None)?;
+                if self.ttl < domain.minimum_ttl {
+                    tracing::warn!(
+                        frontend = FRONTEND_NAME,
+                        ttl = self.ttl,
+                        minimum_ttl = domain.minimum_ttl,
+                        "TTL is below the minimum for the domain, using the minimum instead"
+                    );
+                }
+                self.minimum_ttl = Some(domain.minimum_ttl);
+                domain.minimum_ttl
+            }
+        };
+        Ok(self.ttl.max(minimum_ttl))
+    }
+
+    fn read_records(&self) -> Result<Vec<RecordSet>> {
+        let url = self.rrsets_url();
+        let mut records = Vec::new();
+        // An empty cursor asks for the first page, otherwise
+        // large domains are refused instead of paginated.
+        let mut cursor = String::new();
+        loop {
+            let resp = self.send("GET", &url, &[("cursor", &cursor)], None)?;
+            let next = next_cursor(resp.header("Link"));
+            let rrsets: Vec<RRSet> = resp.into_json().boxed_local().context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })?;
+            records.extend(
+                rrsets
+                    .into_iter()
+                    .map(|rrset| RecordSet::from_api(rrset, &self.domain)),
+            );
+
+            match next {
+                Some(next) => cursor = next,
+                None => return Ok(records),
+            }
+        }
+    }
+}
+
+impl common::Frontend for DeSEC {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // Fails if the token is invalid or can't see the domain.
+        // Tokens are not scoped to read only, so this is enough.
+        self.get_ttl().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let ttl = self.get_ttl()?;
+        let current = self.read_records()?;
+        let mut diff = self.registry.diff(current, group_rrsets(authority));
+        for record in diff.records.create.iter_mut() {
+            record.record.ttl = Some(ttl);
+        }
+        for record in diff.create_owners.iter_mut() {
+            record.ttl = Some(ttl);
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.records.create.len(),
+                update = diff.records.update.len(),
+                delete = diff.records.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.records.create.len(),
+            update = diff.records.update.len(),
+            delete = diff.records.delete.len(),
+            "Applying changes",
+        );
+
+        // All changes are sent in one bulk request, which deSEC applies atomically
+        // and counts as a single request against the rate limits.
+        let mut changes: Vec<RRSet> = Vec::with_capacity(diff.len());
+
+        for record in diff.records.delete.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            changes.push(record.deletion(&self.domain));
+        }
+        for record in diff.delete_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Deleting ownership record",
+            );
+            changes.push(record.deletion(&self.domain));
+        }
+
+        for record in diff.records.update.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+            changes.push(record.desired(&self.domain));
+        }
+
+        for record in diff.records.create.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            changes.push(record.desired(&self.domain));
+        }
+        for record in diff.create_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Creating ownership record",
+            );
+            changes.push(record.desired(&self.domain));
+        }
+
+        self.api_call::<IgnoredAny>("PATCH", &self.rrsets_url(), Some(&changes))
+            .map(|_| ())
+    }
+}
+
+impl From<super::Config> for DeSEC {
+    fn from(value: super::Config) -> Self {
+        let api_key = key_file_or_string(value.api_key, FRONTEND_NAME.into()).unwrap();
+
+        Self {
+            api_key,
+            base_url: value
+                .base_url
+                .map(|url| url.as_str().trim_end_matches('/').to_string())
+                .unwrap_or_else(|| API_BASE_URL.to_string()),
+            domain: value.domain.trim_end_matches('.').to_lowercase(),
+            max_retries: value.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            minimum_ttl: None,
+            registry: TxtRegistry::new(value.registry_prefix, value.instance_id),
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
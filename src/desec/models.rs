@@ -0,0 +1,160 @@
+use crate::common::{
+    join_values, normalise_content, txt_from_zone_value, txt_to_zone_value, Match, Record, Update,
+    RECORD_KIND_TXT,
+};
+
+/// The minimum TTL deSEC allows on most accounts.
+pub(super) const DEFAULT_TTL: u32 = 3600;
+
+/// Record kinds whose content is a domain name, which deSEC requires to end in a dot.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub detail: String,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Domain {
+    pub minimum_ttl: u32,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub(super) struct RRSet {
+    /// Relative to the zone, empty at the apex.
+    pub subname: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Empty to delete the RRset.
+    pub records: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+/// Converts a record value in zone file format to DNSSync content.
+fn value_to_content(kind: &str, value: &str) -> String {
+    if kind == RECORD_KIND_TXT {
+        return txt_from_zone_value(value);
+    }
+    normalise_content(kind, value)
+}
+
+fn content_to_value(kind: &str, content: &str) -> String {
+    match kind {
+        RECORD_KIND_TXT => txt_to_zone_value(content),
+        kind if NAME_KINDS.contains(&kind) => format!("{content}."),
+        _ => content.to_string(),
+    }
+}
+
+/// An RRset with its name made absolute,
+/// and its values as one record separated by newlines.
+#[derive(Clone)]
+pub(super) struct RecordSet {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: Option<u32>,
+}
+
+impl RecordSet {
+    pub(super) fn from_api(rrset: RRSet, zone: &str) -> Self {
+        let name = match rrset.subname.as_str() {
+            "" => zone.to_string(),
+            subname => format!("{subname}.{zone}"),
+        };
+        let content = join_values(
+            rrset
+                .records
+                .iter()
+                .map(|value| value_to_content(&rrset.kind, value))
+                .collect(),
+        );
+        Self {
+            name: name.to_lowercase(),
+            kind: rrset.kind,
+            content,
+            ttl: rrset.ttl,
+        }
+    }
+
+    fn subname(&self, zone: &str) -> String {
+        match self.name.strip_suffix(zone) {
+            Some(name) => name.trim_end_matches('.').to_string(),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The RRset as it should be once written.
+    pub(super) fn desired(&self, zone: &str) -> RRSet {
+        RRSet {
+            subname: self.subname(zone),
+            kind: self.kind.clone(),
+            records: self
+                .content
+                .split('\n')
+                .map(|content| content_to_value(&self.kind, content))
+                .collect(),
+            ttl: self.ttl,
+        }
+    }
+
+    /// A change removing the RRset.
+    pub(super) fn deletion(&self, zone: &str) -> RRSet {
+        RRSet {
+            subname: self.subname(zone),
+            kind: self.kind.clone(),
+            records: Vec::new(),
+            ttl: None,
+        }
+    }
+}
+
+impl Match for RecordSet {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RecordSet {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = RecordSet::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for RecordSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<RecordSet> for Record {
+    fn from(value: RecordSet) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for RecordSet {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = join_values(
+            value
+                .content
+                .split('\n')
+                .map(|content| normalise_content(&kind, content))
+                .collect(),
+        );
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: None,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub api_key: String,
+    pub domain: String,
+    pub instance_id: String,
+    /// Override the API base URL, e.g. to use a proxy.
+    pub base_url: Option<url::Url>,
+    pub registry_prefix: Option<String>,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,46 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.digitalocean;
+in
+{
+  options.dnssync.frontends.digitalocean = {
+    enable = lib.mkEnableOption "DigitalOcean frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The DigitalOcean domain to manage";
+    };
+    keyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the DigitalOcean personal access token. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    baseUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "Override the DigitalOcean API base URL, e.g. to use a proxy";
+    };
+    ttl = mkOption {
+      type = types.ints.between 30 2147483647;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "digitalocean";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_DIGITALOCEAN_DOMAIN" = cfg.domain;
+      "DNSSYNC_DIGITALOCEAN_API_KEY" = "@${cfg.keyFile}";
+      "DNSSYNC_DIGITALOCEAN_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_DIGITALOCEAN_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.baseUrl != null) {
+      "DNSSYNC_DIGITALOCEAN_BASE_URL" = cfg.baseUrl;
+    };
+  };
+}
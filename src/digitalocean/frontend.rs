@@ -0,0 +1,238 @@
+use serde::de::{DeserializeOwned, IgnoredAny};
+use snafu::prelude::*;
+
+use crate::common::{
+    self, key_file_or_string, request_error, FrontendSnafu, Record, Result, TxtRegistry,
+};
+
+use super::models::{APIError, APIRecord, DNSRecord, RecordsResponse, DEFAULT_TTL};
+
+pub const API_BASE_URL: &str = "https://api.digitalocean.com/v2";
+
+pub const FRONTEND_NAME: &str = "DigitalOcean";
+
+const RECORDS_PER_PAGE: usize = 200;
+
+pub struct DigitalOcean {
+    api_key: String,
+    base_url: String,
+    domain: String,
+    registry: TxtRegistry,
+    ttl: u32,
+}
+
+impl DigitalOcean {
+    fn records_url(&self) -> String {
+        format!("{}/domains/{}/records", self.base_url, self.domain)
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        tracing::debug!(
+            url = url,
+            method = method,
+            frontend = "digitalocean",
+            "Sending request"
+        );
+        ureq::request(method, url)
+            .set("Authorization", &format!("Bearer {}", self.api_key))
+            .set("Content-Type", "application/json; charset=utf-8")
+    }
+
+    fn api_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(&str, &str)],
+        body: Option<&APIRecord>,
+    ) -> Result<T> {
+        let mut req = self.request(method, url);
+        for (key, value) in query {
+            req = req.query(key, value);
+        }
+        match body {
+            Some(body) => req.send_json(body),
+            None => req.call(),
+        }
+        .map_err(|err| request_error(err, url, method, APIError::detail))?
+        .into_json()
+        .boxed_local()
+        .context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: "Failed to deserialize response",
+        })
+    }
+
+    fn read_records(&self) -> Result<Vec<DNSRecord>> {
+        let url = self.records_url();
+        let per_page = RECORDS_PER_PAGE.to_string();
+        let mut records = Vec::new();
+        let mut page = 1;
+        loop {
+            let resp: RecordsResponse = self.api_call(
+                "GET",
+                &url,
+                &[("page", &page.to_string()), ("per_page", &per_page)],
+                None,
+            )?;
+            records.extend(
+                resp.domain_records
+                    .into_iter()
+                    .map(|r| DNSRecord::from_api(r, &self.domain)),
+            );
+
+            match resp.links.and_then(|l| l.pages).and_then(|p| p.next) {
+                Some(_) => page += 1,
+                None => return Ok(records),
+            }
+        }
+    }
+
+    fn delete_record(&self, record: &DNSRecord) -> Result<()> {
+        let url = format!("{}/{}", self.records_url(), record.id);
+        // Deletes respond with no content
+        self.request("DELETE", &url)
+            .call()
+            .map_err(|err| request_error(err, &url, "DELETE", APIError::detail))?;
+        Ok(())
+    }
+
+    fn update_record(&self, record: &DNSRecord) -> Result<()> {
+        let url = format!("{}/{}", self.records_url(), record.id);
+        let body = record.to_api(&self.domain);
+        self.api_call::<IgnoredAny>("PUT", &url, &[], Some(&body))
+            .map(|_| ())
+    }
+
+    fn create_record(&self, record: &DNSRecord) -> Result<()> {
+        let body = record.to_api(&self.domain);
+        self.api_call::<IgnoredAny>("POST", &self.records_url(), &[], Some(&body))
+            .map(|_| ())
+    }
+}
+
+impl common::Frontend for DigitalOcean {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // Fails if the token is invalid or the domain isn't in the account.
+        // Read-only tokens can't be told apart without making a change.
+        let url = format!("{}/domains/{}", self.base_url, self.domain);
+        self.api_call::<IgnoredAny>("GET", &url, &[], None)
+            .map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let current = self.read_records()?;
+        let mut diff = self.registry.diff(current, authority);
+        for record in diff.records.create.iter_mut() {
+            record.record.ttl = Some(self.ttl);
+        }
+        for record in diff.create_owners.iter_mut() {
+            record.ttl = Some(self.ttl);
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.records.create.len(),
+                update = diff.records.update.len(),
+                delete = diff.records.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.records.create.len(),
+            update = diff.records.update.len(),
+            delete = diff.records.delete.len(),
+            "Applying changes",
+        );
+
+        // Deletes first - to avoid conflicts with created records.
+        for record in diff.records.delete.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                record_id = record.id,
+                "Deleting record",
+            );
+            self.delete_record(record)?;
+        }
+        for record in diff.delete_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                record_id = record.id,
+                "Deleting ownership record",
+            );
+            self.delete_record(record)?;
+        }
+
+        for record in diff.records.update.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                record_id = record.id,
+                "Updating record"
+            );
+            self.update_record(record)?;
+        }
+
+        for record in diff.records.create.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            self.create_record(record)?;
+        }
+        for record in diff.create_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Creating ownership record",
+            );
+            self.create_record(record)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for DigitalOcean {
+    fn from(value: super::Config) -> Self {
+        let api_key = key_file_or_string(value.api_key, FRONTEND_NAME.into()).unwrap();
+
+        Self {
+            api_key,
+            base_url: value
+                .base_url
+                .map(|url| url.as_str().trim_end_matches('/').to_string())
+                .unwrap_or_else(|| API_BASE_URL.to_string()),
+            domain: value.domain.trim_end_matches('.').to_lowercase(),
+            registry: TxtRegistry::new(value.registry_prefix, value.instance_id),
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
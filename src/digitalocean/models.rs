@@ -0,0 +1,148 @@
+use crate::common::{Match, Record, Update};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+/// The name DigitalOcean uses for the zone apex.
+const APEX: &str = "@";
+
+/// Record kinds whose data is a domain name. DigitalOcean returns these
+/// without the trailing dot, but requires it when writing.
+const NAME_KINDS: [&str; 2] = ["CNAME", "NS"];
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub message: String,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        Some(self.message)
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Pages {
+    pub next: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Links {
+    pub pages: Option<Pages>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct RecordsResponse {
+    #[serde(default)]
+    pub domain_records: Vec<APIRecord>,
+    pub links: Option<Links>,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct APIRecord {
+    #[serde(default, skip_serializing)]
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Relative to the zone.
+    pub name: String,
+    pub data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+/// A record with its name and data made absolute.
+#[derive(Clone)]
+pub(super) struct DNSRecord {
+    pub id: u64,
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: Option<u32>,
+}
+
+impl DNSRecord {
+    pub(super) fn from_api(record: APIRecord, zone: &str) -> Self {
+        let name = match record.name.as_str() {
+            APEX => zone.to_string(),
+            name => format!("{name}.{zone}"),
+        };
+        let content = match NAME_KINDS.contains(&record.kind.as_str()) {
+            true if record.data == APEX => zone.to_string(),
+            true => record.data.trim_end_matches('.').to_lowercase(),
+            false => record.data,
+        };
+        Self {
+            id: record.id,
+            name: name.to_lowercase(),
+            kind: record.kind,
+            content,
+            ttl: record.ttl,
+        }
+    }
+
+    pub(super) fn to_api(&self, zone: &str) -> APIRecord {
+        let name = match self.name.strip_suffix(zone) {
+            Some("") => APEX.to_string(),
+            Some(name) => name.trim_end_matches('.').to_string(),
+            None => self.name.clone(),
+        };
+        let data = match NAME_KINDS.contains(&self.kind.as_str()) {
+            true => format!("{}.", self.content),
+            false => self.content.clone(),
+        };
+        APIRecord {
+            id: self.id,
+            kind: self.kind.clone(),
+            name,
+            data,
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl Match for DNSRecord {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for DNSRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = DNSRecord::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for DNSRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<DNSRecord> for Record {
+    fn from(value: DNSRecord) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for DNSRecord {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = match NAME_KINDS.contains(&kind.as_str()) {
+            true => value.content.trim_end_matches('.').to_lowercase(),
+            false => value.content,
+        };
+        Self {
+            id: 0,
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: None,
+        }
+    }
+}
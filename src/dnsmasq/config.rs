@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// The config snippet to write, e.g. in dnsmasq's conf-dir. It is entirely managed by DNSSync.
+    pub path: PathBuf,
+    /// Shell command run after the file changes, e.g. `systemctl restart dnsmasq`.
+    pub reload_command: Option<String>,
+}
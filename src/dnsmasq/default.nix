@@ -0,0 +1,36 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.dnsmasq;
+in
+{
+  options.dnssync.frontends.dnsmasq = {
+    enable = lib.mkEnableOption "dnsmasq config frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write records for";
+    };
+    path = mkOption {
+      type = types.str;
+      example = "/var/lib/dnssync/dnsmasq.conf";
+      description = "The config snippet to write. Include it with conf-file in dnsmasq's config";
+    };
+    reloadCommand = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "systemctl restart dnsmasq";
+      description = "Command to run after the config snippet changes";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "dnsmasq";
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.path) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_DNSMASQ_DOMAIN" = cfg.domain;
+      "DNSSYNC_DNSMASQ_PATH" = cfg.path;
+    } // lib.optionalAttrs (cfg.reloadCommand != null) {
+      "DNSSYNC_DNSMASQ_RELOAD_COMMAND" = cfg.reloadCommand;
+    };
+  };
+}
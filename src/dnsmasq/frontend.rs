@@ -0,0 +1,180 @@
+use std::path::PathBuf;
+
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, diff_records, read_to_string_or_empty, run_shell, write_atomic,
+    FrontendSnafu, Record, Result,
+};
+
+use super::models::DnsmasqEntry;
+
+pub const FRONTEND_NAME: &str = "Dnsmasq";
+
+const HEADER: &str = "# Managed by DNSSync. Changes made by hand will be overwritten.";
+
+pub struct Dnsmasq {
+    domain: String,
+    path: PathBuf,
+    reload_command: Option<String>,
+}
+
+impl Dnsmasq {
+    fn read_entries(&self) -> Result<Vec<DnsmasqEntry>> {
+        let text = read_to_string_or_empty(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read {}", self.path.display()),
+            })?;
+
+        Ok(text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let entry = DnsmasqEntry::from_line(line);
+                if entry.is_none() {
+                    tracing::warn!(
+                        frontend = FRONTEND_NAME,
+                        line = line,
+                        "Skipping unrecognized config line"
+                    );
+                }
+                entry
+            })
+            .collect())
+    }
+}
+
+impl common::Frontend for Dnsmasq {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.read_entries()?;
+        check_writable(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.path.display()),
+            })
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = DnsmasqEntry::is_supported(&r.kind);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        let current = self.read_entries()?;
+        let diff = diff_records::<DnsmasqEntry>(current.clone(), authority);
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        for entry in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = entry.name,
+                kind = entry.kind,
+                content = entry.content,
+                "Deleting record",
+            );
+        }
+        for entry in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = entry.name,
+                kind = entry.kind,
+                content = entry.content,
+                "Updating record"
+            );
+        }
+        for entry in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = entry.name,
+                kind = entry.kind,
+                content = entry.content,
+                "Creating record"
+            );
+        }
+
+        let mut entries = diff.apply(current);
+        entries.sort_by(|a, b| (&a.name, &a.kind).cmp(&(&b.name, &b.kind)));
+
+        let mut lines = vec![HEADER.to_string()];
+        lines.extend(entries.iter().map(|e| e.to_line()));
+        lines.push(String::new());
+
+        write_atomic(&self.path, &lines.join("\n"))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.path.display()),
+            })?;
+
+        if let Some(command) = &self.reload_command {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                command = command,
+                "Running reload command"
+            );
+            run_shell(command).boxed_local().context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Reload command failed",
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for Dnsmasq {
+    fn from(value: super::Config) -> Self {
+        Self {
+            domain: value.domain,
+            path: value.path,
+            reload_command: value.reload_command,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::common::{
+    Manage, Match, Record, Update, RECORD_KIND_A, RECORD_KIND_AAAA, RECORD_KIND_TXT,
+};
+
+const KIND_CNAME: &str = "CNAME";
+const KIND_PTR: &str = "PTR";
+
+#[derive(Clone)]
+pub(super) struct DnsmasqEntry {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+/// The dnsmasq option used to serve each supported record kind.
+fn option_for(kind: &str) -> Option<&'static str> {
+    match kind {
+        RECORD_KIND_A | RECORD_KIND_AAAA => Some("host-record"),
+        KIND_CNAME => Some("cname"),
+        KIND_PTR => Some("ptr-record"),
+        RECORD_KIND_TXT => Some("txt-record"),
+        _ => None,
+    }
+}
+
+impl DnsmasqEntry {
+    pub(super) fn is_supported(kind: &str) -> bool {
+        option_for(&kind.to_uppercase()).is_some()
+    }
+
+    pub(super) fn to_line(&self) -> String {
+        let option = option_for(&self.kind).unwrap_or_default();
+        let value = match self.kind.as_str() {
+            RECORD_KIND_TXT => format!(
+                "\"{}\"",
+                self.content.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+            _ => self.content.clone(),
+        };
+        format!("{option}={},{value}", self.name)
+    }
+
+    /// Parses a line written by to_line.
+    pub(super) fn from_line(line: &str) -> Option<Self> {
+        let (option, value) = line.split_once('=')?;
+        let (name, content) = value.split_once(',')?;
+        let kind = match option {
+            // Both address families share an option
+            "host-record" => match content.parse::<std::net::IpAddr>().ok()? {
+                std::net::IpAddr::V4(_) => RECORD_KIND_A,
+                std::net::IpAddr::V6(_) => RECORD_KIND_AAAA,
+            },
+            "cname" => KIND_CNAME,
+            "ptr-record" => KIND_PTR,
+            "txt-record" => RECORD_KIND_TXT,
+            _ => return None,
+        };
+        let content = match kind {
+            RECORD_KIND_TXT => content
+                .trim_matches('"')
+                .replace("\\\"", "\"")
+                .replace("\\\\", "\\"),
+            _ => content.to_string(),
+        };
+        Some(Self {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            content,
+        })
+    }
+}
+
+impl Manage for DnsmasqEntry {
+    fn is_managed(&self) -> bool {
+        // The file is only written by DNSSync
+        true
+    }
+}
+
+impl Match for DnsmasqEntry {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for DnsmasqEntry {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = DnsmasqEntry::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for DnsmasqEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<DnsmasqEntry> for Record {
+    fn from(value: DnsmasqEntry) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for DnsmasqEntry {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = match kind.as_str() {
+            KIND_CNAME | KIND_PTR => normalize_name(&value.content),
+            _ => value.content,
+        };
+        Self {
+            name: normalize_name(&value.name.to_string()),
+            kind,
+            content,
+        }
+    }
+}
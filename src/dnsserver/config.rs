@@ -0,0 +1,12 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// Comma separated socket addresses to serve UDP and TCP on. Defaults to [::]:53.
+    pub listen: Option<String>,
+    /// Comma separated names of the zone's nameservers, i.e. this host as
+    /// named in the parent zone's delegation. The first is used in the SOA.
+    pub nameservers: String,
+    /// SOA RNAME, either as an email address or in DNS form. Defaults to hostmaster.<domain>.
+    pub hostmaster: Option<String>,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,67 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.dnsserver;
+in
+{
+  options.dnssync.frontends.dnsserver = {
+    enable = lib.mkEnableOption "built-in authoritative DNS server frontend for records";
+    domain = mkOption {
+      type = types.str;
+      example = "ts.example.com";
+      description = "The domain to serve records for";
+    };
+    listen = mkOption {
+      type = types.listOf types.str;
+      default = [ "[::]:53" ];
+      example = [ "192.0.2.10:53" "[2001:db8::10]:53" ];
+      description = "Socket addresses to answer UDP and TCP queries on";
+    };
+    nameservers = mkOption {
+      type = types.nonEmptyListOf types.str;
+      example = [ "dnssync.example.com" ];
+      description = "Names of this host as delegated to from the parent zone. The first is used in the SOA";
+    };
+    hostmaster = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "hostmaster@example.com";
+      description = "SOA contact. Defaults to hostmaster at the domain";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of served records";
+    };
+    interval = mkOption {
+      type = types.str;
+      default = "1m";
+      description = "How often to sync while serving, replacing the timer";
+    };
+    openFirewall = lib.mkEnableOption "port 53 in the firewall";
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "dnsserver";
+    dnssync.extraArgs = [ "--interval" cfg.interval ];
+    # The server lives as long as the process, so run it continuously
+    systemd.services.dnssync.serviceConfig = {
+      Type = lib.mkForce "simple";
+      Restart = "on-failure";
+      AmbientCapabilities = [ "CAP_NET_BIND_SERVICE" ];
+    };
+    systemd.timers.dnssync.enable = false;
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_DNSSERVER_DOMAIN" = cfg.domain;
+      "DNSSYNC_DNSSERVER_LISTEN" = lib.concatStringsSep "," cfg.listen;
+      "DNSSYNC_DNSSERVER_NAMESERVERS" = lib.concatStringsSep "," cfg.nameservers;
+      "DNSSYNC_DNSSERVER_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.hostmaster != null) {
+      "DNSSYNC_DNSSERVER_HOSTMASTER" = cfg.hostmaster;
+    };
+    networking.firewall = lib.mkIf cfg.openFirewall {
+      allowedTCPPorts = [ 53 ];
+      allowedUDPPorts = [ 53 ];
+    };
+  };
+}
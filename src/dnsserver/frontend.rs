@@ -0,0 +1,304 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hickory_proto::op::Message;
+use hickory_proto::rr::Name;
+use snafu::prelude::*;
+
+use crate::bind::ZoneRecord;
+use crate::common::{self, diff_records, ConfigSnafu, FrontendSnafu, Record, Result};
+
+use super::models::Zone;
+
+pub const FRONTEND_NAME: &str = "DNSServer";
+
+const DEFAULT_LISTEN: &str = "[::]:53";
+const DEFAULT_TTL: u32 = 300;
+const TCP_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct DnsServer {
+    domain: String,
+    origin: Name,
+    listen: Vec<SocketAddr>,
+    nameservers: Vec<Name>,
+    hostmaster: Name,
+    ttl: u32,
+    serial: u32,
+    records: Vec<ZoneRecord>,
+    zone: Arc<RwLock<Zone>>,
+    serving: bool,
+}
+
+fn unix_time() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or_default()
+}
+
+fn config_error(message: String) -> common::Error {
+    ConfigSnafu {
+        message,
+        prefix: FRONTEND_NAME,
+    }
+    .build()
+}
+
+fn parse_name(name: &str) -> Result<Name> {
+    Name::from_ascii(format!("{}.", name.trim().trim_end_matches('.')))
+        .map_err(|err| config_error(format!("Invalid name {name}: {err}")))
+}
+
+fn parse_nameservers(nameservers: &str) -> Result<Vec<Name>> {
+    // Unfortunately config-rs makes it difficult to mix
+    // strings and vec of strings, so we have to parse ourselves
+    let nameservers = nameservers
+        .split(',')
+        .filter(|ns| !ns.trim().is_empty())
+        .map(parse_name)
+        .collect::<Result<Vec<Name>>>()?;
+    ensure!(
+        !nameservers.is_empty(),
+        ConfigSnafu {
+            message: "At least one nameserver is required",
+            prefix: FRONTEND_NAME,
+        }
+    );
+    Ok(nameservers)
+}
+
+fn serve_udp(socket: UdpSocket, zone: Arc<RwLock<Zone>>) {
+    let mut buf = [0u8; 4096];
+    loop {
+        let (len, peer) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(err) => {
+                tracing::warn!(
+                    frontend = FRONTEND_NAME,
+                    error = err.to_string(),
+                    "UDP receive failed"
+                );
+                continue;
+            }
+        };
+        // Malformed requests are dropped
+        let Ok(request) = Message::from_vec(&buf[..len]) else {
+            continue;
+        };
+        let response = zone.read().unwrap().respond(&request, true);
+        if let Ok(bytes) = response.to_vec() {
+            let _ = socket.send_to(&bytes, peer);
+        }
+    }
+}
+
+fn serve_tcp_connection(mut stream: TcpStream, zone: Arc<RwLock<Zone>>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(TCP_TIMEOUT))?;
+    stream.set_write_timeout(Some(TCP_TIMEOUT))?;
+    loop {
+        let mut len = [0u8; 2];
+        stream.read_exact(&mut len)?;
+        let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+        stream.read_exact(&mut buf)?;
+
+        let request = Message::from_vec(&buf).map_err(std::io::Error::other)?;
+        let response = zone.read().unwrap().respond(&request, false);
+        let bytes = response.to_vec().map_err(std::io::Error::other)?;
+        stream.write_all(&(bytes.len() as u16).to_be_bytes())?;
+        stream.write_all(&bytes)?;
+    }
+}
+
+fn serve_tcp(listener: TcpListener, zone: Arc<RwLock<Zone>>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let zone = zone.clone();
+        // Connections end with an error once the client closes them
+        std::thread::spawn(move || serve_tcp_connection(stream, zone));
+    }
+}
+
+impl DnsServer {
+    fn bind(&self) -> Result<Vec<(UdpSocket, TcpListener)>> {
+        self.listen
+            .iter()
+            .map(|addr| {
+                UdpSocket::bind(addr)
+                    .and_then(|udp| Ok((udp, TcpListener::bind(addr)?)))
+                    .boxed_local()
+                    .context(FrontendSnafu {
+                        frontend: FRONTEND_NAME,
+                        message: format!("Failed to listen on {addr}"),
+                    })
+            })
+            .collect()
+    }
+
+    /// Starts answering queries in background threads, which
+    /// run for as long as the process does.
+    fn serve(&mut self) -> Result<()> {
+        for (udp, tcp) in self.bind()? {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                address = udp.local_addr().map(|a| a.to_string()).unwrap_or_default(),
+                "Serving DNS"
+            );
+            let zone = self.zone.clone();
+            std::thread::spawn(move || serve_udp(udp, zone));
+            let zone = self.zone.clone();
+            std::thread::spawn(move || serve_tcp(tcp, zone));
+        }
+        self.serving = true;
+        Ok(())
+    }
+
+    fn build_zone(&self) -> Zone {
+        Zone::new(
+            &self.origin,
+            &self.nameservers,
+            &self.hostmaster,
+            self.serial,
+            self.ttl,
+            &self.records,
+        )
+    }
+}
+
+impl common::Frontend for DnsServer {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // Already listening means the sockets are fine
+        if self.serving {
+            return Ok(());
+        }
+        self.bind().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let mut diff = diff_records::<ZoneRecord>(self.records.clone(), authority);
+        for record in diff.create.iter_mut() {
+            record.ttl = self.ttl;
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 && (self.serving || dry_run) {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        for record in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+        }
+        for record in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+        }
+        for record in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+        }
+
+        self.records = diff.apply(std::mem::take(&mut self.records));
+        if diff.len() > 0 {
+            self.serial = unix_time().max(self.serial + 1);
+        }
+        *self.zone.write().unwrap() = self.build_zone();
+
+        if !self.serving {
+            self.serve()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for DnsServer {
+    fn from(value: super::Config) -> Self {
+        let origin = parse_name(&value.domain).unwrap();
+
+        let listen = value
+            .listen
+            .unwrap_or_else(|| DEFAULT_LISTEN.to_string())
+            .split(',')
+            .map(|addr| {
+                addr.trim()
+                    .parse()
+                    .map_err(|err| config_error(format!("Invalid listen address {addr}: {err}")))
+                    .unwrap()
+            })
+            .collect();
+
+        let nameservers = parse_nameservers(&value.nameservers).unwrap();
+
+        // Email addresses are converted to DNS form
+        let hostmaster = value
+            .hostmaster
+            .map(|h| h.replacen('@', ".", 1))
+            .unwrap_or_else(|| format!("hostmaster.{}", value.domain));
+        let hostmaster = parse_name(&hostmaster).unwrap();
+
+        let ttl = value.ttl.unwrap_or(DEFAULT_TTL);
+        let serial = unix_time();
+        let zone = Zone::new(&origin, &nameservers, &hostmaster, serial, ttl, &[]);
+
+        Self {
+            domain: value.domain,
+            origin,
+            listen,
+            nameservers,
+            hostmaster,
+            ttl,
+            serial,
+            records: Vec::new(),
+            zone: Arc::new(RwLock::new(zone)),
+            serving: false,
+        }
+    }
+}
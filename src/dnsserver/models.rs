@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use hickory_proto::op::{Edns, Message, MessageType, OpCode, Query, ResponseCode};
+use hickory_proto::rr::rdata::{NS, SOA};
+use hickory_proto::rr::{DNSClass, Name, RData, Record, RecordType};
+
+use crate::bind::ZoneRecord;
+use crate::common::content_to_rdata;
+
+// SOA timers, in seconds
+const SOA_REFRESH: i32 = 3600;
+const SOA_RETRY: i32 = 900;
+const SOA_EXPIRE: i32 = 1209600;
+
+/// Record kinds whose content is a domain name.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+/// Limit on following CNAMEs within the zone, to break loops.
+const MAX_CNAME_CHAIN: usize = 8;
+
+/// Largest UDP response sent with EDNS, per the DNS flag day 2020 recommendation.
+const MAX_UDP_PAYLOAD: u16 = 1232;
+
+/// The zone served, rebuilt whenever the records change.
+pub(super) struct Zone {
+    origin: Name,
+    soa: Record,
+    nameservers: Vec<Record>,
+    records: HashMap<Name, Vec<Record>>,
+}
+
+impl Zone {
+    pub(super) fn new(
+        origin: &Name,
+        nameservers: &[Name],
+        hostmaster: &Name,
+        serial: u32,
+        ttl: u32,
+        records: &[ZoneRecord],
+    ) -> Self {
+        let soa = SOA::new(
+            nameservers[0].clone(),
+            hostmaster.clone(),
+            serial,
+            SOA_REFRESH,
+            SOA_RETRY,
+            SOA_EXPIRE,
+            ttl,
+        );
+
+        let mut map: HashMap<Name, Vec<Record>> = HashMap::new();
+        for record in records {
+            let name = match Name::from_ascii(format!("{}.", record.name)) {
+                Ok(name) if origin.zone_of(&name) => name.to_lowercase(),
+                _ => {
+                    tracing::warn!(
+                        frontend = super::FRONTEND_NAME,
+                        name = record.name,
+                        "Skipping record outside of the zone"
+                    );
+                    continue;
+                }
+            };
+            // Names in content are relative to the root, not the zone
+            let content = match NAME_KINDS.contains(&record.kind.as_str()) {
+                true => format!("{}.", record.content),
+                false => record.content.clone(),
+            };
+            match content_to_rdata(&record.kind, &content) {
+                Ok(rdata) => map
+                    .entry(name.clone())
+                    .or_default()
+                    .push(Record::from_rdata(name, record.ttl, rdata)),
+                Err(err) => tracing::warn!(
+                    frontend = super::FRONTEND_NAME,
+                    name = record.name,
+                    kind = record.kind,
+                    content = record.content,
+                    error = err.to_string(),
+                    "Skipping record with unsupported content"
+                ),
+            }
+        }
+
+        Self {
+            origin: origin.clone(),
+            soa: Record::from_rdata(origin.clone(), ttl, RData::SOA(soa)),
+            nameservers: nameservers
+                .iter()
+                .map(|ns| Record::from_rdata(origin.clone(), ttl, RData::NS(NS(ns.clone()))))
+                .collect(),
+            records: map,
+        }
+    }
+
+    /// Every record owned by the name, including the synthesized apex records.
+    fn records_at(&self, name: &Name) -> Vec<&Record> {
+        let mut records: Vec<&Record> = self
+            .records
+            .get(&name.to_lowercase())
+            .map(|records| records.iter().collect())
+            .unwrap_or_default();
+        if name.eq(&self.origin) {
+            records.push(&self.soa);
+            records.extend(self.nameservers.iter());
+        }
+        records
+    }
+
+    /// Whether a name has no records, but does have records below it.
+    fn is_empty_non_terminal(&self, name: &Name) -> bool {
+        self.records
+            .keys()
+            .any(|owner| owner.num_labels() > name.num_labels() && name.zone_of(owner))
+    }
+
+    /// The SOA record sent with negative answers, with the negative caching TTL.
+    fn negative_soa(&self) -> Record {
+        let mut soa = self.soa.clone();
+        if let Some(RData::SOA(data)) = soa.data() {
+            let ttl = soa.ttl().min(data.minimum());
+            soa.set_ttl(ttl);
+        }
+        soa
+    }
+
+    /// Fills in the answer to the query, returning the response code.
+    fn answer(&self, query: &Query, response: &mut Message) -> ResponseCode {
+        let qtype = query.query_type();
+        let mut name = query.name().clone();
+
+        for _ in 0..MAX_CNAME_CHAIN {
+            let records = self.records_at(&name);
+            if records.is_empty() {
+                response.add_name_server(self.negative_soa());
+                return match self.is_empty_non_terminal(&name) {
+                    true => ResponseCode::NoError,
+                    false => ResponseCode::NXDomain,
+                };
+            }
+
+            let matching: Vec<&Record> = records
+                .iter()
+                .copied()
+                .filter(|r| qtype == RecordType::ANY || r.record_type() == qtype)
+                .collect();
+            if !matching.is_empty() {
+                for record in matching {
+                    // Answer with the name as asked, as resolvers may randomize its case
+                    let mut record = record.clone();
+                    record.set_name(name.clone());
+                    response.add_answer(record);
+                }
+                return ResponseCode::NoError;
+            }
+
+            let cname = records.iter().find_map(|r| match r.data() {
+                Some(RData::CNAME(target)) => Some((r, target.0.clone())),
+                _ => None,
+            });
+            match cname {
+                Some((record, target)) => {
+                    let mut record = (*record).clone();
+                    record.set_name(name.clone());
+                    response.add_answer(record);
+                    // Targets outside the zone are left to the resolver
+                    if !self.origin.zone_of(&target) {
+                        return ResponseCode::NoError;
+                    }
+                    name = target;
+                }
+                None => {
+                    // The name exists, but not with this kind
+                    response.add_name_server(self.negative_soa());
+                    return ResponseCode::NoError;
+                }
+            }
+        }
+
+        ResponseCode::NoError
+    }
+
+    /// Builds the response to a request. Responses to UDP requests
+    /// are truncated to fit the requester's buffer.
+    pub(super) fn respond(&self, request: &Message, udp: bool) -> Message {
+        let mut response = Message::new();
+        response
+            .set_id(request.id())
+            .set_message_type(MessageType::Response)
+            .set_op_code(request.op_code())
+            .set_recursion_desired(request.recursion_desired())
+            .set_recursion_available(false)
+            .add_queries(request.queries().to_vec());
+
+        if request.extensions().is_some() {
+            let mut edns = Edns::new();
+            edns.set_max_payload(MAX_UDP_PAYLOAD);
+            response.set_edns(edns);
+        }
+
+        let code = match (request.op_code(), request.queries()) {
+            (OpCode::Query, [query]) => {
+                let class = query.query_class();
+                let supported_class = class == DNSClass::IN || class == DNSClass::ANY;
+                let transfer = matches!(query.query_type(), RecordType::AXFR | RecordType::IXFR);
+                if !supported_class || transfer || !self.origin.zone_of(query.name()) {
+                    ResponseCode::Refused
+                } else {
+                    response.set_authoritative(true);
+                    self.answer(query, &mut response)
+                }
+            }
+            (OpCode::Query, _) => ResponseCode::FormErr,
+            _ => ResponseCode::NotImp,
+        };
+        response.set_response_code(code);
+
+        if udp {
+            let limit = request.max_payload().min(MAX_UDP_PAYLOAD) as usize;
+            let too_long = response.to_vec().map(|b| b.len() > limit).unwrap_or(true);
+            if too_long {
+                response.take_answers();
+                response.take_name_servers();
+                response.take_additionals();
+                response.set_truncated(true);
+            }
+        }
+        response
+    }
+}
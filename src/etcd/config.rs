@@ -0,0 +1,13 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    /// The etcd v3 HTTP gateway, e.g. http://127.0.0.1:2379
+    pub base_url: url::Url,
+    pub domain: String,
+    /// The path CoreDNS reads, set with `path` in its etcd block. Defaults to /skydns.
+    pub prefix: Option<String>,
+    pub instance_id: String,
+    /// Only needed if etcd has authentication enabled.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,59 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.etcd;
+in
+{
+  options.dnssync.frontends.etcd = {
+    enable = lib.mkEnableOption "CoreDNS etcd (SkyDNS) frontend for records";
+    baseUrl = mkOption {
+      type = types.str;
+      default = "http://127.0.0.1:2379";
+      description = "The etcd v3 HTTP gateway";
+    };
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write keys for. Must be one of the zones of CoreDNS's etcd block";
+    };
+    prefix = mkOption {
+      type = types.str;
+      default = "/skydns";
+      description = "The path CoreDNS reads keys from";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    username = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "The etcd user to authenticate as, if authentication is enabled";
+    };
+    passwordFile = mkOption {
+      type = types.nullOr types.path;
+      default = null;
+      description = "Path to a file containing the etcd password. Must be owned by the dnssync user";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "etcd";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_ETCD_BASE_URL" = cfg.baseUrl;
+      "DNSSYNC_ETCD_DOMAIN" = cfg.domain;
+      "DNSSYNC_ETCD_PREFIX" = cfg.prefix;
+      "DNSSYNC_ETCD_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_ETCD_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.username != null) {
+      "DNSSYNC_ETCD_USERNAME" = cfg.username;
+      "DNSSYNC_ETCD_PASSWORD" = "@${cfg.passwordFile}";
+    };
+  };
+}
@@ -0,0 +1,252 @@
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, diff_records, key_file_or_string, request_error, ConfigSnafu, FrontendSnafu, Record,
+    Result,
+};
+
+use super::models::{
+    encode, is_supported, name_to_path, range_end, APIError, AuthResponse, Entry, KeyValue,
+    RangeRequest, RangeResponse, DEFAULT_PREFIX, DEFAULT_TTL,
+};
+
+pub const FRONTEND_NAME: &str = "Etcd";
+
+/// The instance ID becomes part of each key, so it must be a single segment.
+fn parse_instance_id(instance_id: String) -> Result<String> {
+    ensure!(
+        !instance_id.is_empty() && !instance_id.contains('/'),
+        ConfigSnafu {
+            message: "instance_id must not be empty or contain '/'",
+            prefix: FRONTEND_NAME,
+        }
+    );
+    Ok(instance_id)
+}
+
+fn parse_credentials(
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<Option<(String, String)>> {
+    match (username, password) {
+        (Some(username), Some(password)) => Ok(Some((
+            username,
+            key_file_or_string(password, FRONTEND_NAME.into())?,
+        ))),
+        (None, None) => Ok(None),
+        _ => ConfigSnafu {
+            message: "username and password must be set together",
+            prefix: FRONTEND_NAME,
+        }
+        .fail(),
+    }
+}
+
+pub struct Etcd {
+    base_url: url::Url,
+    domain: String,
+    prefix: String,
+    instance_id: String,
+    credentials: Option<(String, String)>,
+    ttl: u32,
+    /// The auth token of the current sync, if etcd has authentication enabled.
+    token: Option<String>,
+}
+
+impl Etcd {
+    /// Calls the gateway, which takes every request as a JSON POST.
+    fn api_call<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        body: impl serde::Serialize,
+    ) -> Result<T> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .push("v3")
+            .extend(path);
+        tracing::debug!(
+            url = url.as_str(),
+            method = "POST",
+            frontend = "etcd",
+            "Sending request"
+        );
+
+        let mut request = ureq::post(url.as_str());
+        if let Some(token) = &self.token {
+            request = request.set("Authorization", token);
+        }
+        request
+            .send_json(body)
+            .map_err(|err| request_error(err, url.as_str(), "POST", APIError::detail))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    /// Gets a token for the sync, as tokens expire after a few minutes.
+    fn authenticate(&mut self) -> Result<()> {
+        let Some((username, password)) = &self.credentials else {
+            return Ok(());
+        };
+        self.token = None;
+        let resp: AuthResponse = self.api_call(
+            &["auth", "authenticate"],
+            serde_json::json!({ "name": username, "password": password }),
+        )?;
+        self.token = Some(resp.token);
+        Ok(())
+    }
+
+    /// Reads every key under the domain's path.
+    fn read_records(&self) -> Result<Vec<Entry>> {
+        let path = format!("{}/", name_to_path(&self.prefix, &self.domain));
+        let resp: RangeResponse = self.api_call(
+            &["kv", "range"],
+            RangeRequest {
+                key: encode(&path),
+                range_end: encode(&range_end(&path)),
+            },
+        )?;
+        Ok(resp
+            .kvs
+            .iter()
+            .filter_map(|kv| Entry::from_api(&self.prefix, kv))
+            .collect())
+    }
+}
+
+impl common::Frontend for Etcd {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.authenticate()?;
+        self.read_records().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = is_supported(&r.kind);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        self.authenticate()?;
+        let current = self.read_records()?;
+        let diff = diff_records::<Entry>(current, authority);
+
+        // Only delete keys this instance wrote
+        let deletions: Vec<Entry> = diff
+            .delete
+            .into_iter()
+            .filter(|record| record.owner.as_ref() == Some(&self.instance_id))
+            .collect();
+
+        // Short circuit on no changes. Values are part of each key, so there are no updates.
+        if diff.create.len() + deletions.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                delete = deletions.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            delete = deletions.len(),
+            "Applying changes",
+        );
+
+        // Deletes first - to avoid conflicts with CNAMEs
+        for record in deletions {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            let key = record.key.as_deref().expect("Existing entries have a key");
+            self.api_call::<serde_json::Value>(
+                &["kv", "deleterange"],
+                KeyValue {
+                    key: encode(key),
+                    value: String::new(),
+                },
+            )?;
+        }
+
+        for record in diff.create {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            let value = serde_json::to_string(&record.to_service(self.ttl))
+                .boxed_local()
+                .context(FrontendSnafu {
+                    frontend: FRONTEND_NAME,
+                    message: "Failed to serialize record",
+                })?;
+            self.api_call::<serde_json::Value>(
+                &["kv", "put"],
+                KeyValue {
+                    key: encode(&record.new_key(&self.prefix, &self.instance_id)),
+                    value: encode(&value),
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for Etcd {
+    fn from(value: super::Config) -> Self {
+        let instance_id = parse_instance_id(value.instance_id).unwrap();
+        let credentials = parse_credentials(value.username, value.password).unwrap();
+        let prefix = value.prefix.unwrap_or_else(|| DEFAULT_PREFIX.to_string());
+
+        Self {
+            base_url: value.base_url,
+            domain: value.domain.trim_end_matches('.').to_lowercase(),
+            prefix: format!("/{}", prefix.trim_matches('/')),
+            instance_id,
+            credentials,
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+            token: None,
+        }
+    }
+}
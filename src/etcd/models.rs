@@ -0,0 +1,225 @@
+use std::net::IpAddr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use crate::common::{
+    Manage, Match, Record, Update, RECORD_KIND_A, RECORD_KIND_AAAA, RECORD_KIND_TXT,
+};
+
+pub(super) const DEFAULT_PREFIX: &str = "/skydns";
+pub(super) const DEFAULT_TTL: u32 = 300;
+pub(super) const KEY_OWNER_PREFIX: &str = "dnssync-";
+
+pub(super) const RECORD_KIND_CNAME: &str = "CNAME";
+
+pub(super) fn is_supported(kind: &str) -> bool {
+    [
+        RECORD_KIND_A,
+        RECORD_KIND_AAAA,
+        RECORD_KIND_CNAME,
+        RECORD_KIND_TXT,
+    ]
+    .iter()
+    .any(|k| k.eq_ignore_ascii_case(kind))
+}
+
+/// The body of an error response from the gateway.
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub message: Option<String>,
+    pub error: Option<String>,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        self.message.or(self.error)
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct AuthResponse {
+    pub token: String,
+}
+
+#[derive(serde::Serialize)]
+pub(super) struct RangeRequest {
+    pub key: String,
+    pub range_end: String,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct RangeResponse {
+    /// Left out when no keys are in the range.
+    #[serde(default)]
+    pub kvs: Vec<KeyValue>,
+}
+
+/// A key and value, both base64 encoded, as used by every request and response.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub(super) struct KeyValue {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub value: String,
+}
+
+/// A SkyDNS service, as read by CoreDNS. Fields DNSSync doesn't
+/// write, such as port or priority, are ignored when reading.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub(super) struct Service {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub host: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+pub(super) fn encode(value: &str) -> String {
+    STANDARD.encode(value)
+}
+
+fn decode(value: &str) -> Option<String> {
+    String::from_utf8(STANDARD.decode(value).ok()?).ok()
+}
+
+/// The end of the range of keys beginning with prefix.
+pub(super) fn range_end(prefix: &str) -> String {
+    let mut end = prefix.as_bytes().to_vec();
+    // Prefixes end with '/', so the last byte never overflows
+    *end.last_mut().expect("prefix is not empty") += 1;
+    String::from_utf8(end).expect("'/' + 1 is ASCII")
+}
+
+/// The path of a name, with its labels reversed under the prefix,
+/// e.g. /skydns/com/example/host for host.example.com.
+pub(super) fn name_to_path(prefix: &str, name: &str) -> String {
+    let mut path = prefix.to_string();
+    for label in name.split('.').rev() {
+        path.push('/');
+        path.push_str(label);
+    }
+    path
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+#[derive(Clone)]
+pub(super) struct Entry {
+    /// None if not yet written.
+    pub key: Option<String>,
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    /// The instance which wrote the key, taken from its last segment.
+    pub owner: Option<String>,
+}
+
+impl Entry {
+    /// Returns None for keys outside the prefix, or values which
+    /// aren't services DNSSync can write, such as SRV targets.
+    pub(super) fn from_api(prefix: &str, kv: &KeyValue) -> Option<Self> {
+        let key = decode(&kv.key)?;
+        let service: Service = serde_json::from_str(&decode(&kv.value)?).ok()?;
+        let mut labels: Vec<&str> = key.strip_prefix(prefix)?.split('/').skip(1).collect();
+
+        // Keys this instance writes have a segment after the name, so a name
+        // can have several values. Other keys may be at the name itself.
+        let owner = labels
+            .last()
+            .and_then(|leaf| leaf.strip_prefix(KEY_OWNER_PREFIX))
+            .and_then(|leaf| leaf.rsplit_once('-'))
+            .map(|(owner, _)| owner.to_string());
+        if owner.is_some() {
+            labels.pop();
+        }
+        labels.reverse();
+
+        // CoreDNS answers a host which isn't an address with a CNAME
+        let (kind, content) = match (service.host.parse::<IpAddr>(), service.host.is_empty()) {
+            (Ok(IpAddr::V4(_)), _) => (RECORD_KIND_A, service.host),
+            (Ok(IpAddr::V6(_)), _) => (RECORD_KIND_AAAA, service.host),
+            (Err(_), false) => (RECORD_KIND_CNAME, normalize_name(&service.host)),
+            (Err(_), true) if !service.text.is_empty() => (RECORD_KIND_TXT, service.text),
+            _ => return None,
+        };
+        Some(Self {
+            name: normalize_name(&labels.join(".")),
+            key: Some(key.clone()),
+            kind: kind.to_string(),
+            content,
+            owner,
+        })
+    }
+
+    /// The key this instance writes the entry to. The last segment
+    /// is derived from the value, so each value has its own key.
+    pub(super) fn new_key(&self, prefix: &str, instance_id: &str) -> String {
+        let digest = hex::encode(Sha256::digest(format!("{} {}", self.kind, self.content)));
+        format!(
+            "{}/{KEY_OWNER_PREFIX}{instance_id}-{}",
+            name_to_path(prefix, &self.name),
+            &digest[..12]
+        )
+    }
+
+    pub(super) fn to_service(&self, ttl: u32) -> Service {
+        let (host, text) = match self.kind.as_str() {
+            RECORD_KIND_TXT => (String::new(), self.content.clone()),
+            _ => (self.content.clone(), String::new()),
+        };
+        Service {
+            host,
+            text,
+            ttl: Some(ttl),
+        }
+    }
+}
+
+impl Manage for Entry {
+    fn is_managed(&self) -> bool {
+        self.owner.is_some()
+    }
+}
+
+impl Match for Entry {
+    fn matches(&self, other: &Self) -> bool {
+        // A name may have several values, each of which is its own key
+        self.kind.eq_ignore_ascii_case(&other.kind)
+            && self.name.eq_ignore_ascii_case(&other.name)
+            && self.content == other.content
+    }
+}
+
+impl Update for Entry {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = Entry::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other)
+    }
+}
+
+impl From<Record> for Entry {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = match kind.as_str() {
+            RECORD_KIND_CNAME => normalize_name(&value.content),
+            _ => value.content,
+        };
+        Self {
+            key: None,
+            name: normalize_name(&value.name.to_string()),
+            kind,
+            content,
+            owner: None,
+        }
+    }
+}
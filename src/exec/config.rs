@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// Shell command which applies the changeset it reads on stdin.
+    pub command: String,
+    /// Where the current records are kept between runs, so only changes are
+    /// sent. Without it, every record is sent as a create on each run.
+    pub state_path: Option<PathBuf>,
+}
@@ -0,0 +1,38 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.exec;
+in
+{
+  options.dnssync.frontends.exec = {
+    enable = lib.mkEnableOption "external command frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to hand records to the command for";
+    };
+    command = mkOption {
+      type = types.str;
+      example = "/etc/dnssync/apply-records";
+      description = "Shell command which reads the changes as JSON on stdin";
+    };
+    statePath = mkOption {
+      type = types.nullOr types.str;
+      default = "/var/lib/dnssync/exec-state.json";
+      description = "Where the current records are kept between runs. If null, every record is sent as a create on each run";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "exec";
+    systemd.services.dnssync.serviceConfig.StateDirectory = [ "dnssync" ];
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = lib.mkIf (cfg.statePath != null) [
+      (builtins.dirOf cfg.statePath)
+    ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_EXEC_DOMAIN" = cfg.domain;
+      "DNSSYNC_EXEC_COMMAND" = cfg.command;
+    } // lib.optionalAttrs (cfg.statePath != null) {
+      "DNSSYNC_EXEC_STATE_PATH" = cfg.statePath;
+    };
+  };
+}
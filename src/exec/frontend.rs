@@ -0,0 +1,255 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, diff_records, group_rrsets, read_to_string_or_empty, write_atomic,
+    FrontendSnafu, Record, Result,
+};
+
+use super::models::{Changes, JsonRecord, RecordSet, Request, UpdatedSet};
+
+pub const FRONTEND_NAME: &str = "Exec";
+
+pub struct Exec {
+    domain: String,
+    command: String,
+    state_path: Option<PathBuf>,
+}
+
+impl Exec {
+    /// Reads the records the last run left behind, if state is kept.
+    fn read_state(&self) -> Result<Vec<RecordSet>> {
+        let Some(path) = &self.state_path else {
+            return Ok(Vec::new());
+        };
+        let text = read_to_string_or_empty(path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read {}", path.display()),
+            })?;
+        let records = JsonRecord::parse(&text)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to parse {}", path.display()),
+            })?;
+        Ok(RecordSet::group(records))
+    }
+
+    fn write_state(&self, records: Vec<JsonRecord>) -> Result<()> {
+        let Some(path) = &self.state_path else {
+            return Ok(());
+        };
+        // Sorted the same way whether or not the command reported it
+        let text = JsonRecord::render(&RecordSet::flatten(&RecordSet::group(records)))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to serialize records",
+            })?;
+        write_atomic(path, &text)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", path.display()),
+            })
+    }
+
+    /// Runs the command with the request on stdin, returning the
+    /// records it reports on stdout, if any.
+    fn run(&self, request: &Request) -> Result<Option<Vec<JsonRecord>>> {
+        let input = serde_json::to_vec(request)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to serialize changes",
+            })?;
+
+        tracing::debug!(
+            frontend = FRONTEND_NAME,
+            command = self.command,
+            "Running command"
+        );
+        let mut child = Command::new("sh")
+            .args(["-c", &self.command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to run {}", self.command),
+            })?;
+
+        // Write from another thread, so a command which writes before it
+        // has read all of stdin can't fill its stdout pipe and deadlock
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child
+            .wait_with_output()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to run {}", self.command),
+            })?;
+        match writer.join().expect("stdin writer panicked") {
+            // The command doesn't have to read the changes
+            Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => {}
+            result => result.boxed_local().context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to write changes to the command",
+            })?,
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if !stderr.is_empty() {
+            tracing::debug!(frontend = FRONTEND_NAME, stderr = stderr, "Command output");
+        }
+        // Empty output means the command doesn't report its state
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let state = (!stdout.trim().is_empty()).then(|| JsonRecord::parse(&stdout));
+
+        if !output.status.success() {
+            // Keep what the command managed to apply, so the next run only retries the rest
+            if let Some(Ok(records)) = state {
+                self.write_state(records)?;
+            }
+            return Err(std::io::Error::other(stderr))
+                .boxed_local()
+                .context(FrontendSnafu {
+                    frontend: FRONTEND_NAME,
+                    message: format!("{} exited with {}", self.command, output.status),
+                });
+        }
+
+        state.transpose().boxed_local().context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: "Failed to parse records written by the command",
+        })
+    }
+}
+
+impl common::Frontend for Exec {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // The command is never run here, as it can't be told to change nothing
+        self.read_state()?;
+        match &self.state_path {
+            Some(path) => check_writable(path).boxed_local().context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", path.display()),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let current = self.read_state()?;
+        let diff = diff_records::<RecordSet>(current.clone(), group_rrsets(authority));
+
+        // Without state, the command is always run so it sees every record
+        if diff.len() == 0 && self.state_path.is_some() {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        for set in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = set.name,
+                kind = set.kind,
+                content = set.values.join(" "),
+                "Deleting record",
+            );
+        }
+        for set in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = set.name,
+                kind = set.kind,
+                content = set.values.join(" "),
+                "Updating record",
+            );
+        }
+        for set in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = set.name,
+                kind = set.kind,
+                content = set.values.join(" "),
+                "Creating record"
+            );
+        }
+
+        let records = RecordSet::flatten(&diff.apply(current.clone()));
+        let request = Request {
+            domain: &self.domain,
+            records: records.clone(),
+            changes: Changes {
+                create: &diff.create,
+                update: diff
+                    .update
+                    .iter()
+                    .map(|set| UpdatedSet {
+                        set,
+                        previous: current
+                            .iter()
+                            .find(|c| c.name == set.name && c.kind == set.kind)
+                            .map(|c| c.values.as_slice())
+                            .unwrap_or_default(),
+                    })
+                    .collect(),
+                delete: &diff.delete,
+            },
+        };
+
+        // The command may report what actually exists, otherwise it is
+        // trusted to have applied every change
+        match self.run(&request)? {
+            Some(state) => self.write_state(state),
+            None => self.write_state(records),
+        }
+    }
+}
+
+impl From<super::Config> for Exec {
+    fn from(value: super::Config) -> Self {
+        Self {
+            domain: value.domain,
+            command: value.command,
+            state_path: value.state_path,
+        }
+    }
+}
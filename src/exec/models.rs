@@ -0,0 +1,145 @@
+use crate::common::{Manage, Match, Record, Update};
+
+/// A record in the format read by the jsonfile backend, used for the
+/// desired records sent to the command and the state it reports back.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct JsonRecord {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+}
+
+/// All the values of a name and kind, so a name with several
+/// addresses is changed as a whole.
+#[derive(Clone, serde::Serialize)]
+pub(super) struct RecordSet {
+    pub name: String,
+    pub kind: String,
+    pub values: Vec<String>,
+}
+
+/// An updated record set, with the values it replaces.
+#[derive(serde::Serialize)]
+pub(super) struct UpdatedSet<'a> {
+    #[serde(flatten)]
+    pub set: &'a RecordSet,
+    pub previous: &'a [String],
+}
+
+#[derive(serde::Serialize)]
+pub(super) struct Changes<'a> {
+    pub create: &'a [RecordSet],
+    pub update: Vec<UpdatedSet<'a>>,
+    pub delete: &'a [RecordSet],
+}
+
+/// Written to the command's stdin.
+#[derive(serde::Serialize)]
+pub(super) struct Request<'a> {
+    pub domain: &'a str,
+    /// Every record of the domain once the changes are applied.
+    pub records: Vec<JsonRecord>,
+    pub changes: Changes<'a>,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+fn sorted(mut values: Vec<String>) -> Vec<String> {
+    values.sort();
+    values.dedup();
+    values
+}
+
+impl JsonRecord {
+    /// Parses a list of records, treating empty text as no records.
+    pub(super) fn parse(text: &str) -> serde_json::Result<Vec<Self>> {
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(text)
+    }
+
+    pub(super) fn render(records: &[Self]) -> serde_json::Result<String> {
+        let mut text = serde_json::to_string_pretty(records)?;
+        text.push('\n');
+        Ok(text)
+    }
+}
+
+impl RecordSet {
+    /// Groups records into record sets, keeping the order they first appear in.
+    pub(super) fn group(records: Vec<JsonRecord>) -> Vec<Self> {
+        let mut sets: Vec<Self> = Vec::with_capacity(records.len());
+        for record in records {
+            let name = normalize_name(&record.name);
+            let kind = record.kind.to_uppercase();
+            match sets.iter_mut().find(|s| s.name == name && s.kind == kind) {
+                Some(set) => set.values.push(record.content),
+                None => sets.push(Self {
+                    name,
+                    kind,
+                    values: vec![record.content],
+                }),
+            }
+        }
+        sets.into_iter()
+            .map(|set| Self {
+                values: sorted(set.values),
+                ..set
+            })
+            .collect()
+    }
+
+    /// Lists every value as its own record, sorted by name and kind.
+    pub(super) fn flatten(sets: &[Self]) -> Vec<JsonRecord> {
+        let mut sets: Vec<&Self> = sets.iter().collect();
+        sets.sort_by(|a, b| (&a.name, &a.kind).cmp(&(&b.name, &b.kind)));
+        sets.into_iter()
+            .flat_map(|set| {
+                set.values.iter().map(|value| JsonRecord {
+                    name: set.name.clone(),
+                    kind: set.kind.clone(),
+                    content: value.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Manage for RecordSet {
+    fn is_managed(&self) -> bool {
+        // The command is handed every record of the domain
+        true
+    }
+}
+
+impl Match for RecordSet {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RecordSet {
+    fn update(mut self, authority: Record) -> Self {
+        self.values = RecordSet::from(authority).values;
+        self
+    }
+}
+
+impl PartialEq for RecordSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.values == other.values
+    }
+}
+
+impl From<Record> for RecordSet {
+    fn from(value: Record) -> Self {
+        Self {
+            name: normalize_name(&value.name.to_string()),
+            kind: value.kind.to_uppercase(),
+            values: sorted(value.content.split('\n').map(String::from).collect()),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// An OctoDNS YAML zone file.
+    #[default]
+    Yaml,
+    /// A list of records, as read by the jsonfile backend.
+    Json,
+}
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// The file to write. It is entirely managed by DNSSync.
+    pub path: PathBuf,
+    #[serde(default)]
+    pub format: Format,
+    /// TTL written into YAML records. OctoDNS defaults to 3600 if unset.
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,41 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.export;
+in
+{
+  options.dnssync.frontends.export = {
+    enable = lib.mkEnableOption "OctoDNS YAML or JSON file export frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The zone to export records for";
+    };
+    path = mkOption {
+      type = types.str;
+      example = "/var/lib/dnssync/example.com.yaml";
+      description = "The file to write. Must be writable by the dnssync user";
+    };
+    format = mkOption {
+      type = types.enum [ "yaml" "json" ];
+      default = "yaml";
+      description = "Write an OctoDNS YAML zone file, or JSON as read by the jsonfile backend";
+    };
+    ttl = mkOption {
+      type = types.nullOr types.ints.unsigned;
+      default = null;
+      description = "TTL written into YAML records. OctoDNS defaults to 3600";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "export";
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.path) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_EXPORT_DOMAIN" = cfg.domain;
+      "DNSSYNC_EXPORT_PATH" = cfg.path;
+      "DNSSYNC_EXPORT_FORMAT" = cfg.format;
+    } // lib.optionalAttrs (cfg.ttl != null) {
+      "DNSSYNC_EXPORT_TTL" = toString cfg.ttl;
+    };
+  };
+}
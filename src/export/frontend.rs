@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, read_to_string_or_empty, write_atomic, FrontendSnafu, Record,
+    ResponseSnafu, Result,
+};
+
+use super::models::{is_supported, natural_cmp, quote, JsonRecord, Yaml};
+use super::Format;
+
+pub const FRONTEND_NAME: &str = "Export";
+
+/// The values of each kind of record, in order.
+type Kinds = Vec<(String, Vec<Yaml>)>;
+
+pub struct Export {
+    domain: String,
+    path: PathBuf,
+    format: Format,
+    ttl: Option<u32>,
+}
+
+impl Export {
+    /// The name of a record relative to the zone, which is empty at the apex.
+    fn relative_name(&self, name: &str) -> String {
+        match name == self.domain {
+            true => String::new(),
+            false => name
+                .strip_suffix(&format!(".{}", self.domain))
+                .unwrap_or(name)
+                .to_string(),
+        }
+    }
+
+    /// Renders the sorted records as an OctoDNS zone file.
+    fn render_yaml(&self, mut records: Vec<JsonRecord>) -> Result<String> {
+        // Keys are relative names, so the apex sorts first. The sort is stable,
+        // which keeps the kinds and values of each name in order.
+        records.sort_by(|a, b| {
+            natural_cmp(&self.relative_name(&a.name), &self.relative_name(&b.name))
+        });
+
+        // Group the values of each name and kind, and the kinds of each name
+        let mut names: Vec<(String, Kinds)> = Vec::new();
+        for record in records {
+            let value = Yaml::from_content(&record.kind, &record.content).ok_or_else(|| {
+                ResponseSnafu {
+                    message: format!(
+                        "Cannot export {} record {} with content {}",
+                        record.kind, record.name, record.content
+                    ),
+                }
+                .build()
+            })?;
+            let name = self.relative_name(&record.name);
+            if names.last().map(|(n, _)| n) != Some(&name) {
+                names.push((name, Vec::new()));
+            }
+            let kinds = &mut names.last_mut().expect("name was just pushed").1;
+            if kinds.last().map(|(k, _)| k) != Some(&record.kind) {
+                kinds.push((record.kind, Vec::new()));
+            }
+            kinds
+                .last_mut()
+                .expect("kind was just pushed")
+                .1
+                .push(value);
+        }
+
+        let zone = names
+            .into_iter()
+            .map(|(name, kinds)| {
+                let mut sets: Vec<Yaml> = kinds
+                    .into_iter()
+                    .map(|(kind, mut values)| {
+                        let mut set = Vec::with_capacity(3);
+                        if let Some(ttl) = self.ttl {
+                            set.push(("ttl".to_string(), Yaml::Scalar(ttl.to_string())));
+                        }
+                        set.push(("type".to_string(), Yaml::Scalar(kind)));
+                        match values.len() {
+                            1 => set.push(("value".to_string(), values.remove(0))),
+                            _ => set.push(("values".to_string(), Yaml::List(values))),
+                        }
+                        Yaml::Map(set)
+                    })
+                    .collect();
+                // A name with a single record is written without the list
+                let records = match sets.len() {
+                    1 => sets.remove(0),
+                    _ => Yaml::List(sets),
+                };
+                (quote(&name), records)
+            })
+            .collect();
+
+        let mut lines = vec!["---".to_string()];
+        lines.extend(Yaml::Map(zone).lines());
+        lines.push(String::new());
+        Ok(lines.join("\n"))
+    }
+
+    /// Renders the sorted records as a list the jsonfile backend can read.
+    fn render_json(&self, records: Vec<JsonRecord>) -> Result<String> {
+        let mut text = serde_json::to_string_pretty(&records)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to serialize records",
+            })?;
+        text.push('\n');
+        Ok(text)
+    }
+}
+
+impl common::Frontend for Export {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        check_writable(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.path.display()),
+            })
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let mut records: Vec<JsonRecord> = authority
+            .iter()
+            .filter(|r| {
+                // JSON can hold anything the jsonfile backend can read
+                let supported = self.format == Format::Json || is_supported(&r.kind);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .map(JsonRecord::from)
+            .collect();
+
+        // Everything is sorted, as OctoDNS requires of its keys, and for stable diffs
+        records.sort_by(|a, b| {
+            natural_cmp(&a.name, &b.name)
+                .then_with(|| natural_cmp(&a.kind, &b.kind))
+                .then_with(|| natural_cmp(&a.content, &b.content))
+        });
+        records.dedup_by(|a, b| a.name == b.name && a.kind == b.kind && a.content == b.content);
+        let count = records.len();
+
+        let rendered = match self.format {
+            Format::Yaml => self.render_yaml(records)?,
+            Format::Json => self.render_json(records)?,
+        };
+        let text = read_to_string_or_empty(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read {}", self.path.display()),
+            })?;
+
+        // The file is only written when it changes, so it can be committed as is
+        if rendered == text {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                records = count,
+                path = self.path.display().to_string(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            records = count,
+            path = self.path.display().to_string(),
+            "Applying changes",
+        );
+
+        write_atomic(&self.path, &rendered)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.path.display()),
+            })
+    }
+}
+
+impl From<super::Config> for Export {
+    fn from(value: super::Config) -> Self {
+        Self {
+            domain: value.domain.trim_end_matches('.').to_lowercase(),
+            path: value.path,
+            format: value.format,
+            ttl: value.ttl,
+        }
+    }
+}
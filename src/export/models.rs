@@ -0,0 +1,190 @@
+use std::cmp::Ordering;
+
+use crate::common::Record;
+
+/// Record kinds whose content is a domain name.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+/// Record kinds with a plain string value.
+const STRING_KINDS: [&str; 3] = ["A", "AAAA", "TXT"];
+
+/// Record kinds whose OctoDNS values are objects, and the fields
+/// of each value, in presentation order.
+const VALUE_FIELDS: [(&str, &[&str]); 3] = [
+    ("MX", &["preference", "exchange"]),
+    ("SRV", &["priority", "weight", "port", "target"]),
+    ("CAA", &["flags", "tag", "value"]),
+];
+
+/// Fields which OctoDNS expects as numbers.
+const NUMBER_FIELDS: [&str; 5] = ["preference", "priority", "weight", "port", "flags"];
+
+/// Fields holding a domain name, which OctoDNS expects fully qualified.
+const TARGET_FIELDS: [&str; 2] = ["exchange", "target"];
+
+fn value_fields(kind: &str) -> Option<&'static [&'static str]> {
+    VALUE_FIELDS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, fields)| *fields)
+}
+
+pub(super) fn is_supported(kind: &str) -> bool {
+    let kind = kind.to_uppercase();
+    STRING_KINDS.contains(&kind.as_str())
+        || NAME_KINDS.contains(&kind.as_str())
+        || value_fields(&kind).is_some()
+}
+
+/// A record in the format read by the jsonfile backend.
+#[derive(serde::Serialize)]
+pub(super) struct JsonRecord {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+}
+
+impl From<&Record> for JsonRecord {
+    fn from(value: &Record) -> Self {
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind: value.kind.to_uppercase(),
+            content: value.content.clone(),
+        }
+    }
+}
+
+enum Chunk<'a> {
+    Number(&'a str),
+    Text(&'a str),
+}
+
+/// Splits a string into runs of digits and runs of anything else.
+fn chunks(value: &str) -> Vec<Chunk<'_>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut digits = false;
+    for (i, c) in value.char_indices() {
+        if i > start && c.is_ascii_digit() != digits {
+            let chunk = &value[start..i];
+            chunks.push(match digits {
+                true => Chunk::Number(chunk),
+                false => Chunk::Text(chunk),
+            });
+            start = i;
+        }
+        if i == start {
+            digits = c.is_ascii_digit();
+        }
+    }
+    if start < value.len() {
+        let chunk = &value[start..];
+        chunks.push(match digits {
+            true => Chunk::Number(chunk),
+            false => Chunk::Text(chunk),
+        });
+    }
+    chunks
+}
+
+/// Compares strings with runs of digits ordered by their value, e.g. host9
+/// before host10, which is the order OctoDNS requires of YAML keys.
+pub(super) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (a, b) = (chunks(a), chunks(b));
+    for pair in a.iter().zip(b.iter()) {
+        let ordering = match pair {
+            (Chunk::Number(x), Chunk::Number(y)) => {
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (Chunk::Text(x), Chunk::Text(y)) => x.cmp(y),
+            (Chunk::Number(_), Chunk::Text(_)) => Ordering::Less,
+            (Chunk::Text(_), Chunk::Number(_)) => Ordering::Greater,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// A YAML node, with scalars already rendered.
+pub(super) enum Yaml {
+    Scalar(String),
+    Map(Vec<(String, Yaml)>),
+    List(Vec<Yaml>),
+}
+
+/// Quotes a string, so it is always read back as a string.
+pub(super) fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn fqdn(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+impl Yaml {
+    /// Renders the node as block YAML lines, without indentation.
+    pub(super) fn lines(&self) -> Vec<String> {
+        match self {
+            Yaml::Scalar(value) => vec![value.clone()],
+            Yaml::Map(entries) if entries.is_empty() => vec!["{}".to_string()],
+            Yaml::Map(entries) => entries
+                .iter()
+                .flat_map(|(key, value)| match value {
+                    Yaml::Scalar(value) => vec![format!("{key}: {value}")],
+                    value => {
+                        let mut lines = vec![format!("{key}:")];
+                        lines.extend(value.lines().into_iter().map(|l| format!("  {l}")));
+                        lines
+                    }
+                })
+                .collect(),
+            Yaml::List(items) => items
+                .iter()
+                .flat_map(|item| {
+                    // The first line of each item follows the dash
+                    item.lines()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, line)| match i {
+                            0 => format!("- {line}"),
+                            _ => format!("  {line}"),
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    /// Converts the content of a record to its OctoDNS value.
+    /// Returns None if the content can't be represented.
+    pub(super) fn from_content(kind: &str, content: &str) -> Option<Self> {
+        if NAME_KINDS.contains(&kind) {
+            return Some(Yaml::Scalar(quote(&fqdn(content))));
+        }
+        if kind == "TXT" {
+            // OctoDNS requires semicolons to be escaped
+            return Some(Yaml::Scalar(quote(&content.replace(';', "\\;"))));
+        }
+        let Some(fields) = value_fields(kind) else {
+            return Some(Yaml::Scalar(quote(content)));
+        };
+
+        // The last field takes the rest of the content, as CAA values may contain spaces
+        let mut parts = content.splitn(fields.len(), ' ');
+        let mut entries = Vec::with_capacity(fields.len());
+        for field in fields.iter() {
+            let part = parts.next().filter(|p| !p.is_empty())?;
+            let value = match *field {
+                f if NUMBER_FIELDS.contains(&f) => part.parse::<u64>().ok()?.to_string(),
+                f if TARGET_FIELDS.contains(&f) => quote(&fqdn(part)),
+                // CAA values are quoted in presentation format, but not in OctoDNS
+                _ => quote(part.trim_matches('"')),
+            };
+            entries.push((field.to_string(), Yaml::Scalar(value)));
+        }
+        entries.sort_by(|(a, _), (b, _)| natural_cmp(a, b));
+        Some(Yaml::Map(entries))
+    }
+}
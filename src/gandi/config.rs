@@ -0,0 +1,11 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    /// A personal access token with the "Manage domain name technical configurations" permission.
+    pub api_key: String,
+    pub domain: String,
+    pub instance_id: String,
+    /// Override the API base URL, e.g. to use the sandbox.
+    pub base_url: Option<url::Url>,
+    pub registry_prefix: Option<String>,
+    pub ttl: Option<u32>,
+}
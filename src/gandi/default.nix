@@ -0,0 +1,46 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.gandi;
+in
+{
+  options.dnssync.frontends.gandi = {
+    enable = lib.mkEnableOption "Gandi LiveDNS frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The Gandi domain to manage";
+    };
+    keyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the Gandi personal access token. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    baseUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "Override the Gandi LiveDNS API base URL, e.g. to use the sandbox";
+    };
+    ttl = mkOption {
+      type = types.ints.between 300 2592000;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "gandi";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_GANDI_DOMAIN" = cfg.domain;
+      "DNSSYNC_GANDI_API_KEY" = "@${cfg.keyFile}";
+      "DNSSYNC_GANDI_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_GANDI_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.baseUrl != null) {
+      "DNSSYNC_GANDI_BASE_URL" = cfg.baseUrl;
+    };
+  };
+}
@@ -0,0 +1,219 @@
+use serde::de::{DeserializeOwned, IgnoredAny};
+use snafu::prelude::*;
+
+use crate::common::{
+    self, group_rrsets, key_file_or_string, request_error, FrontendSnafu, Record, Result,
+    TxtRegistry,
+};
+
+use super::models::{APIError, APIRRSet, RRSetValues, RecordSet, DEFAULT_TTL};
+
+pub const API_BASE_URL: &str = "https://api.gandi.net/v5/livedns";
+
+pub const FRONTEND_NAME: &str = "Gandi";
+
+pub struct Gandi {
+    api_key: String,
+    base_url: url::Url,
+    domain: String,
+    registry: TxtRegistry,
+    ttl: u32,
+}
+
+impl Gandi {
+    fn api_url(&self, path: &[&str]) -> url::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .push("domains")
+            .push(&self.domain)
+            .extend(path);
+        url
+    }
+
+    fn rrset_url(&self, record: &RecordSet) -> url::Url {
+        self.api_url(&["records", &record.relative_name(&self.domain), &record.kind])
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        url: url::Url,
+        body: Option<&RRSetValues>,
+    ) -> Result<ureq::Response> {
+        tracing::debug!(
+            url = url.as_str(),
+            method = method,
+            frontend = "gandi",
+            "Sending request"
+        );
+        let req = ureq::request(method, url.as_str())
+            .set("Authorization", &format!("Bearer {}", self.api_key));
+        match body {
+            Some(body) => req.send_json(body),
+            None => req.call(),
+        }
+        .map_err(|err| request_error(err, url.as_str(), method, APIError::detail))
+    }
+
+    fn api_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        url: url::Url,
+        body: Option<&RRSetValues>,
+    ) -> Result<T> {
+        self.send(method, url, body)?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    fn read_records(&self) -> Result<Vec<RecordSet>> {
+        let rrsets: Vec<APIRRSet> = self.api_call("GET", self.api_url(&["records"]), None)?;
+        Ok(rrsets
+            .into_iter()
+            .map(|rrset| RecordSet::from_api(rrset, &self.domain))
+            .collect())
+    }
+
+    /// Replaces the whole RRset, creating it if needed.
+    fn put_rrset(&self, record: &RecordSet) -> Result<()> {
+        self.api_call::<IgnoredAny>("PUT", self.rrset_url(record), Some(&record.to_api()))
+            .map(|_| ())
+    }
+
+    fn delete_rrset(&self, record: &RecordSet) -> Result<()> {
+        // Deletes respond with no content
+        self.send("DELETE", self.rrset_url(record), None)
+            .map(|_| ())
+    }
+}
+
+impl common::Frontend for Gandi {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // Fails if the token is invalid, lacks LiveDNS permissions
+        // or the domain isn't using LiveDNS.
+        self.api_call::<IgnoredAny>("GET", self.api_url(&[]), None)
+            .map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let current = self.read_records()?;
+        let mut diff = self.registry.diff(current, group_rrsets(authority));
+        for record in diff.records.create.iter_mut() {
+            record.record.ttl = self.ttl;
+        }
+        for record in diff.create_owners.iter_mut() {
+            record.ttl = self.ttl;
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.records.create.len(),
+                update = diff.records.update.len(),
+                delete = diff.records.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.records.create.len(),
+            update = diff.records.update.len(),
+            delete = diff.records.delete.len(),
+            "Applying changes",
+        );
+
+        // Deletes first - to avoid conflicts with created records.
+        for record in diff.records.delete.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            self.delete_rrset(record)?;
+        }
+        for record in diff.delete_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Deleting ownership record",
+            );
+            self.delete_rrset(record)?;
+        }
+
+        for record in diff.records.update.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+            self.put_rrset(record)?;
+        }
+
+        for record in diff.records.create.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            self.put_rrset(record)?;
+        }
+        for record in diff.create_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Creating ownership record",
+            );
+            self.put_rrset(record)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for Gandi {
+    fn from(value: super::Config) -> Self {
+        let api_key = key_file_or_string(value.api_key, FRONTEND_NAME.into()).unwrap();
+
+        let base_url = value
+            .base_url
+            .unwrap_or_else(|| url::Url::parse(API_BASE_URL).unwrap());
+
+        Self {
+            api_key,
+            base_url,
+            domain: value.domain.trim_end_matches('.').to_lowercase(),
+            registry: TxtRegistry::new(value.registry_prefix, value.instance_id),
+            // LiveDNS refuses TTLs below its minimum
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL).max(DEFAULT_TTL),
+        }
+    }
+}
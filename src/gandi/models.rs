@@ -0,0 +1,170 @@
+use crate::common::{
+    join_values, normalise_content, txt_from_zone_value, txt_to_zone_value, Match, Record, Update,
+    RECORD_KIND_TXT,
+};
+
+/// The minimum TTL LiveDNS allows.
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+/// The name LiveDNS uses for the zone apex.
+const APEX: &str = "@";
+
+/// Record kinds whose value is a domain name, relative to the zone unless it ends in a dot.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub message: Option<String>,
+    #[serde(default)]
+    pub errors: Vec<ErrorDetail>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ErrorDetail {
+    pub name: String,
+    pub description: String,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        match self.errors.is_empty() {
+            true => self.message,
+            false => Some(
+                self.errors
+                    .iter()
+                    .map(|e| format!("{}: {}", e.name, e.description))
+                    .collect::<Vec<String>>()
+                    .join("; "),
+            ),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIRRSet {
+    /// Relative to the zone.
+    pub rrset_name: String,
+    pub rrset_type: String,
+    pub rrset_ttl: Option<u32>,
+    pub rrset_values: Vec<String>,
+}
+
+/// The body to replace an RRset with.
+#[derive(serde::Serialize)]
+pub(super) struct RRSetValues {
+    pub rrset_values: Vec<String>,
+    pub rrset_ttl: u32,
+}
+
+/// An RRset with its name and values made absolute,
+/// and its values as one record separated by newlines.
+#[derive(Clone)]
+pub(super) struct RecordSet {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: u32,
+}
+
+impl RecordSet {
+    pub(super) fn from_api(rrset: APIRRSet, zone: &str) -> Self {
+        let absolute = |name: &str| match name {
+            APEX => zone.to_string(),
+            name => format!("{name}.{zone}"),
+        };
+        let content = join_values(
+            rrset
+                .rrset_values
+                .iter()
+                .map(|value| match rrset.rrset_type.as_str() {
+                    RECORD_KIND_TXT => txt_from_zone_value(value),
+                    kind if NAME_KINDS.contains(&kind) => match value.strip_suffix('.') {
+                        Some(name) => name.to_lowercase(),
+                        None => absolute(value).to_lowercase(),
+                    },
+                    _ => value.clone(),
+                })
+                .collect(),
+        );
+        Self {
+            name: absolute(&rrset.rrset_name).to_lowercase(),
+            kind: rrset.rrset_type,
+            content,
+            ttl: rrset.rrset_ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+
+    /// The name relative to the zone, as used in API paths.
+    pub(super) fn relative_name(&self, zone: &str) -> String {
+        match self.name.strip_suffix(zone) {
+            Some("") => APEX.to_string(),
+            Some(name) => name.trim_end_matches('.').to_string(),
+            None => self.name.clone(),
+        }
+    }
+
+    pub(super) fn to_api(&self) -> RRSetValues {
+        let rrset_values = self
+            .content
+            .split('\n')
+            .map(|content| match self.kind.as_str() {
+                RECORD_KIND_TXT => txt_to_zone_value(content),
+                kind if NAME_KINDS.contains(&kind) => format!("{content}."),
+                _ => content.to_string(),
+            })
+            .collect();
+        RRSetValues {
+            rrset_values,
+            rrset_ttl: self.ttl,
+        }
+    }
+}
+
+impl Match for RecordSet {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RecordSet {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = RecordSet::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for RecordSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<RecordSet> for Record {
+    fn from(value: RecordSet) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for RecordSet {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = join_values(
+            value
+                .content
+                .split('\n')
+                .map(|content| normalise_content(&kind, content))
+                .collect(),
+        );
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: DEFAULT_TTL,
+        }
+    }
+}
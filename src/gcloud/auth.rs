@@ -0,0 +1,97 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ring::rand::SystemRandom;
+use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+
+pub(super) const SCOPE: &str = "https://www.googleapis.com/auth/ndev.clouddns.readwrite";
+pub(super) const GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+/// The longest lifetime Google allows for an assertion.
+const ASSERTION_LIFETIME: Duration = Duration::from_secs(3600);
+
+fn default_token_uri() -> String {
+    DEFAULT_TOKEN_URI.to_string()
+}
+
+/// The fields of a service account JSON key which are needed to authenticate.
+#[derive(serde::Deserialize)]
+pub(super) struct ServiceAccount {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+    pub project_id: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct Header {
+    alg: &'static str,
+    typ: &'static str,
+}
+
+#[derive(serde::Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+/// Parses the PEM encoded PKCS#8 private key of a service account.
+pub(super) fn signing_key(pem: &str) -> Result<RsaKeyPair, String> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(body)
+        .map_err(|err| format!("Private key is not valid PEM: {err}"))?;
+    RsaKeyPair::from_pkcs8(&der).map_err(|err| format!("Private key was rejected: {err}"))
+}
+
+/// Builds a signed JWT asserting the service account's identity, to exchange for an access token.
+/// See https://developers.google.com/identity/protocols/oauth2/service-account#httprest
+pub(super) fn assertion(
+    account: &ServiceAccount,
+    key: &RsaKeyPair,
+    now: SystemTime,
+) -> Result<String, String> {
+    let iat = now
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err.to_string())?
+        .as_secs();
+    let header = Header {
+        alg: "RS256",
+        typ: "JWT",
+    };
+    let claims = Claims {
+        iss: &account.client_email,
+        scope: SCOPE,
+        aud: &account.token_uri,
+        iat,
+        exp: iat + ASSERTION_LIFETIME.as_secs(),
+    };
+    let header = serde_json::to_vec(&header).map_err(|err| err.to_string())?;
+    let claims = serde_json::to_vec(&claims).map_err(|err| err.to_string())?;
+    let message = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header),
+        URL_SAFE_NO_PAD.encode(claims)
+    );
+
+    let mut signature = vec![0; key.public().modulus_len()];
+    key.sign(
+        &RSA_PKCS1_SHA256,
+        &SystemRandom::new(),
+        message.as_bytes(),
+        &mut signature,
+    )
+    .map_err(|_| "Failed to sign assertion".to_string())?;
+    Ok(format!("{message}.{}", URL_SAFE_NO_PAD.encode(signature)))
+}
@@ -0,0 +1,15 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    /// The service account JSON key, or @ followed by a path to read it from.
+    pub credentials: String,
+    /// Defaults to the project of the service account.
+    pub project: Option<String>,
+    /// The name of the managed zone, which is not its DNS name.
+    pub managed_zone: String,
+    pub domain: String,
+    pub instance_id: String,
+    /// Override the API base URL, e.g. to use a proxy.
+    pub base_url: Option<url::Url>,
+    pub registry_prefix: Option<String>,
+    pub ttl: Option<u32>,
+}
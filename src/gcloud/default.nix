@@ -0,0 +1,51 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.gcloud;
+in
+{
+  options.dnssync.frontends.gcloud = {
+    enable = lib.mkEnableOption "Google Cloud DNS frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The DNS name of the managed zone";
+    };
+    managedZone = mkOption {
+      type = types.str;
+      description = "The name of the managed zone in Cloud DNS";
+    };
+    project = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "The project owning the managed zone. Defaults to the project of the service account";
+    };
+    credentialsFile = mkOption {
+      type = types.path;
+      description = "Path to a service account JSON key file. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "gcloud";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_GCLOUD_DOMAIN" = cfg.domain;
+      "DNSSYNC_GCLOUD_MANAGED_ZONE" = cfg.managedZone;
+      "DNSSYNC_GCLOUD_CREDENTIALS" = "@${cfg.credentialsFile}";
+      "DNSSYNC_GCLOUD_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_GCLOUD_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.project != null) {
+      "DNSSYNC_GCLOUD_PROJECT" = cfg.project;
+    };
+  };
+}
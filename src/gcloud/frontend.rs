@@ -0,0 +1,309 @@
+use std::time::SystemTime;
+
+use ring::signature::RsaKeyPair;
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, group_rrsets, key_file_or_string, request_error, AccessToken, ConfigSnafu, FrontendSnafu,
+    Record, ResponseSnafu, Result, TxtRegistry,
+};
+
+use super::auth::{assertion, signing_key, ServiceAccount, GRANT_TYPE};
+use super::models::{
+    APIError, Change, ChangeResponse, ManagedZone, RRSetsResponse, RecordSet, DEFAULT_TTL,
+};
+
+pub const API_BASE_URL: &str = "https://dns.googleapis.com/dns/v1";
+
+pub const FRONTEND_NAME: &str = "GoogleCloudDNS";
+
+pub struct GoogleCloudDNS {
+    account: ServiceAccount,
+    key: RsaKeyPair,
+    token: Option<AccessToken>,
+    base_url: url::Url,
+    project: String,
+    managed_zone: String,
+    domain: String,
+    registry: TxtRegistry,
+    ttl: u32,
+}
+
+impl GoogleCloudDNS {
+    /// Exchanges a signed assertion for an access token,
+    /// unless the current token is still fresh.
+    fn authorize(&mut self) -> Result<()> {
+        if self.token.as_ref().is_some_and(AccessToken::is_fresh) {
+            return Ok(());
+        }
+
+        let jwt = assertion(&self.account, &self.key, SystemTime::now())
+            .map_err(|message| ResponseSnafu { message }.build())?;
+        let token = AccessToken::request(
+            FRONTEND_NAME,
+            &self.account.token_uri,
+            &[("grant_type", GRANT_TYPE), ("assertion", &jwt)],
+        )?;
+        self.token = Some(token);
+        Ok(())
+    }
+
+    fn zone_url(&self, path: &[&str]) -> url::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .extend(&[
+                "projects",
+                &self.project,
+                "managedZones",
+                &self.managed_zone,
+            ])
+            .extend(path);
+        url
+    }
+
+    fn request(&self, method: &str, url: &url::Url) -> ureq::Request {
+        tracing::debug!(
+            url = url.as_str(),
+            method = method,
+            frontend = "gcloud",
+            "Sending request"
+        );
+        let token = self.token.as_ref().map(|t| t.value.as_str());
+        ureq::request(method, url.as_str()).set(
+            "Authorization",
+            &format!("Bearer {}", token.unwrap_or_default()),
+        )
+    }
+
+    fn api_call<T: DeserializeOwned>(&self, url: url::Url) -> Result<T> {
+        self.request("GET", &url)
+            .call()
+            .map_err(|err| request_error(err, url.as_str(), "GET", APIError::detail))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    fn read_records(&self) -> Result<Vec<RecordSet>> {
+        let mut records = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut url = self.zone_url(&["rrsets"]);
+            if let Some(page_token) = &page_token {
+                url.query_pairs_mut().append_pair("pageToken", page_token);
+            }
+            let resp: RRSetsResponse = self.api_call(url)?;
+
+            for rrset in resp.rrsets {
+                let (name, kind) = (rrset.name.clone(), rrset.kind.clone());
+                match RecordSet::from_api(rrset) {
+                    Some(record) => records.push(record),
+                    None => tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = name,
+                        kind = kind,
+                        "Skipping routing policy record set"
+                    ),
+                }
+            }
+
+            match resp.next_page_token {
+                Some(next) => page_token = Some(next),
+                None => return Ok(records),
+            }
+        }
+    }
+
+    fn change(&self, change: Change) -> Result<()> {
+        let url = self.zone_url(&["changes"]);
+        let resp: ChangeResponse = self
+            .request("POST", &url)
+            .send_json(change)
+            .map_err(|err| request_error(err, url.as_str(), "POST", APIError::detail))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })?;
+        tracing::debug!(
+            frontend = FRONTEND_NAME,
+            change_id = resp.id,
+            status = resp.status,
+            "Applied changes"
+        );
+        Ok(())
+    }
+}
+
+impl common::Frontend for GoogleCloudDNS {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.authorize()?;
+        let zone: ManagedZone = self.api_call(self.zone_url(&[]))?;
+        let dns_name = format!("{}.", self.domain.trim_end_matches('.'));
+        if !zone.dns_name.eq_ignore_ascii_case(&dns_name) {
+            return ResponseSnafu {
+                message: format!(
+                    "Managed zone {} is for {}, not {}",
+                    zone.name, zone.dns_name, dns_name
+                ),
+            }
+            .fail();
+        }
+        self.read_records().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        self.authorize()?;
+        let current = self.read_records()?;
+        let mut diff = self.registry.diff(current, group_rrsets(authority));
+        for record in diff.records.create.iter_mut() {
+            record.record.ttl = self.ttl;
+        }
+        for record in diff.create_owners.iter_mut() {
+            record.ttl = self.ttl;
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.records.create.len(),
+                update = diff.records.update.len(),
+                delete = diff.records.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.records.create.len(),
+            update = diff.records.update.len(),
+            delete = diff.records.delete.len(),
+            "Applying changes",
+        );
+
+        // Deletions must match the RRsets in the zone exactly,
+        // so a changed RRset is deleted as it was and added as it should be.
+        let mut change = Change {
+            additions: Vec::with_capacity(diff.len()),
+            deletions: Vec::with_capacity(diff.len()),
+        };
+
+        for record in diff.records.delete.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            change.deletions.push(record.current());
+        }
+        for record in diff.delete_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Deleting ownership record",
+            );
+            change.deletions.push(record.current());
+        }
+
+        for record in diff.records.update.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+            change.deletions.push(record.current());
+            change.additions.push(record.desired());
+        }
+
+        for record in diff.records.create.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            change.additions.push(record.desired());
+        }
+        for record in diff.create_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Creating ownership record",
+            );
+            change.additions.push(record.desired());
+        }
+
+        self.change(change)
+    }
+}
+
+impl From<super::Config> for GoogleCloudDNS {
+    fn from(value: super::Config) -> Self {
+        let credentials = key_file_or_string(value.credentials, FRONTEND_NAME.into()).unwrap();
+        let config_error = |message: String| {
+            ConfigSnafu {
+                message,
+                prefix: FRONTEND_NAME,
+            }
+            .build()
+        };
+
+        let account: ServiceAccount = serde_json::from_str(&credentials)
+            .map_err(|err| config_error(format!("Invalid service account key: {err}")))
+            .unwrap();
+        let key = signing_key(&account.private_key)
+            .map_err(config_error)
+            .unwrap();
+        let project = value
+            .project
+            .or_else(|| account.project_id.clone())
+            .ok_or_else(|| {
+                config_error("No project set, and none in the service account key".into())
+            })
+            .unwrap();
+
+        let base_url = value
+            .base_url
+            .unwrap_or_else(|| url::Url::parse(API_BASE_URL).unwrap());
+
+        Self {
+            account,
+            key,
+            token: None,
+            base_url,
+            project,
+            managed_zone: value.managed_zone,
+            domain: value.domain,
+            registry: TxtRegistry::new(value.registry_prefix, value.instance_id),
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
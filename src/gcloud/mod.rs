@@ -0,0 +1,7 @@
+mod auth;
+mod config;
+mod frontend;
+mod models;
+
+pub use config::*;
+pub use frontend::*;
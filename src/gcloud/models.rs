@@ -0,0 +1,202 @@
+use crate::common::{
+    join_values, normalise_content, txt_from_zone_value, txt_to_zone_value, Match, Record, Update,
+    RECORD_KIND_TXT,
+};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+/// Record kinds whose data is a domain name.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+/// Record kinds whose data ends with a domain name.
+const TARGET_KINDS: [&str; 2] = ["MX", "SRV"];
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub error: ErrorDetail,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ErrorDetail {
+    pub message: String,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        Some(self.error.message)
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ManagedZone {
+    pub name: String,
+    pub dns_name: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RRSetsResponse {
+    #[serde(default)]
+    pub rrsets: Vec<ResourceRecordSet>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ResourceRecordSet {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub ttl: u32,
+    #[serde(default)]
+    pub rrdatas: Vec<String>,
+    // Routing policy record sets are never managed
+    #[serde(skip_serializing)]
+    pub routing_policy: Option<serde::de::IgnoredAny>,
+}
+
+/// A change to a zone, which Cloud DNS applies atomically.
+#[derive(serde::Serialize)]
+pub(super) struct Change {
+    pub additions: Vec<ResourceRecordSet>,
+    pub deletions: Vec<ResourceRecordSet>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ChangeResponse {
+    pub id: String,
+    pub status: String,
+}
+
+/// Converts a record value in zone file format to DNSSync content.
+fn value_to_content(kind: &str, value: &str) -> String {
+    if kind == RECORD_KIND_TXT {
+        return txt_from_zone_value(value);
+    }
+    normalise_content(kind, value)
+}
+
+/// Converts DNSSync content to a record value, with names fully qualified.
+fn content_to_value(kind: &str, content: &str) -> String {
+    if kind == RECORD_KIND_TXT {
+        return txt_to_zone_value(content);
+    }
+    if NAME_KINDS.contains(&kind) || TARGET_KINDS.contains(&kind) {
+        return format!("{content}.");
+    }
+    content.to_string()
+}
+
+/// An RRset, with its values as one record separated by newlines.
+#[derive(Clone)]
+pub(super) struct RecordSet {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: u32,
+    // The values currently in the zone, as deleting an RRset
+    // requires them exactly. Empty for RRsets not yet created.
+    pub rrdatas: Vec<String>,
+}
+
+impl RecordSet {
+    /// Returns None for RRsets which cannot be represented as a record.
+    pub(super) fn from_api(rrset: ResourceRecordSet) -> Option<Self> {
+        if rrset.routing_policy.is_some() {
+            return None;
+        }
+        let content = join_values(
+            rrset
+                .rrdatas
+                .iter()
+                .map(|value| value_to_content(&rrset.kind, value))
+                .collect(),
+        );
+        Some(Self {
+            name: rrset.name.trim_end_matches('.').to_lowercase(),
+            kind: rrset.kind,
+            content,
+            ttl: rrset.ttl,
+            rrdatas: rrset.rrdatas,
+        })
+    }
+
+    /// The RRset as it should be once written.
+    pub(super) fn desired(&self) -> ResourceRecordSet {
+        self.to_api(
+            self.content
+                .split('\n')
+                .map(|content| content_to_value(&self.kind, content))
+                .collect(),
+        )
+    }
+
+    /// The RRset as it currently is in the zone.
+    pub(super) fn current(&self) -> ResourceRecordSet {
+        match self.rrdatas.is_empty() {
+            true => self.desired(),
+            false => self.to_api(self.rrdatas.clone()),
+        }
+    }
+
+    fn to_api(&self, rrdatas: Vec<String>) -> ResourceRecordSet {
+        ResourceRecordSet {
+            name: format!("{}.", self.name),
+            kind: self.kind.clone(),
+            ttl: self.ttl,
+            rrdatas,
+            routing_policy: None,
+        }
+    }
+}
+
+impl Match for RecordSet {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RecordSet {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = RecordSet::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for RecordSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<RecordSet> for Record {
+    fn from(value: RecordSet) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for RecordSet {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = join_values(
+            value
+                .content
+                .split('\n')
+                .map(|content| normalise_content(&kind, content))
+                .collect(),
+        );
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: DEFAULT_TTL,
+            rrdatas: Vec::new(),
+        }
+    }
+}
@@ -53,15 +53,15 @@ impl Backend {
     }
 }
 
-impl common::Backend for Backend {
-    fn read_records(&self) -> Result<Vec<Record>> {
+impl Backend {
+    fn get_machines(&self) -> Result<MachinesResponse> {
         tracing::debug!(
             url = self.machines_url.as_str(),
             method = "GET",
             backend = "headscale",
             "Sending request"
         );
-        let response: MachinesResponse = ureq::get(self.machines_url.as_str())
+        ureq::get(self.machines_url.as_str())
             .set("Authorization", &format!("Bearer {}", self.api_key))
             .call()
             .context(RequestSnafu {
@@ -73,7 +73,21 @@ impl common::Backend for Backend {
             .context(BackendSnafu {
                 backend: BACKEND_NAME,
                 message: "Failed to deserialize response",
-            })?;
+            })
+    }
+}
+
+impl common::Backend for Backend {
+    fn get_name(&self) -> &str {
+        BACKEND_NAME
+    }
+
+    fn check(&self) -> Result<()> {
+        self.get_machines().map(|_| ())
+    }
+
+    fn read_records(&self) -> Result<Vec<Record>> {
+        let response = self.get_machines()?;
 
         let mut records = Vec::new();
         for machine in response.machines {
@@ -0,0 +1,46 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.hetzner;
+in
+{
+  options.dnssync.frontends.hetzner = {
+    enable = lib.mkEnableOption "Hetzner DNS frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The name of the Hetzner DNS zone to manage";
+    };
+    keyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the Hetzner DNS API token. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    baseUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      description = "Override the Hetzner DNS API base URL, e.g. to use a proxy";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "hetzner";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_HETZNER_DOMAIN" = cfg.domain;
+      "DNSSYNC_HETZNER_API_KEY" = "@${cfg.keyFile}";
+      "DNSSYNC_HETZNER_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_HETZNER_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.baseUrl != null) {
+      "DNSSYNC_HETZNER_BASE_URL" = cfg.baseUrl;
+    };
+  };
+}
@@ -0,0 +1,313 @@
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, key_file_or_string, request_error, FrontendSnafu, Record, ResponseSnafu, Result,
+    TxtRegistry,
+};
+
+use super::models::{
+    APIError, APIRecord, BulkCreateResponse, BulkRequest, BulkUpdateResponse, DNSRecord,
+    RecordsResponse, ZonesResponse, DEFAULT_TTL,
+};
+
+pub const API_BASE_URL: &str = "https://dns.hetzner.com/api/v1";
+
+pub const FRONTEND_NAME: &str = "Hetzner";
+
+const RECORDS_PER_PAGE: usize = 100;
+
+/// The zone ID and name.
+#[derive(Clone)]
+struct ZoneInfo {
+    id: String,
+    name: String,
+}
+
+pub struct Hetzner {
+    api_key: String,
+    base_url: String,
+    domain: String,
+    registry: TxtRegistry,
+    ttl: u32,
+    zone: Option<ZoneInfo>,
+}
+
+impl Hetzner {
+    fn api_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(&str, &str)],
+        body: Option<impl serde::Serialize>,
+    ) -> Result<T> {
+        tracing::debug!(
+            url = url,
+            method = method,
+            frontend = "hetzner",
+            "Sending request"
+        );
+        let mut req = ureq::request(method, url)
+            .set("Auth-API-Token", &self.api_key)
+            .set("Content-Type", "application/json; charset=utf-8");
+        for (key, value) in query {
+            req = req.query(key, value);
+        }
+        match body {
+            Some(body) => req.send_json(body),
+            None => req.call(),
+        }
+        .map_err(|err| request_error(err, url, method, APIError::detail))?
+        .into_json()
+        .boxed_local()
+        .context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: "Failed to deserialize response",
+        })
+    }
+
+    fn get_zone(&mut self) -> Result<ZoneInfo> {
+        if let Some(zone) = &self.zone {
+            return Ok(zone.clone());
+        }
+
+        let url = format!("{}/zones", self.base_url);
+        let resp: ZonesResponse =
+            self.api_call("GET", &url, &[("name", &self.domain)], None::<()>)?;
+
+        let zone = resp
+            .zones
+            .into_iter()
+            .find(|zone| zone.name.eq_ignore_ascii_case(&self.domain))
+            .context(ResponseSnafu {
+                message: format!("Failed to find a zone ID for domain {}", self.domain),
+            })?;
+
+        let zone = ZoneInfo {
+            id: zone.id,
+            name: zone.name.to_lowercase(),
+        };
+        self.zone = Some(zone.clone());
+        Ok(zone)
+    }
+
+    fn read_records(&self, zone: &ZoneInfo) -> Result<Vec<DNSRecord>> {
+        let url = format!("{}/records", self.base_url);
+        let per_page = RECORDS_PER_PAGE.to_string();
+        let mut records = Vec::new();
+        let mut page = 1;
+        loop {
+            let resp: RecordsResponse = self.api_call(
+                "GET",
+                &url,
+                &[
+                    ("zone_id", &zone.id),
+                    ("page", &page.to_string()),
+                    ("per_page", &per_page),
+                ],
+                None::<()>,
+            )?;
+            records.extend(
+                resp.records
+                    .into_iter()
+                    .map(|r| DNSRecord::from_api(r, &zone.name)),
+            );
+
+            match resp.meta {
+                Some(meta) if meta.pagination.page < meta.pagination.last_page => page += 1,
+                _ => return Ok(records),
+            }
+        }
+    }
+
+    fn delete_record(&self, record: &DNSRecord) -> Result<()> {
+        let url = format!("{}/records/{}", self.base_url, record.id);
+        tracing::debug!(
+            url = url,
+            method = "DELETE",
+            frontend = "hetzner",
+            "Sending request"
+        );
+        ureq::delete(&url)
+            .set("Auth-API-Token", &self.api_key)
+            .call()
+            .map_err(|err| request_error(err, &url, "DELETE", APIError::detail))?;
+        Ok(())
+    }
+
+    fn update_records(&self, records: Vec<APIRecord>) -> Result<()> {
+        let url = format!("{}/records/bulk", self.base_url);
+        let resp: BulkUpdateResponse =
+            self.api_call("PUT", &url, &[], Some(BulkRequest { records }))?;
+        match resp.failed_records.first() {
+            Some(failed) => ResponseSnafu {
+                message: format!(
+                    "Failed to update {} records, including {} {}",
+                    resp.failed_records.len(),
+                    failed.name,
+                    failed.kind
+                ),
+            }
+            .fail(),
+            None => Ok(()),
+        }
+    }
+
+    fn create_records(&self, records: Vec<APIRecord>) -> Result<()> {
+        let url = format!("{}/records/bulk", self.base_url);
+        let resp: BulkCreateResponse =
+            self.api_call("POST", &url, &[], Some(BulkRequest { records }))?;
+        match resp.invalid_records.first() {
+            Some(invalid) => ResponseSnafu {
+                message: format!(
+                    "Failed to create {} invalid records, including {} {}",
+                    resp.invalid_records.len(),
+                    invalid.name,
+                    invalid.kind
+                ),
+            }
+            .fail(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl common::Frontend for Hetzner {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // Hetzner API tokens are not scoped, so being able
+        // to read the zone means we can also edit it.
+        let zone = self.get_zone()?;
+        self.read_records(&zone).map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let zone = self.get_zone()?;
+        let current = self.read_records(&zone)?;
+        let mut diff = self.registry.diff(current, authority);
+        for record in diff.records.create.iter_mut() {
+            record.record.ttl = Some(self.ttl);
+        }
+        for record in diff.create_owners.iter_mut() {
+            record.ttl = Some(self.ttl);
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.records.create.len(),
+                update = diff.records.update.len(),
+                delete = diff.records.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.records.create.len(),
+            update = diff.records.update.len(),
+            delete = diff.records.delete.len(),
+            "Applying changes",
+        );
+
+        // There is no bulk delete, so records are deleted one at a time.
+        // Deletes first - to avoid conflicts with created records.
+        for record in diff.records.delete.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                record_id = record.id,
+                "Deleting record",
+            );
+            self.delete_record(record)?;
+        }
+        for record in diff.delete_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                record_id = record.id,
+                "Deleting ownership record",
+            );
+            self.delete_record(record)?;
+        }
+
+        let updates: Vec<APIRecord> = diff
+            .records
+            .update
+            .iter()
+            .map(|r| &r.record)
+            .inspect(|record| {
+                tracing::info!(
+                    frontend = FRONTEND_NAME,
+                    name = record.name,
+                    kind = record.kind,
+                    content = record.content,
+                    record_id = record.id,
+                    "Updating record"
+                )
+            })
+            .map(|record| record.to_api(&zone.id, &zone.name))
+            .collect();
+        if !updates.is_empty() {
+            self.update_records(updates)?;
+        }
+
+        let creates: Vec<APIRecord> = diff
+            .records
+            .create
+            .iter()
+            .map(|r| &r.record)
+            .inspect(|record| {
+                tracing::info!(
+                    frontend = FRONTEND_NAME,
+                    name = record.name,
+                    kind = record.kind,
+                    content = record.content,
+                    "Creating record"
+                )
+            })
+            .chain(diff.create_owners.iter())
+            .map(|record| record.to_api(&zone.id, &zone.name))
+            .collect();
+        if !creates.is_empty() {
+            self.create_records(creates)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for Hetzner {
+    fn from(value: super::Config) -> Self {
+        let api_key = key_file_or_string(value.api_key, FRONTEND_NAME.into()).unwrap();
+
+        Self {
+            api_key,
+            base_url: value
+                .base_url
+                .map(|url| url.as_str().trim_end_matches('/').to_string())
+                .unwrap_or_else(|| API_BASE_URL.to_string()),
+            domain: value.domain,
+            registry: TxtRegistry::new(value.registry_prefix, value.instance_id),
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+            zone: None,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::common::{
+    txt_from_zone_value, txt_to_zone_value, Match, Record, Update, RECORD_KIND_TXT,
+};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+/// The name Hetzner uses for the zone apex.
+const APEX: &str = "@";
+
+/// Record kinds whose value is a domain name, relative to the zone unless it ends in a dot.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub error: Option<ErrorDetail>,
+    pub message: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ErrorDetail {
+    pub message: String,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        self.error.map(|e| e.message).or(self.message)
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Pagination {
+    pub page: usize,
+    pub last_page: usize,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Meta {
+    pub pagination: Pagination,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Zone {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ZonesResponse {
+    pub zones: Vec<Zone>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct RecordsResponse {
+    #[serde(default)]
+    pub records: Vec<APIRecord>,
+    pub meta: Option<Meta>,
+}
+
+#[derive(serde::Serialize)]
+pub(super) struct BulkRequest {
+    pub records: Vec<APIRecord>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct BulkCreateResponse {
+    #[serde(default)]
+    pub invalid_records: Vec<APIRecord>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct BulkUpdateResponse {
+    #[serde(default)]
+    pub failed_records: Vec<APIRecord>,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct APIRecord {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    pub zone_id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Relative to the zone.
+    pub name: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+/// A record with its name and value made absolute.
+#[derive(Clone)]
+pub(super) struct DNSRecord {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: Option<u32>,
+}
+
+impl DNSRecord {
+    pub(super) fn from_api(record: APIRecord, zone: &str) -> Self {
+        let absolute = |name: &str| match name {
+            APEX => zone.to_string(),
+            name => format!("{name}.{zone}"),
+        };
+        let content = match record.kind.as_str() {
+            RECORD_KIND_TXT => txt_from_zone_value(&record.value),
+            kind if NAME_KINDS.contains(&kind) => match record.value.strip_suffix('.') {
+                Some(name) => name.to_lowercase(),
+                None => absolute(&record.value).to_lowercase(),
+            },
+            _ => record.value,
+        };
+        Self {
+            id: record.id,
+            name: absolute(&record.name).to_lowercase(),
+            kind: record.kind,
+            content,
+            ttl: record.ttl,
+        }
+    }
+
+    pub(super) fn to_api(&self, zone_id: &str, zone: &str) -> APIRecord {
+        let name = match self.name.strip_suffix(zone) {
+            Some("") => APEX.to_string(),
+            Some(name) => name.trim_end_matches('.').to_string(),
+            None => self.name.clone(),
+        };
+        let value = match self.kind.as_str() {
+            RECORD_KIND_TXT => txt_to_zone_value(&self.content),
+            kind if NAME_KINDS.contains(&kind) => format!("{}.", self.content),
+            _ => self.content.clone(),
+        };
+        APIRecord {
+            id: self.id.clone(),
+            zone_id: zone_id.to_string(),
+            kind: self.kind.clone(),
+            name,
+            value,
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl Match for DNSRecord {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for DNSRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = DNSRecord::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for DNSRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<DNSRecord> for Record {
+    fn from(value: DNSRecord) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for DNSRecord {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = match NAME_KINDS.contains(&kind.as_str()) {
+            true => value.content.trim_end_matches('.').to_lowercase(),
+            false => value.content,
+        };
+        Self {
+            id: String::new(),
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: None,
+        }
+    }
+}
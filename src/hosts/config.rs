@@ -0,0 +1,9 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// Defaults to /etc/hosts.
+    pub path: Option<PathBuf>,
+    pub instance_id: String,
+}
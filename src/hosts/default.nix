@@ -0,0 +1,35 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.hosts;
+in
+{
+  options.dnssync.frontends.hosts = {
+    enable = lib.mkEnableOption "hosts file frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write records for";
+    };
+    path = mkOption {
+      type = types.str;
+      example = "/var/lib/dnssync/hosts";
+      description = "The hosts file to write to. On NixOS, /etc/hosts is read-only,"
+        + " so point a resolver such as dnsmasq (addn-hosts) at a separate file";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to name the managed block in the file";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "hosts";
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.path) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_HOSTS_DOMAIN" = cfg.domain;
+      "DNSSYNC_HOSTS_PATH" = cfg.path;
+      "DNSSYNC_HOSTS_INSTANCE_ID" = cfg.instanceId;
+    };
+  };
+}
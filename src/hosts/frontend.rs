@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, diff_records, read_to_string_or_empty, write_atomic, FrontendSnafu,
+    Record, ResponseSnafu, Result, RECORD_KIND_A, RECORD_KIND_AAAA,
+};
+
+use super::models::{HostEntry, HostsFile};
+
+pub const FRONTEND_NAME: &str = "Hosts";
+
+const DEFAULT_PATH: &str = "/etc/hosts";
+
+pub struct Hosts {
+    domain: String,
+    path: PathBuf,
+    instance_id: String,
+}
+
+impl Hosts {
+    fn read_file(&self) -> Result<(String, HostsFile)> {
+        let text = read_to_string_or_empty(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read {}", self.path.display()),
+            })?;
+        let file = HostsFile::parse(&text, &self.instance_id).context(ResponseSnafu {
+            message: format!(
+                "{} has no \"{}\" line to end the managed block",
+                self.path.display(),
+                HostsFile::end_marker(&self.instance_id)
+            ),
+        })?;
+        Ok((text, file))
+    }
+}
+
+impl common::Frontend for Hosts {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.read_file()?;
+        check_writable(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.path.display()),
+            })
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        // Hosts files can only hold addresses
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = r.kind.eq_ignore_ascii_case(RECORD_KIND_A)
+                    || r.kind.eq_ignore_ascii_case(RECORD_KIND_AAAA);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        let (_, mut file) = self.read_file()?;
+        let diff = diff_records::<HostEntry>(file.entries.clone(), authority);
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        for entry in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = entry.name,
+                kind = entry.kind,
+                content = entry.address,
+                "Deleting record",
+            );
+        }
+        for entry in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = entry.name,
+                kind = entry.kind,
+                content = entry.address,
+                "Updating record"
+            );
+        }
+        for entry in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = entry.name,
+                kind = entry.kind,
+                content = entry.address,
+                "Creating record"
+            );
+        }
+
+        file.entries = diff.apply(file.entries);
+        file.entries
+            .sort_by(|a, b| (&a.name, &a.kind).cmp(&(&b.name, &b.kind)));
+
+        write_atomic(&self.path, &file.render(&self.instance_id))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.path.display()),
+            })
+    }
+}
+
+impl From<super::Config> for Hosts {
+    fn from(value: super::Config) -> Self {
+        Self {
+            domain: value.domain,
+            path: value.path.unwrap_or_else(|| DEFAULT_PATH.into()),
+            instance_id: value.instance_id,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::common::{Manage, Match, Record, Update};
+
+#[derive(Clone)]
+pub(super) struct HostEntry {
+    pub name: String,
+    pub kind: String,
+    pub address: String,
+}
+
+/// A hosts file, split around the block managed by this instance.
+pub(super) struct HostsFile {
+    pub before: Vec<String>,
+    pub entries: Vec<HostEntry>,
+    pub after: Vec<String>,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+impl HostEntry {
+    pub(super) fn to_line(&self) -> String {
+        format!("{}\t{}", self.address, self.name)
+    }
+
+    /// Parses an address and its names, returning an entry per name.
+    fn from_line(line: &str) -> Vec<Self> {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        let Some(address) = fields.next() else {
+            return Vec::new();
+        };
+        let kind = match address.parse::<std::net::IpAddr>() {
+            Ok(std::net::IpAddr::V4(_)) => crate::common::RECORD_KIND_A,
+            Ok(std::net::IpAddr::V6(_)) => crate::common::RECORD_KIND_AAAA,
+            Err(_) => return Vec::new(),
+        };
+        fields
+            .map(|name| Self {
+                name: normalize_name(name),
+                kind: kind.to_string(),
+                address: address.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl HostsFile {
+    pub(super) fn begin_marker(instance_id: &str) -> String {
+        format!("# BEGIN dnssync {instance_id}")
+    }
+
+    pub(super) fn end_marker(instance_id: &str) -> String {
+        format!("# END dnssync {instance_id}")
+    }
+
+    /// Returns None if the block has no end marker, as there is
+    /// no telling where the entries end and other lines begin.
+    pub(super) fn parse(text: &str, instance_id: &str) -> Option<Self> {
+        let begin = Self::begin_marker(instance_id);
+        let end = Self::end_marker(instance_id);
+        let lines: Vec<&str> = text.lines().collect();
+
+        let Some(start) = lines.iter().position(|l| l.trim() == begin) else {
+            return Some(Self {
+                before: lines.into_iter().map(String::from).collect(),
+                entries: Vec::new(),
+                after: Vec::new(),
+            });
+        };
+        let stop = start + lines[start..].iter().position(|l| l.trim() == end)?;
+
+        Some(Self {
+            before: lines[..start].iter().map(|l| l.to_string()).collect(),
+            entries: lines[start + 1..stop]
+                .iter()
+                .flat_map(|l| HostEntry::from_line(l))
+                .collect(),
+            after: lines
+                .get(stop + 1..)
+                .unwrap_or_default()
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+        })
+    }
+
+    /// Renders the file, leaving out the block if it has no entries.
+    pub(super) fn render(&self, instance_id: &str) -> String {
+        let mut lines = self.before.clone();
+        if !self.entries.is_empty() {
+            lines.push(Self::begin_marker(instance_id));
+            lines.extend(self.entries.iter().map(|e| e.to_line()));
+            lines.push(Self::end_marker(instance_id));
+        }
+        lines.extend(self.after.iter().cloned());
+
+        let mut text = lines.join("\n");
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl Manage for HostEntry {
+    fn is_managed(&self) -> bool {
+        // Only entries in our block are read
+        true
+    }
+}
+
+impl Match for HostEntry {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for HostEntry {
+    fn update(mut self, authority: Record) -> Self {
+        self.address = authority.content;
+        self
+    }
+}
+
+impl PartialEq for HostEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.address == other.address
+    }
+}
+
+impl From<HostEntry> for Record {
+    fn from(value: HostEntry) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.address,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for HostEntry {
+    fn from(value: Record) -> Self {
+        Self {
+            name: normalize_name(&value.name.to_string()),
+            kind: value.kind.to_uppercase(),
+            address: value.content,
+        }
+    }
+}
@@ -11,6 +11,14 @@ pub struct Backend {
 }
 
 impl common::Backend for Backend {
+    fn get_name(&self) -> &str {
+        BACKEND_NAME
+    }
+
+    fn check(&self) -> Result<()> {
+        self.read_records().map(|_| ())
+    }
+
     fn read_records(&self) -> Result<Vec<Record>> {
         let file = File::open(&self.source)
             .boxed_local()
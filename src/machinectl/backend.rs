@@ -62,6 +62,34 @@ impl Machinectl {
 }
 
 impl common::Backend for Machinectl {
+    fn get_name(&self) -> &str {
+        BACKEND_NAME
+    }
+
+    fn check(&self) -> Result<()> {
+        // Listing machines requires both the tool and the systemd bus
+        let output = std::process::Command::new("machinectl")
+            .args(["list", "--no-legend"])
+            .output()
+            .boxed_local()
+            .context(BackendSnafu {
+                backend: BACKEND_NAME,
+                message: "Failed to run machinectl list",
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(std::io::Error::other(stderr))
+                .boxed_local()
+                .context(BackendSnafu {
+                    backend: BACKEND_NAME,
+                    message: format!("machinectl list exited with {}", output.status),
+                });
+        }
+
+        Ok(())
+    }
+
     fn read_records(&self) -> Result<Vec<Record>> {
         let output = std::process::Command::new("machinectl")
             .args(["list", "-o", "json"])
@@ -0,0 +1,9 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// The file Headscale reads extra records from, set as
+    /// `dns.extra_records_path` in its config. It is entirely managed by DNSSync.
+    pub path: PathBuf,
+}
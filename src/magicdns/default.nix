@@ -0,0 +1,29 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.magicdns;
+in
+{
+  options.dnssync.frontends.magicdns = {
+    enable = lib.mkEnableOption "Headscale MagicDNS extra records frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write extra records for";
+    };
+    path = mkOption {
+      type = types.str;
+      default = "/var/lib/dnssync/extra-records.json";
+      description = "The extra records file to write. Set Headscale's dns.extra_records_path to it";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "magicdns";
+    systemd.services.dnssync.serviceConfig.StateDirectory = [ "dnssync" ];
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.path) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_MAGICDNS_DOMAIN" = cfg.domain;
+      "DNSSYNC_MAGICDNS_PATH" = cfg.path;
+    };
+  };
+}
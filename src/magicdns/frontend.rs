@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, diff_records, read_to_string_or_empty, write_atomic, FrontendSnafu,
+    Record, Result, RECORD_KIND_A, RECORD_KIND_AAAA,
+};
+
+use super::models::ExtraRecord;
+
+pub const FRONTEND_NAME: &str = "MagicDNS";
+
+pub struct MagicDNS {
+    domain: String,
+    path: PathBuf,
+}
+
+impl MagicDNS {
+    fn read_file(&self) -> Result<(String, Vec<ExtraRecord>)> {
+        let text = read_to_string_or_empty(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read {}", self.path.display()),
+            })?;
+        // Refuse to overwrite a file we can't make sense of
+        let records = ExtraRecord::parse(&text)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to parse {}", self.path.display()),
+            })?;
+        Ok((text, records))
+    }
+}
+
+impl common::Frontend for MagicDNS {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.read_file()?;
+        check_writable(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.path.display()),
+            })
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        // Tailscale clients only answer extra records with addresses
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = r.kind.eq_ignore_ascii_case(RECORD_KIND_A)
+                    || r.kind.eq_ignore_ascii_case(RECORD_KIND_AAAA);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        let (text, current) = self.read_file()?;
+        let diff = diff_records::<ExtraRecord>(current.clone(), authority);
+        let mut records = diff.apply(current);
+        records.sort_by(|a, b| (&a.name, &a.kind, &a.value).cmp(&(&b.name, &b.kind, &b.value)));
+
+        // Comparing the rendered file also catches a file that was edited by hand
+        let rendered = ExtraRecord::render(&records)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to serialize records",
+            })?;
+        if rendered == text {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        for record in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.value,
+                "Deleting record",
+            );
+        }
+        for record in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.value,
+                "Creating record"
+            );
+        }
+
+        // Headscale watches the file, so there is nothing to reload
+        write_atomic(&self.path, &rendered)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.path.display()),
+            })
+    }
+}
+
+impl From<super::Config> for MagicDNS {
+    fn from(value: super::Config) -> Self {
+        Self {
+            domain: value.domain,
+            path: value.path,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::common::{Manage, Match, Record, Update};
+
+/// An entry of Headscale's extra records file,
+/// which is passed on to Tailscale clients as is.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct ExtraRecord {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: String,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+impl ExtraRecord {
+    /// Parses the file, treating an empty file as no records.
+    pub(super) fn parse(text: &str) -> serde_json::Result<Vec<Self>> {
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(text)
+    }
+
+    pub(super) fn render(records: &[Self]) -> serde_json::Result<String> {
+        let mut text = serde_json::to_string_pretty(records)?;
+        text.push('\n');
+        Ok(text)
+    }
+}
+
+impl Manage for ExtraRecord {
+    fn is_managed(&self) -> bool {
+        // The whole file belongs to DNSSync
+        true
+    }
+}
+
+impl Match for ExtraRecord {
+    fn matches(&self, other: &Self) -> bool {
+        // A name may have several addresses, each of which is its own entry
+        self.kind.eq_ignore_ascii_case(&other.kind)
+            && self.name.eq_ignore_ascii_case(&other.name)
+            && self.value == other.value
+    }
+}
+
+impl Update for ExtraRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.value = authority.content;
+        self
+    }
+}
+
+impl PartialEq for ExtraRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other)
+    }
+}
+
+impl From<ExtraRecord> for Record {
+    fn from(value: ExtraRecord) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.value,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for ExtraRecord {
+    fn from(value: Record) -> Self {
+        Self {
+            name: normalize_name(&value.name.to_string()),
+            kind: value.kind.to_uppercase(),
+            value: value.content,
+        }
+    }
+}
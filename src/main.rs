@@ -1,10 +1,32 @@
+pub mod adguard;
+pub mod azure;
+pub mod bind;
 pub mod cloudflare;
 pub mod common;
 pub mod config;
+pub mod desec;
+pub mod digitalocean;
+pub mod dnsmasq;
+pub mod dnsserver;
+pub mod etcd;
+pub mod exec;
+pub mod export;
+pub mod gandi;
+pub mod gcloud;
 pub mod headscale;
+pub mod hetzner;
+pub mod hosts;
 pub mod jsonfile;
 pub mod machinectl;
+pub mod magicdns;
+pub mod mikrotik;
+pub mod pihole;
+pub mod powerdns;
+pub mod rfc2136;
+pub mod route53;
 pub mod service;
+pub mod technitium;
+pub mod unbound;
 
 pub use config::*;
 
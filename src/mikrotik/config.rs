@@ -0,0 +1,11 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    /// The router's web service, e.g. https://192.168.88.1
+    pub base_url: url::Url,
+    pub username: String,
+    pub password: String,
+    /// Only static entries under this domain are managed.
+    pub domain: String,
+    pub instance_id: String,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,50 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.mikrotik;
+in
+{
+  options.dnssync.frontends.mikrotik = {
+    enable = lib.mkEnableOption "MikroTik RouterOS static DNS frontend for records";
+    baseUrl = mkOption {
+      type = types.str;
+      example = "https://192.168.88.1";
+      description = "The router's web service, which serves the REST API";
+    };
+    username = mkOption {
+      type = types.str;
+      description = "The RouterOS user to log in as";
+    };
+    passwordFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the RouterOS password. Must be owned by the dnssync user";
+    };
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write static entries for";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same router";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created entries";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "mikrotik";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_MIKROTIK_BASE_URL" = cfg.baseUrl;
+      "DNSSYNC_MIKROTIK_USERNAME" = cfg.username;
+      "DNSSYNC_MIKROTIK_PASSWORD" = "@${cfg.passwordFile}";
+      "DNSSYNC_MIKROTIK_DOMAIN" = cfg.domain;
+      "DNSSYNC_MIKROTIK_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_MIKROTIK_TTL" = toString cfg.ttl;
+    };
+  };
+}
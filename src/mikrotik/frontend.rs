@@ -0,0 +1,221 @@
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, diff_records, key_file_or_string, request_error, FrontendSnafu, Record, Result,
+};
+
+use super::models::{is_supported, APIEntry, APIError, StaticEntry, DEFAULT_TTL};
+
+pub const FRONTEND_NAME: &str = "MikroTik";
+
+pub struct MikroTik {
+    base_url: url::Url,
+    username: String,
+    password: String,
+    domain: String,
+    instance_id: String,
+    ttl: u32,
+}
+
+impl MikroTik {
+    /// The URL of the static entries, or of one entry if an ID is given.
+    fn api_url(&self, id: Option<&str>) -> url::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .extend(&["rest", "ip", "dns", "static"])
+            .extend(id);
+        url
+    }
+
+    fn request(&self, method: &str, url: &url::Url) -> ureq::Request {
+        tracing::debug!(
+            url = url.as_str(),
+            method = method,
+            frontend = "mikrotik",
+            "Sending request"
+        );
+        let credentials = base64::engine::general_purpose::STANDARD
+            .encode(format!("{}:{}", self.username, self.password));
+        ureq::request(method, url.as_str()).set("Authorization", &format!("Basic {credentials}"))
+    }
+
+    fn api_call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        id: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> Result<T> {
+        let url = self.api_url(id);
+        let request = self.request(method, &url);
+        let resp = match body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        };
+        resp.map_err(|err| request_error(err, url.as_str(), method, APIError::detail))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    /// Reads the static entries under the domain.
+    fn read_records(&self) -> Result<Vec<StaticEntry>> {
+        let entries: Vec<APIEntry> = self.api_call("GET", None, None)?;
+        let suffix = format!(".{}", self.domain);
+        Ok(entries
+            .into_iter()
+            .filter_map(StaticEntry::from_api)
+            .filter(|e| e.name == self.domain || e.name.ends_with(&suffix))
+            .collect())
+    }
+
+    fn delete(&self, id: &str) -> Result<()> {
+        let url = self.api_url(Some(id));
+        self.request("DELETE", &url)
+            .call()
+            .map_err(|err| request_error(err, url.as_str(), "DELETE", APIError::detail))?;
+        Ok(())
+    }
+}
+
+impl common::Frontend for MikroTik {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.read_records().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = is_supported(&r.kind);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        let current = self.read_records()?;
+        let diff = diff_records::<StaticEntry>(current, authority);
+
+        // Evaluate deletions more precisely based on instance ID
+        let diff_len = diff.len() - diff.delete.len();
+        let deletions: Vec<StaticEntry> = diff
+            .delete
+            .into_iter()
+            .filter(|record| record.get_instance_id().eq(&Some(&self.instance_id)))
+            .collect();
+        let diff_len = diff_len + deletions.len();
+
+        // Short circuit on no changes
+        if diff_len == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = deletions.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = deletions.len(),
+            "Applying changes",
+        );
+
+        // Deletes first - to avoid conflicts with CNAMEs
+        for record in deletions {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            let id = record.id.as_deref().expect("Existing entries have an ID");
+            self.delete(id)?;
+        }
+
+        for mut record in diff.update {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+
+            if record.get_instance_id().ne(&Some(&self.instance_id)) {
+                tracing::warn!(
+                    frontend = FRONTEND_NAME,
+                    name = record.name,
+                    kind = record.kind,
+                    content = record.content,
+                    "Record is not managed by this instance but we will update anyway"
+                )
+            }
+            record.set_instance_id(&self.instance_id);
+            let id = record.id.as_deref().expect("Existing entries have an ID");
+            self.api_call::<serde_json::Value>("PATCH", Some(id), Some(record.to_api()))?;
+        }
+
+        for mut record in diff.create {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            record.ttl = Some(self.ttl);
+            record.set_instance_id(&self.instance_id);
+            self.api_call::<serde_json::Value>("PUT", None, Some(record.to_api()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for MikroTik {
+    fn from(value: super::Config) -> Self {
+        let password = key_file_or_string(value.password, FRONTEND_NAME.into()).unwrap();
+
+        Self {
+            base_url: value.base_url,
+            username: value.username,
+            password,
+            domain: value.domain.trim_end_matches('.').to_lowercase(),
+            instance_id: value.instance_id,
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
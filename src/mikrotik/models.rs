@@ -0,0 +1,168 @@
+use serde_json::{json, Map, Value};
+
+use crate::common::{
+    format_watermark, merge_watermark, parse_watermark, Manage, Match, Record, Update,
+    COMMENT_WATERMARK, RECORD_KIND_A, RECORD_KIND_AAAA,
+};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+pub(super) const RECORD_KIND_CNAME: &str = "CNAME";
+
+pub(super) fn is_supported(kind: &str) -> bool {
+    [RECORD_KIND_A, RECORD_KIND_AAAA, RECORD_KIND_CNAME]
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(kind))
+}
+
+/// The body of an error response.
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub message: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl APIError {
+    /// The detail is more specific, but often missing or empty.
+    pub(super) fn detail(self) -> Option<String> {
+        [self.detail, self.message]
+            .into_iter()
+            .flatten()
+            .find(|d| !d.is_empty())
+    }
+}
+
+/// An entry of `/ip/dns/static`. RouterOS returns every value as a string,
+/// and leaves out properties which are unset or at their default.
+#[derive(serde::Deserialize)]
+pub(super) struct APIEntry {
+    #[serde(rename = ".id")]
+    pub id: String,
+    /// Entries matching a regexp have no name.
+    pub name: Option<String>,
+    /// Left out of A entries.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub address: Option<String>,
+    pub cname: Option<String>,
+    pub comment: Option<String>,
+    pub dynamic: Option<String>,
+}
+
+#[derive(Clone)]
+pub(super) struct StaticEntry {
+    /// None if not yet created.
+    pub id: Option<String>,
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    /// Only sent when creating, so existing entries keep their TTL.
+    pub ttl: Option<u32>,
+    pub comment: Option<String>,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+impl StaticEntry {
+    /// Returns None for entries DNSSync doesn't manage,
+    /// such as regexp, dynamic, or forwarding entries.
+    pub(super) fn from_api(entry: APIEntry) -> Option<Self> {
+        if entry.dynamic.as_deref() == Some("true") {
+            return None;
+        }
+        let kind = entry
+            .kind
+            .map(|k| k.to_uppercase())
+            .unwrap_or_else(|| RECORD_KIND_A.to_string());
+        let content = match kind.as_str() {
+            RECORD_KIND_A | RECORD_KIND_AAAA => entry.address?,
+            RECORD_KIND_CNAME => normalize_name(&entry.cname?),
+            _ => return None,
+        };
+        Some(Self {
+            id: Some(entry.id),
+            name: normalize_name(&entry.name?),
+            kind,
+            content,
+            ttl: None,
+            comment: entry.comment.filter(|c| !c.is_empty()),
+        })
+    }
+
+    /// The body of a request writing the entry.
+    pub(super) fn to_api(&self) -> Value {
+        let mut entry = Map::new();
+        entry.insert("name".into(), json!(self.name));
+        entry.insert("type".into(), json!(self.kind));
+        match self.kind.as_str() {
+            RECORD_KIND_CNAME => entry.insert("cname".into(), json!(self.content)),
+            _ => entry.insert("address".into(), json!(self.content)),
+        };
+        if let Some(ttl) = self.ttl {
+            entry.insert("ttl".into(), json!(format!("{ttl}s")));
+        }
+        entry.insert(
+            "comment".into(),
+            json!(self.comment.as_deref().unwrap_or_default()),
+        );
+        Value::Object(entry)
+    }
+
+    pub(super) fn get_instance_id(&self) -> Option<&str> {
+        self.comment.as_deref().and_then(parse_watermark)
+    }
+
+    /// Marks the entry as managed by instance_id.
+    /// Any text before the watermark is kept as is.
+    pub(super) fn set_instance_id(&mut self, instance_id: &str) {
+        let managed = format_watermark(Some(instance_id), None);
+        self.comment = merge_watermark(self.comment.as_deref(), Some(&managed));
+    }
+}
+
+impl Manage for StaticEntry {
+    fn is_managed(&self) -> bool {
+        self.comment
+            .as_ref()
+            .is_some_and(|c| c.contains(COMMENT_WATERMARK))
+    }
+}
+
+impl Match for StaticEntry {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for StaticEntry {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = StaticEntry::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for StaticEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<Record> for StaticEntry {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = match kind.as_str() {
+            RECORD_KIND_CNAME => normalize_name(&value.content),
+            _ => value.content,
+        };
+        Self {
+            id: None,
+            name: normalize_name(&value.name.to_string()),
+            kind,
+            content,
+            ttl: None,
+            comment: Some(COMMENT_WATERMARK.to_string()),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    /// The Pi-hole web interface, e.g. http://pi.hole
+    pub base_url: url::Url,
+    /// The web interface or app password. Not needed if the API is unprotected.
+    pub password: Option<String>,
+    pub domain: String,
+    /// Where to keep the list of entries this instance created.
+    pub state_path: PathBuf,
+}
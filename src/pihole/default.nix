@@ -0,0 +1,43 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.pihole;
+in
+{
+  options.dnssync.frontends.pihole = {
+    enable = lib.mkEnableOption "Pi-hole local DNS frontend for records";
+    baseUrl = mkOption {
+      type = types.str;
+      description = "The Pi-hole web interface, e.g. http://192.168.1.2";
+    };
+    passwordFile = mkOption {
+      type = types.nullOr types.path;
+      default = null;
+      description = "Path to a file containing the Pi-hole web or app password. Must be owned by the dnssync user."
+        + " Leave unset if the Pi-hole has no password";
+    };
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write local DNS records for";
+    };
+    statePath = mkOption {
+      type = types.str;
+      default = "/var/lib/dnssync/pihole.json";
+      description = "Where to keep the list of records created by dnssync";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "pihole";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.serviceConfig.StateDirectory = [ "dnssync" ];
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.statePath) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_PIHOLE_BASE_URL" = cfg.baseUrl;
+      "DNSSYNC_PIHOLE_DOMAIN" = cfg.domain;
+      "DNSSYNC_PIHOLE_STATE_PATH" = cfg.statePath;
+    } // lib.optionalAttrs (cfg.passwordFile != null) {
+      "DNSSYNC_PIHOLE_PASSWORD" = "@${cfg.passwordFile}";
+    };
+  };
+}
@@ -0,0 +1,342 @@
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, check_writable, diff_records, key_file_or_string, request_error, FrontendSnafu,
+    OwnedEntry, OwnershipFile, Record, ResponseSnafu, Result, RECORD_KIND_A, RECORD_KIND_AAAA,
+};
+
+use super::models::{
+    APIError, AuthRequest, AuthResponse, ConfigResponse, LocalRecord, RECORD_KIND_CNAME,
+};
+
+pub const FRONTEND_NAME: &str = "PiHole";
+
+const SESSION_HEADER: &str = "X-FTL-SID";
+
+/// Like request_error, but failed logins describe the problem in the session.
+fn login_error(err: ureq::Error, url: &str) -> common::Error {
+    match err {
+        ureq::Error::Status(code, resp) => {
+            match resp
+                .into_json::<AuthResponse>()
+                .map(|body| body.session.message)
+            {
+                Ok(Some(detail)) => ResponseSnafu {
+                    message: format!("POST {url} failed with status {code}: {detail}"),
+                }
+                .build(),
+                _ => ResponseSnafu {
+                    message: format!("POST {url} failed with status {code}"),
+                }
+                .build(),
+            }
+        }
+        err => request_error(err, url, "POST", APIError::detail),
+    }
+}
+
+pub struct PiHole {
+    base_url: url::Url,
+    password: Option<String>,
+    domain: String,
+    ownership: OwnershipFile,
+}
+
+/// An authenticated API session, logged out when dropped
+/// as Pi-hole only allows a few sessions at once.
+struct Session<'a> {
+    frontend: &'a PiHole,
+    sid: Option<String>,
+}
+
+impl Session<'_> {
+    fn request(&self, method: &str, url: &url::Url) -> ureq::Request {
+        tracing::debug!(
+            url = url.as_str(),
+            method = method,
+            frontend = "pihole",
+            "Sending request"
+        );
+        let req = ureq::request(method, url.as_str());
+        match &self.sid {
+            Some(sid) => req.set(SESSION_HEADER, sid),
+            None => req,
+        }
+    }
+
+    fn api_call<T: DeserializeOwned>(&self, method: &str, url: url::Url) -> Result<T> {
+        self.request(method, &url)
+            .call()
+            .map_err(|err| request_error(err, url.as_str(), method, APIError::detail))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    fn read_records(&self) -> Result<Vec<LocalRecord>> {
+        let url = self.frontend.api_url(&["config", "dns"]);
+        let resp: ConfigResponse = self.api_call("GET", url)?;
+        Ok(resp
+            .config
+            .dns
+            .hosts
+            .iter()
+            .flat_map(|entry| LocalRecord::from_host(entry))
+            .chain(
+                resp.config
+                    .dns
+                    .cname_records
+                    .iter()
+                    .filter_map(|entry| LocalRecord::from_cname(entry)),
+            )
+            .collect())
+    }
+
+    fn add_entry(&self, key: &str, entry: &str) -> Result<()> {
+        let url = self.frontend.api_url(&["config", "dns", key, entry]);
+        self.request("PUT", &url)
+            .call()
+            .map_err(|err| request_error(err, url.as_str(), "PUT", APIError::detail))?;
+        Ok(())
+    }
+
+    fn delete_entry(&self, key: &str, entry: &str) -> Result<()> {
+        let url = self.frontend.api_url(&["config", "dns", key, entry]);
+        self.request("DELETE", &url)
+            .call()
+            .map_err(|err| request_error(err, url.as_str(), "DELETE", APIError::detail))?;
+        Ok(())
+    }
+}
+
+impl Drop for Session<'_> {
+    fn drop(&mut self) {
+        if self.sid.is_none() {
+            return;
+        }
+        let url = self.frontend.api_url(&["auth"]);
+        if let Err(err) = self.request("DELETE", &url).call() {
+            tracing::warn!(
+                frontend = FRONTEND_NAME,
+                error = err.to_string(),
+                "Failed to log out"
+            );
+        }
+    }
+}
+
+impl PiHole {
+    fn api_url(&self, path: &[&str]) -> url::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .push("api")
+            .extend(path);
+        url
+    }
+
+    fn login(&self) -> Result<Session<'_>> {
+        let Some(password) = &self.password else {
+            return Ok(Session {
+                frontend: self,
+                sid: None,
+            });
+        };
+
+        let url = self.api_url(&["auth"]);
+        tracing::debug!(
+            url = url.as_str(),
+            method = "POST",
+            frontend = "pihole",
+            "Sending request"
+        );
+        let resp: AuthResponse = ureq::post(url.as_str())
+            .send_json(AuthRequest { password })
+            .map_err(|err| login_error(err, url.as_str()))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })?;
+
+        match (resp.session.valid, resp.session.sid) {
+            (true, sid) => Ok(Session {
+                frontend: self,
+                sid,
+            }),
+            (false, _) => ResponseSnafu {
+                message: format!(
+                    "Failed to log in to Pi-hole: {}",
+                    resp.session.message.unwrap_or_default()
+                ),
+            }
+            .fail(),
+        }
+    }
+
+    fn load_ownership(&self) -> Result<Vec<OwnedEntry>> {
+        self.ownership.load().boxed_local().context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: format!("Failed to read {}", self.ownership.path().display()),
+        })
+    }
+
+    fn save_ownership(&self, owned: Vec<OwnedEntry>) -> Result<()> {
+        self.ownership
+            .save(owned)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.ownership.path().display()),
+            })
+    }
+}
+
+impl common::Frontend for PiHole {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.load_ownership()?;
+        check_writable(self.ownership.path())
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.ownership.path().display()),
+            })?;
+        self.login()?.read_records().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        // Local DNS records can only hold addresses and CNAMEs
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = [RECORD_KIND_A, RECORD_KIND_AAAA, RECORD_KIND_CNAME]
+                    .iter()
+                    .any(|kind| r.kind.eq_ignore_ascii_case(kind));
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        let owned = self.load_ownership()?;
+        let session = self.login()?;
+        let current: Vec<LocalRecord> = session
+            .read_records()?
+            .into_iter()
+            .map(|record| record.with_owner(&owned))
+            .collect();
+        let diff = diff_records(current.clone(), authority);
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        // Claim the records about to be created first, so they are
+        // still recognised as ours if a later change fails.
+        let owned_after: Vec<OwnedEntry> = diff
+            .apply(current)
+            .iter()
+            .filter(|record| record.managed)
+            .map(LocalRecord::owned_entry)
+            .collect();
+        let mut claimed = owned.clone();
+        claimed.extend(owned_after.iter().filter(|e| !owned.contains(e)).cloned());
+        self.save_ownership(claimed)?;
+
+        // Entries can't be edited, so updates replace the old entry
+        for record in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            let (key, entry) = record.current_entry();
+            session.delete_entry(key, entry)?;
+        }
+        for record in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+            let (key, entry) = record.current_entry();
+            session.delete_entry(key, entry)?;
+            let (key, entry) = record.to_entry();
+            session.add_entry(key, &entry)?;
+        }
+        for record in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            let (key, entry) = record.to_entry();
+            session.add_entry(key, &entry)?;
+        }
+
+        self.save_ownership(owned_after)
+    }
+}
+
+impl From<super::Config> for PiHole {
+    fn from(value: super::Config) -> Self {
+        let password = value
+            .password
+            .map(|password| key_file_or_string(password, FRONTEND_NAME.into()).unwrap());
+
+        Self {
+            base_url: value.base_url,
+            password,
+            domain: value.domain,
+            ownership: OwnershipFile::new(value.state_path),
+        }
+    }
+}
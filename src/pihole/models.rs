@@ -0,0 +1,183 @@
+use std::net::IpAddr;
+
+use crate::common::{Manage, Match, OwnedEntry, Record, Update, RECORD_KIND_A, RECORD_KIND_AAAA};
+
+pub(super) const RECORD_KIND_CNAME: &str = "CNAME";
+
+/// The config keys holding each kind of local record.
+const HOSTS_KEY: &str = "hosts";
+const CNAME_RECORDS_KEY: &str = "cnameRecords";
+
+#[derive(serde::Serialize)]
+pub(super) struct AuthRequest<'a> {
+    pub password: &'a str,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct AuthResponse {
+    pub session: Session,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Session {
+    pub valid: bool,
+    pub sid: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub error: ErrorDetail,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ErrorDetail {
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        Some(match self.error.hint {
+            Some(hint) => format!("{} ({hint})", self.error.message),
+            None => self.error.message,
+        })
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ConfigResponse {
+    pub config: ConfigSection,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ConfigSection {
+    pub dns: DnsConfig,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct DnsConfig {
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default, rename = "cnameRecords")]
+    pub cname_records: Vec<String>,
+}
+
+/// A local DNS record, or CNAME record.
+#[derive(Clone)]
+pub(super) struct LocalRecord {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    /// The config entry the record was read from, empty if not yet written.
+    pub entry: String,
+    pub managed: bool,
+}
+
+impl LocalRecord {
+    /// Parses a `hosts` entry such as `192.168.1.10 nas.lan`.
+    /// Entries may list more than one name for the address.
+    pub(super) fn from_host(entry: &str) -> Vec<Self> {
+        let mut fields = entry.split_whitespace();
+        let address = fields.next().unwrap_or_default();
+        let kind = match address.parse::<IpAddr>() {
+            Ok(IpAddr::V4(_)) => RECORD_KIND_A,
+            Ok(IpAddr::V6(_)) => RECORD_KIND_AAAA,
+            Err(_) => return Vec::new(),
+        };
+        fields
+            .take_while(|name| !name.starts_with('#'))
+            .map(|name| Self {
+                name: name.to_lowercase(),
+                kind: kind.to_string(),
+                content: address.to_string(),
+                entry: entry.to_string(),
+                managed: false,
+            })
+            .collect()
+    }
+
+    /// Parses a `cnameRecords` entry such as `www.lan,nas.lan` or `www.lan,nas.lan,300`.
+    pub(super) fn from_cname(entry: &str) -> Option<Self> {
+        let mut fields = entry.split(',').map(str::trim);
+        let name = fields.next().filter(|name| !name.is_empty())?;
+        let target = fields.next().filter(|target| !target.is_empty())?;
+        Some(Self {
+            name: name.to_lowercase(),
+            kind: RECORD_KIND_CNAME.to_string(),
+            content: target.to_lowercase(),
+            entry: entry.to_string(),
+            managed: false,
+        })
+    }
+
+    /// The config key and entry this record should be written as.
+    pub(super) fn to_entry(&self) -> (&'static str, String) {
+        match self.kind.as_str() {
+            RECORD_KIND_CNAME => (CNAME_RECORDS_KEY, format!("{},{}", self.name, self.content)),
+            _ => (HOSTS_KEY, format!("{} {}", self.content, self.name)),
+        }
+    }
+
+    /// The config key and entry this record was read from.
+    pub(super) fn current_entry(&self) -> (&'static str, &str) {
+        (self.to_entry().0, &self.entry)
+    }
+
+    pub(super) fn owned_entry(&self) -> OwnedEntry {
+        OwnedEntry {
+            name: self.name.clone(),
+            kind: self.kind.clone(),
+            content: self.content.clone(),
+        }
+    }
+
+    /// Marks the record as managed if it is listed in owned, and is the only
+    /// record in its entry, so that deleting the entry can't remove others.
+    pub(super) fn with_owner(mut self, owned: &[OwnedEntry]) -> Self {
+        self.managed = owned.contains(&self.owned_entry()) && self.entry == self.to_entry().1;
+        self
+    }
+}
+
+impl Manage for LocalRecord {
+    fn is_managed(&self) -> bool {
+        self.managed
+    }
+}
+
+impl Match for LocalRecord {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for LocalRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = LocalRecord::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for LocalRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<Record> for LocalRecord {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = match kind.as_str() {
+            RECORD_KIND_CNAME => value.content.trim_end_matches('.').to_lowercase(),
+            _ => value.content,
+        };
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            entry: String::new(),
+            managed: true,
+        }
+    }
+}
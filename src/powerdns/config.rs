@@ -0,0 +1,11 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub api_key: String,
+    /// The webserver URL of the PowerDNS Authoritative server.
+    pub base_url: url::Url,
+    /// Defaults to localhost.
+    pub server_id: Option<String>,
+    pub domain: String,
+    pub instance_id: String,
+    pub ttl: Option<usize>,
+}
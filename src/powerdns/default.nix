@@ -0,0 +1,51 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.powerdns;
+in
+{
+  options.dnssync.frontends.powerdns = {
+    enable = lib.mkEnableOption "PowerDNS Authoritative frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The zone to update. Must exist on the PowerDNS server";
+    };
+    baseUrl = mkOption {
+      type = types.str;
+      example = "http://127.0.0.1:8081";
+      description = "URL of the PowerDNS webserver";
+    };
+    serverId = mkOption {
+      type = types.str;
+      default = "localhost";
+      description = "The server ID in the API path";
+    };
+    apiKeyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the PowerDNS API key. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "powerdns";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_POWERDNS_DOMAIN" = cfg.domain;
+      "DNSSYNC_POWERDNS_BASE_URL" = cfg.baseUrl;
+      "DNSSYNC_POWERDNS_SERVER_ID" = cfg.serverId;
+      "DNSSYNC_POWERDNS_API_KEY" = "@${cfg.apiKeyFile}";
+      "DNSSYNC_POWERDNS_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_POWERDNS_TTL" = toString cfg.ttl;
+    };
+  };
+}
@@ -0,0 +1,207 @@
+use snafu::prelude::*;
+
+use crate::common::{
+    self, diff_records, group_rrsets, key_file_or_string, request_error, FrontendSnafu, Record,
+    Result,
+};
+
+use super::models::{
+    APIError, RRSet, Zone, ZonePatch, CHANGETYPE_DELETE, CHANGETYPE_REPLACE, DEFAULT_TTL,
+};
+
+pub const FRONTEND_NAME: &str = "PowerDNS";
+
+const DEFAULT_SERVER_ID: &str = "localhost";
+
+pub struct PowerDNS {
+    api_key: String,
+    domain: String,
+    instance_id: String,
+    ttl: usize,
+    zone_url: url::Url,
+}
+
+impl PowerDNS {
+    fn with_headers(&self, req: ureq::Request) -> ureq::Request {
+        req.set("X-API-Key", &self.api_key)
+            .set("Content-Type", "application/json")
+    }
+
+    pub(super) fn read_zone(&self) -> Result<Zone> {
+        let url = self.zone_url.as_str();
+        tracing::debug!(
+            url = url,
+            method = "GET",
+            frontend = "powerdns",
+            "Sending request"
+        );
+        self.with_headers(ureq::get(url))
+            .call()
+            .map_err(|err| request_error(err, url, "GET", APIError::detail))?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    fn patch_zone(&self, patch: ZonePatch) -> Result<()> {
+        let url = self.zone_url.as_str();
+        tracing::debug!(
+            url = url,
+            method = "PATCH",
+            frontend = "powerdns",
+            rrsets = patch.rrsets.len(),
+            "Sending request"
+        );
+        self.with_headers(ureq::request("PATCH", url))
+            .send_json(patch)
+            .map_err(|err| request_error(err, url, "PATCH", APIError::detail))?;
+        Ok(())
+    }
+}
+
+impl common::Frontend for PowerDNS {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // PowerDNS API keys are not scoped, so being able
+        // to read the zone means we can also edit it.
+        self.read_zone().map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let zone = self.read_zone()?;
+        let diff = diff_records::<RRSet>(zone.rrsets, group_rrsets(authority));
+
+        // Evaluate deletions more precisely based on instance ID
+        let diff_len = diff.len() - diff.delete.len();
+        let deletions: Vec<RRSet> = diff
+            .delete
+            .into_iter()
+            .filter(|rrset| rrset.get_instance_id().eq(&Some(&self.instance_id)))
+            .collect();
+        let diff_len = diff_len + deletions.len();
+
+        // Short circuit on no changes
+        if diff_len == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = deletions.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = deletions.len(),
+            "Applying changes",
+        );
+
+        // All changes are sent in one PATCH, which PowerDNS applies atomically.
+        let mut rrsets: Vec<RRSet> = Vec::with_capacity(diff_len);
+
+        for mut rrset in deletions {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = rrset.name,
+                kind = rrset.kind,
+                "Deleting record",
+            );
+            rrset.changetype = Some(CHANGETYPE_DELETE.to_string());
+            rrset.records.clear();
+            rrset.comments.clear();
+            rrsets.push(rrset);
+        }
+
+        for mut rrset in diff.update {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = rrset.name,
+                kind = rrset.kind,
+                content = rrset.display_content(),
+                "Updating record"
+            );
+
+            if rrset.get_instance_id().ne(&Some(&self.instance_id)) {
+                tracing::warn!(
+                    frontend = FRONTEND_NAME,
+                    name = rrset.name,
+                    kind = rrset.kind,
+                    content = rrset.display_content(),
+                    "Record is not managed by this instance but we will update anyway"
+                )
+            }
+            rrset.set_instance_id(&self.instance_id);
+            rrset.changetype = Some(CHANGETYPE_REPLACE.to_string());
+            rrsets.push(rrset);
+        }
+
+        for mut rrset in diff.create {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = rrset.name,
+                kind = rrset.kind,
+                content = rrset.display_content(),
+                "Creating record"
+            );
+            rrset.ttl = self.ttl;
+            rrset.set_instance_id(&self.instance_id);
+            rrset.changetype = Some(CHANGETYPE_REPLACE.to_string());
+            rrsets.push(rrset);
+        }
+
+        self.patch_zone(ZonePatch { rrsets })?;
+
+        tracing::debug!(
+            frontend = FRONTEND_NAME,
+            zone = zone.name,
+            "Applied changes"
+        );
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for PowerDNS {
+    fn from(mut value: super::Config) -> Self {
+        let api_key = key_file_or_string(value.api_key, FRONTEND_NAME.into()).unwrap();
+
+        let server_id = value
+            .server_id
+            .unwrap_or_else(|| DEFAULT_SERVER_ID.to_string());
+        let zone_id = format!("{}.", value.domain.trim_end_matches('.'));
+        value
+            .base_url
+            .path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .extend(&["api", "v1", "servers", &server_id, "zones", &zone_id]);
+
+        Self {
+            api_key,
+            domain: value.domain,
+            instance_id: value.instance_id,
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+            zone_url: value.base_url,
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::common::{
+    format_watermark, parse_watermark, txt_from_zone_value, txt_to_zone_value, Manage, Match,
+    Record, Update, COMMENT_WATERMARK, RECORD_KIND_TXT,
+};
+
+pub(super) const COMMENT_ACCOUNT: &str = "dnssync";
+pub(super) const DEFAULT_TTL: usize = 300;
+
+pub(super) const CHANGETYPE_REPLACE: &str = "REPLACE";
+pub(super) const CHANGETYPE_DELETE: &str = "DELETE";
+
+/// Record kinds whose content is a domain name, which PowerDNS requires
+/// to be fully qualified.
+const NAME_KINDS: [&str; 4] = ["CNAME", "NS", "PTR", "DNAME"];
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIError {
+    pub error: String,
+}
+
+impl APIError {
+    pub(super) fn detail(self) -> Option<String> {
+        Some(self.error)
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Zone {
+    pub name: String,
+    pub rrsets: Vec<RRSet>,
+}
+
+#[derive(serde::Serialize)]
+pub(super) struct ZonePatch {
+    pub rrsets: Vec<RRSet>,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct RecordData {
+    pub content: String,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct Comment {
+    pub content: String,
+    #[serde(default)]
+    pub account: String,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub(super) struct RRSet {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub ttl: usize,
+    pub records: Vec<RecordData>,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub changetype: Option<String>,
+}
+
+fn to_fqdn(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+/// Converts DNSSync record content to the PowerDNS presentation format.
+fn to_content(kind: &str, content: &str) -> String {
+    if kind.eq_ignore_ascii_case(RECORD_KIND_TXT) {
+        txt_to_zone_value(content)
+    } else if NAME_KINDS.iter().any(|k| k.eq_ignore_ascii_case(kind)) {
+        to_fqdn(content)
+    } else {
+        content.to_string()
+    }
+}
+
+/// Converts PowerDNS presentation format to DNSSync record content.
+fn from_content(kind: &str, content: &str) -> String {
+    if kind.eq_ignore_ascii_case(RECORD_KIND_TXT) {
+        txt_from_zone_value(content)
+    } else if NAME_KINDS.iter().any(|k| k.eq_ignore_ascii_case(kind)) {
+        content.trim_end_matches('.').to_string()
+    } else {
+        content.to_string()
+    }
+}
+
+/// Converts newline separated DNSSync content to one record per value.
+fn to_records(kind: &str, content: &str) -> Vec<RecordData> {
+    content
+        .split('\n')
+        .map(|value| RecordData {
+            content: to_content(kind, value),
+            disabled: false,
+        })
+        .collect()
+}
+
+impl RRSet {
+    fn owner_comment(&self) -> Option<&Comment> {
+        self.comments
+            .iter()
+            .find(|c| c.content.contains(COMMENT_WATERMARK))
+    }
+
+    pub(super) fn get_instance_id(&self) -> Option<&str> {
+        self.owner_comment()
+            .and_then(|comment| parse_watermark(&comment.content))
+    }
+
+    /// Replaces our comment on the RRset, leaving comments made by others.
+    pub(super) fn set_instance_id(&mut self, instance_id: &str) {
+        self.comments
+            .retain(|c| !c.content.contains(COMMENT_WATERMARK));
+        self.comments.push(Comment {
+            content: format_watermark(Some(instance_id), None),
+            account: COMMENT_ACCOUNT.to_string(),
+        });
+    }
+
+    /// The values of the RRset as DNSSync content, sorted
+    /// so the same set always has the same content.
+    pub(super) fn display_content(&self) -> String {
+        let mut values: Vec<String> = self
+            .records
+            .iter()
+            .map(|r| from_content(&self.kind, &r.content))
+            .collect();
+        values.sort();
+        values.dedup();
+        values.join("\n")
+    }
+}
+
+impl Manage for RRSet {
+    fn is_managed(&self) -> bool {
+        self.owner_comment().is_some()
+    }
+}
+
+impl Match for RRSet {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RRSet {
+    fn update(mut self, authority: Record) -> Self {
+        self.records = to_records(&self.kind, &authority.content);
+        self
+    }
+}
+
+impl PartialEq for RRSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.display_content() == other.display_content()
+    }
+}
+
+impl From<RRSet> for Record {
+    fn from(value: RRSet) -> Self {
+        Record {
+            kind: value.kind.to_uppercase(),
+            content: value.display_content(),
+            name: url::Host::Domain(value.name.trim_end_matches('.').to_string()),
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for RRSet {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        Self {
+            name: to_fqdn(&value.name.to_string()),
+            records: to_records(&kind, &value.content),
+            kind,
+            ttl: DEFAULT_TTL,
+            comments: Vec::new(),
+            changetype: None,
+        }
+    }
+}
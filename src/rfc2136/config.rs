@@ -0,0 +1,14 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// Primary server address, as host or host:port.
+    pub server: String,
+    pub key_name: String,
+    /// Base64 encoded TSIG secret.
+    pub key_secret: String,
+    /// Defaults to hmac-sha256.
+    pub key_algorithm: Option<String>,
+    pub instance_id: String,
+    pub registry_prefix: Option<String>,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,56 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.rfc2136;
+in
+{
+  options.dnssync.frontends.rfc2136 = {
+    enable = lib.mkEnableOption "RFC 2136 dynamic update frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The zone to update. Must be the zone's apex";
+    };
+    server = mkOption {
+      type = types.str;
+      example = "ns1.example.com:53";
+      description = "The primary server for the zone. Must allow zone transfers and updates with the key";
+    };
+    keyName = mkOption {
+      type = types.str;
+      description = "The name of the TSIG key";
+    };
+    keyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the base64 TSIG secret. Must be owned by the dnssync user";
+    };
+    keyAlgorithm = mkOption {
+      type = types.enum [ "hmac-sha256" "hmac-sha384" "hmac-sha512" ];
+      default = "hmac-sha256";
+      description = "The TSIG key algorithm";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "rfc2136";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_RFC2136_DOMAIN" = cfg.domain;
+      "DNSSYNC_RFC2136_SERVER" = cfg.server;
+      "DNSSYNC_RFC2136_KEY_NAME" = cfg.keyName;
+      "DNSSYNC_RFC2136_KEY_SECRET" = "@${cfg.keyFile}";
+      "DNSSYNC_RFC2136_KEY_ALGORITHM" = cfg.keyAlgorithm;
+      "DNSSYNC_RFC2136_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_RFC2136_TTL" = toString cfg.ttl;
+    };
+  };
+}
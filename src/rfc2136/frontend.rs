@@ -0,0 +1,407 @@
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use hickory_proto::op::{
+    update_message, Message, MessageType, MessageVerifier, OpCode, Query, ResponseCode,
+    UpdateMessage,
+};
+use hickory_proto::rr::dnssec::rdata::tsig::TsigAlgorithm;
+use hickory_proto::rr::dnssec::tsig::TSigner;
+use hickory_proto::rr::{DNSClass, Name, RecordSet, RecordType};
+use snafu::prelude::*;
+
+use crate::common::{
+    self, key_file_or_string, ConfigSnafu, FrontendSnafu, Record, ResponseSnafu, Result,
+    TxtRegistry,
+};
+
+use super::models::{DNSRecord, DEFAULT_TTL};
+
+pub const FRONTEND_NAME: &str = "RFC2136";
+
+const DEFAULT_PORT: u16 = 53;
+const DEFAULT_KEY_ALGORITHM: &str = "hmac-sha256";
+const TSIG_FUDGE: u16 = 300;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Rfc2136 {
+    domain: String,
+    zone: Name,
+    server: String,
+    signer: TSigner,
+    registry: TxtRegistry,
+    ttl: u32,
+}
+
+fn unix_time() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or_default()
+}
+
+fn check_response(message: &Message) -> Result<()> {
+    match message.response_code() {
+        ResponseCode::NoError => Ok(()),
+        code => ResponseSnafu {
+            message: format!("Server responded with {code}"),
+        }
+        .fail(),
+    }
+}
+
+impl Rfc2136 {
+    fn connect(&self) -> Result<TcpStream> {
+        tracing::debug!(
+            server = self.server,
+            frontend = "rfc2136",
+            "Connecting to server"
+        );
+        let stream = TcpStream::connect(&self.server)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to connect to {}", self.server),
+            })?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to set socket timeouts",
+            })?;
+        Ok(stream)
+    }
+
+    /// Signs the message, returning a verifier for the response(s).
+    fn sign(&self, message: &mut Message) -> Result<MessageVerifier> {
+        let verifier = message
+            .finalize(&self.signer, unix_time())
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to sign message",
+            })?;
+        Ok(verifier.expect("TSIG signing always produces a verifier"))
+    }
+
+    fn write_message(&self, stream: &mut TcpStream, message: &Message) -> Result<()> {
+        let bytes = message.to_vec().boxed_local().context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: "Failed to encode message",
+        })?;
+        stream
+            .write_all(&(bytes.len() as u16).to_be_bytes())
+            .and_then(|_| stream.write_all(&bytes))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to send message to {}", self.server),
+            })
+    }
+
+    fn read_message(
+        &self,
+        stream: &mut TcpStream,
+        verifier: &mut MessageVerifier,
+    ) -> Result<Message> {
+        let mut len = [0u8; 2];
+        stream
+            .read_exact(&mut len)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read response from {}", self.server),
+            })?;
+        let mut bytes = vec![0u8; u16::from_be_bytes(len) as usize];
+        stream
+            .read_exact(&mut bytes)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read response from {}", self.server),
+            })?;
+
+        let response: Message = verifier(&bytes)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to verify response",
+            })?
+            .into();
+        check_response(&response)?;
+        Ok(response)
+    }
+
+    /// Reads every record in the zone with a zone transfer (AXFR).
+    pub(super) fn read_records(&self) -> Result<Vec<DNSRecord>> {
+        let mut message = update_message::zone_transfer(self.zone.clone(), None);
+        let mut verifier = self.sign(&mut message)?;
+        let mut stream = self.connect()?;
+        self.write_message(&mut stream, &message)?;
+
+        // The transfer begins and ends with the zone's SOA record
+        let mut records = Vec::new();
+        let mut soa_count = 0;
+        while soa_count < 2 {
+            let response = self.read_message(&mut stream, &mut verifier)?;
+            if response.answers().is_empty() {
+                return ResponseSnafu {
+                    message: "Zone transfer ended unexpectedly",
+                }
+                .fail();
+            }
+            for record in response.answers() {
+                match record.record_type() {
+                    RecordType::SOA => soa_count += 1,
+                    _ => records.extend(DNSRecord::from_wire(record)),
+                }
+            }
+        }
+
+        Ok(records)
+    }
+
+    fn apply(&self, mut message: Message) -> Result<()> {
+        let mut verifier = self.sign(&mut message)?;
+        let mut stream = self.connect()?;
+        self.write_message(&mut stream, &message)?;
+        self.read_message(&mut stream, &mut verifier)?;
+        Ok(())
+    }
+
+    fn to_wire(&self, record: &DNSRecord, rdata: hickory_proto::rr::RData) -> Result<RecordSet> {
+        let name = Name::from_ascii(format!("{}.", record.name))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Invalid record name {}", record.name),
+            })?;
+        if !self.zone.zone_of(&name) {
+            return ResponseSnafu {
+                message: format!("Record {} is outside of zone {}", record.name, self.zone),
+            }
+            .fail();
+        }
+        Ok(hickory_proto::rr::Record::from_rdata(name, self.ttl, rdata).into())
+    }
+
+    /// Creates the record, on condition that no record of its name and kind exists.
+    fn create_message(&self, record: &DNSRecord) -> Result<Message> {
+        Ok(update_message::create(
+            self.to_wire(record, record.desired_rdata()?)?,
+            self.zone.clone(),
+            false,
+        ))
+    }
+
+    /// Replaces the record, on condition that it still has its current content.
+    fn update_message(&self, record: &DNSRecord) -> Result<Message> {
+        let mut current = self.to_wire(record, record.current_rdata()?)?;
+        current.set_ttl(record.ttl);
+        Ok(update_message::compare_and_swap(
+            current,
+            self.to_wire(record, record.desired_rdata()?)?,
+            self.zone.clone(),
+            false,
+        ))
+    }
+
+    /// Deletes the record, on condition that it still has its current content.
+    fn delete_message(&self, record: &DNSRecord) -> Result<Message> {
+        let rrset = self.to_wire(record, record.current_rdata()?)?;
+        let mut prerequisite = rrset.clone();
+        prerequisite.set_ttl(0);
+
+        let mut message = update_message::delete_by_rdata(rrset, self.zone.clone(), false);
+        message.add_pre_requisites(prerequisite);
+        Ok(message)
+    }
+
+    /// An update which changes nothing, to check the key is allowed to update the zone.
+    fn noop_message(&self) -> Message {
+        let mut zone = Query::new();
+        zone.set_name(self.zone.clone())
+            .set_query_class(DNSClass::IN)
+            .set_query_type(RecordType::SOA);
+
+        let mut message = Message::new();
+        message
+            .set_id(uuid::Uuid::new_v4().as_u128() as u16)
+            .set_message_type(MessageType::Query)
+            .set_op_code(OpCode::Update)
+            .set_recursion_desired(false);
+        message.add_zone(zone);
+
+        // Require that the zone exists, i.e. has an SOA record
+        let mut soa = hickory_proto::rr::Record::with(self.zone.clone(), RecordType::SOA, 0);
+        soa.set_dns_class(DNSClass::ANY);
+        message.add_pre_requisite(soa);
+        message
+    }
+}
+
+impl common::Frontend for Rfc2136 {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.read_records()?;
+        self.apply(self.noop_message())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let current = self.read_records()?;
+        let diff = self.registry.diff(current, authority);
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.records.create.len(),
+                update = diff.records.update.len(),
+                delete = diff.records.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.records.create.len(),
+            update = diff.records.update.len(),
+            delete = diff.records.delete.len(),
+            "Applying changes",
+        );
+
+        // Each change is sent as its own update, so that a failed
+        // prerequisite only affects the record it concerns.
+        // Deletes first - to avoid conflicts with created records.
+        for record in diff.records.delete.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            self.apply(self.delete_message(record)?)?;
+        }
+
+        for record in diff.delete_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Deleting ownership record",
+            );
+            self.apply(self.delete_message(record)?)?;
+        }
+
+        for record in diff.records.update.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+            self.apply(self.update_message(record)?)?;
+        }
+
+        for record in diff.records.create.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            self.apply(self.create_message(record)?)?;
+        }
+
+        for record in diff.create_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Creating ownership record",
+            );
+            self.apply(self.create_message(record)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn config_error(message: String) -> common::Error {
+    ConfigSnafu {
+        message,
+        prefix: FRONTEND_NAME,
+    }
+    .build()
+}
+
+/// Appends the default port to the server address if it has none.
+fn with_default_port(server: String) -> String {
+    if server.parse::<SocketAddr>().is_ok() {
+        return server;
+    }
+    match server.parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::new(ip, DEFAULT_PORT).to_string(),
+        Err(_) if server.contains(':') => server,
+        Err(_) => format!("{server}:{DEFAULT_PORT}"),
+    }
+}
+
+impl From<super::Config> for Rfc2136 {
+    fn from(value: super::Config) -> Self {
+        let secret = key_file_or_string(value.key_secret, FRONTEND_NAME.into()).unwrap();
+        let secret = base64::engine::general_purpose::STANDARD
+            .decode(secret)
+            .map_err(|err| config_error(format!("Invalid key secret: {err}")))
+            .unwrap();
+
+        let algorithm = value
+            .key_algorithm
+            .unwrap_or_else(|| DEFAULT_KEY_ALGORITHM.to_string())
+            .to_lowercase();
+        let algorithm = Name::from_ascii(&algorithm)
+            .map(TsigAlgorithm::from_name)
+            .map_err(|err| config_error(format!("Invalid key algorithm {algorithm}: {err}")))
+            .unwrap();
+
+        let key_name = Name::from_ascii(&value.key_name)
+            .map_err(|err| config_error(format!("Invalid key name {}: {err}", value.key_name)))
+            .unwrap();
+
+        let signer = TSigner::new(secret, algorithm, key_name, TSIG_FUDGE)
+            .map_err(|err| config_error(format!("Unsupported key: {err}")))
+            .unwrap();
+
+        let zone = Name::from_ascii(format!("{}.", value.domain.trim_end_matches('.')))
+            .map_err(|err| config_error(format!("Invalid domain {}: {err}", value.domain)))
+            .unwrap();
+
+        Self {
+            domain: value.domain,
+            zone,
+            server: with_default_port(value.server),
+            signer,
+            registry: TxtRegistry::new(value.registry_prefix, value.instance_id),
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use hickory_proto::rr::RData;
+
+use snafu::ResultExt;
+
+use crate::common::{
+    content_to_rdata, rdata_to_content, FrontendSnafu, Match, Record, Result, Update,
+};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+#[derive(Clone)]
+pub(super) struct DNSRecord {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: u32,
+    // The data currently in the zone, used to match the record exactly
+    // when replacing or deleting it. None for records not yet created.
+    pub rdata: Option<RData>,
+}
+
+impl DNSRecord {
+    pub(super) fn from_wire(record: &hickory_proto::rr::Record) -> Option<Self> {
+        let rdata = record.data()?.clone();
+        Some(Self {
+            name: normalize_name(&record.name().to_ascii()),
+            kind: record.record_type().to_string(),
+            content: rdata_to_content(&rdata),
+            ttl: record.ttl(),
+            rdata: Some(rdata),
+        })
+    }
+
+    /// The data currently in the zone for this record.
+    pub(super) fn current_rdata(&self) -> Result<RData> {
+        match &self.rdata {
+            Some(rdata) => Ok(rdata.clone()),
+            None => self.desired_rdata(),
+        }
+    }
+
+    /// The data this record should have once written.
+    pub(super) fn desired_rdata(&self) -> Result<RData> {
+        content_to_rdata(&self.kind, &self.content).context(FrontendSnafu {
+            frontend: super::FRONTEND_NAME,
+            message: format!("Unsupported {} record content {}", self.kind, self.content),
+        })
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+impl Match for DNSRecord {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for DNSRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = DNSRecord::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for DNSRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<DNSRecord> for Record {
+    fn from(value: DNSRecord) -> Self {
+        Record {
+            kind: value.kind.to_uppercase(),
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for DNSRecord {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        // Round trip the content so that it compares equal to the zone's copy
+        let content = match content_to_rdata(&kind, &value.content) {
+            Ok(rdata) => rdata_to_content(&rdata),
+            Err(_) => value.content,
+        };
+        Self {
+            name: normalize_name(&value.name.to_string()),
+            kind,
+            content,
+            ttl: DEFAULT_TTL,
+            rdata: None,
+        }
+    }
+}
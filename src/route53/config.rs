@@ -0,0 +1,16 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub access_key_id: String,
+    /// The secret access key, or @ followed by a path to read it from.
+    pub secret_access_key: String,
+    pub domain: String,
+    pub instance_id: String,
+    /// Looked up by the domain if not set.
+    pub hosted_zone_id: Option<String>,
+    /// Override the API base URL, e.g. to use a local emulator.
+    pub base_url: Option<url::Url>,
+    /// The region requests are signed for. Defaults to us-east-1.
+    pub region: Option<String>,
+    pub registry_prefix: Option<String>,
+    pub ttl: Option<u32>,
+}
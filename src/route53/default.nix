@@ -0,0 +1,66 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.route53;
+in
+{
+  options.dnssync.frontends.route53 = {
+    enable = lib.mkEnableOption "Amazon Route 53 frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The domain of the hosted zone to manage";
+    };
+    accessKeyId = mkOption {
+      type = types.str;
+      description = "The access key ID of an IAM user allowed to change the hosted zone";
+    };
+    secretAccessKeyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the secret access key. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    hostedZoneId = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "Z0123456789ABCDEFGHIJ";
+      description = "The hosted zone ID. Looked up by the domain if not set";
+    };
+    baseUrl = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "http://localhost:5000";
+      description = "Override the Route 53 API base URL, e.g. to use a local emulator";
+    };
+    region = mkOption {
+      type = types.str;
+      default = "us-east-1";
+      description = "The region requests are signed for";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "route53";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_ROUTE53_DOMAIN" = cfg.domain;
+      "DNSSYNC_ROUTE53_ACCESS_KEY_ID" = cfg.accessKeyId;
+      "DNSSYNC_ROUTE53_SECRET_ACCESS_KEY" = "@${cfg.secretAccessKeyFile}";
+      "DNSSYNC_ROUTE53_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_ROUTE53_REGION" = cfg.region;
+      "DNSSYNC_ROUTE53_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.hostedZoneId != null) {
+      "DNSSYNC_ROUTE53_HOSTED_ZONE_ID" = cfg.hostedZoneId;
+    } // lib.optionalAttrs (cfg.baseUrl != null) {
+      "DNSSYNC_ROUTE53_BASE_URL" = cfg.baseUrl;
+    };
+  };
+}
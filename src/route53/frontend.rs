@@ -0,0 +1,326 @@
+use std::time::SystemTime;
+
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, group_rrsets, key_file_or_string, request_error_text, FrontendSnafu, Record,
+    ResponseSnafu, Result, TxtRegistry,
+};
+
+use super::models::{
+    error_detail, Change, ChangeBatch, ChangeResourceRecordSetsRequest, Changes,
+    ListHostedZonesByNameResponse, ListResourceRecordSetsResponse, RecordSet, ACTION_CREATE,
+    ACTION_DELETE, ACTION_UPSERT, DEFAULT_TTL, XMLNS,
+};
+use super::signing::{canonical_query, sign, Credentials};
+
+pub const API_BASE_URL: &str = "https://route53.amazonaws.com";
+
+pub const FRONTEND_NAME: &str = "Route53";
+
+const API_VERSION: &str = "2013-04-01";
+const DEFAULT_REGION: &str = "us-east-1";
+const HOSTED_ZONE_PREFIX: &str = "/hostedzone/";
+
+/// Route 53 accepts at most 1000 changes in a batch.
+const MAX_BATCH_CHANGES: usize = 1000;
+
+pub struct Route53 {
+    credentials: Credentials,
+    base_url: url::Url,
+    domain: String,
+    hosted_zone_id: Option<String>,
+    registry: TxtRegistry,
+    ttl: u32,
+}
+
+impl Route53 {
+    fn api_url(&self, path: &[&str], query: &[(&str, &str)]) -> url::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .push(API_VERSION)
+            .extend(path);
+        let query = canonical_query(query);
+        url.set_query((!query.is_empty()).then_some(query.as_str()));
+        url
+    }
+
+    fn api_call<T: DeserializeOwned>(&self, method: &str, url: url::Url, body: &str) -> Result<T> {
+        tracing::debug!(
+            url = url.as_str(),
+            method = method,
+            frontend = "route53",
+            "Sending request"
+        );
+        let signature = sign(&self.credentials, method, &url, body, SystemTime::now());
+        let req = ureq::request(method, url.as_str())
+            .set("X-Amz-Date", &signature.amz_date)
+            .set("Authorization", &signature.authorization);
+        let resp = match body.is_empty() {
+            true => req.call(),
+            false => req.set("Content-Type", "application/xml").send_string(body),
+        }
+        .map_err(|err| request_error_text(err, url.as_str(), method, error_detail))?;
+
+        let text = resp.into_string().boxed_local().context(FrontendSnafu {
+            frontend: FRONTEND_NAME,
+            message: "Failed to read response",
+        })?;
+        quick_xml::de::from_str(&text)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })
+    }
+
+    fn get_zone_id(&mut self) -> Result<String> {
+        if let Some(id) = &self.hosted_zone_id {
+            return Ok(id.clone());
+        }
+
+        let url = self.api_url(
+            &["hostedzonesbyname"],
+            &[("dnsname", &self.domain), ("maxitems", "1")],
+        );
+        let resp: ListHostedZonesByNameResponse = self.api_call("GET", url, "")?;
+
+        // Zones are listed from the given name onwards, so check it is the one we asked for
+        let zone_name = format!("{}.", self.domain.trim_end_matches('.'));
+        let zone = resp
+            .hosted_zones
+            .items
+            .into_iter()
+            .find(|zone| zone.name.eq_ignore_ascii_case(&zone_name))
+            .context(ResponseSnafu {
+                message: format!("No hosted zone found for {}", self.domain),
+            })?;
+
+        let id = zone
+            .id
+            .strip_prefix(HOSTED_ZONE_PREFIX)
+            .unwrap_or(&zone.id)
+            .to_string();
+        self.hosted_zone_id = Some(id.clone());
+        Ok(id)
+    }
+
+    fn read_records(&self, zone_id: &str) -> Result<Vec<RecordSet>> {
+        let mut records = Vec::new();
+        let mut next: Option<(String, String, Option<String>)> = None;
+        loop {
+            let mut query: Vec<(&str, &str)> = Vec::new();
+            if let Some((name, kind, identifier)) = &next {
+                query.push(("name", name));
+                query.push(("type", kind));
+                if let Some(identifier) = identifier {
+                    query.push(("identifier", identifier));
+                }
+            }
+            let url = self.api_url(&["hostedzone", zone_id, "rrset"], &query);
+            let resp: ListResourceRecordSetsResponse = self.api_call("GET", url, "")?;
+
+            for rrset in resp.resource_record_sets.items {
+                let (name, kind) = (rrset.name.clone(), rrset.kind.clone());
+                match RecordSet::from_api(rrset) {
+                    Some(record) => records.push(record),
+                    None => tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = name,
+                        kind = kind,
+                        "Skipping alias or routing policy record set"
+                    ),
+                }
+            }
+
+            match (
+                resp.is_truncated,
+                resp.next_record_name,
+                resp.next_record_type,
+            ) {
+                (true, Some(name), Some(kind)) => {
+                    next = Some((name, kind, resp.next_record_identifier))
+                }
+                _ => return Ok(records),
+            }
+        }
+    }
+
+    fn change(&self, zone_id: &str, changes: Vec<Change>) -> Result<()> {
+        // Each batch is applied atomically by Route 53
+        let mut changes = changes.into_iter().peekable();
+        while changes.peek().is_some() {
+            let batch: Vec<Change> = changes.by_ref().take(MAX_BATCH_CHANGES).collect();
+            let request = ChangeResourceRecordSetsRequest {
+                xmlns: XMLNS,
+                change_batch: ChangeBatch {
+                    comment: format!("DNSSync {}", env!("CARGO_PKG_VERSION")),
+                    changes: Changes { items: batch },
+                },
+            };
+            let body =
+                quick_xml::se::to_string_with_root("ChangeResourceRecordSetsRequest", &request)
+                    .boxed_local()
+                    .context(FrontendSnafu {
+                        frontend: FRONTEND_NAME,
+                        message: "Failed to serialize change batch",
+                    })?;
+            let url = self.api_url(&["hostedzone", zone_id, "rrset", ""], &[]);
+            self.api_call::<serde::de::IgnoredAny>("POST", url, &body)?;
+        }
+        Ok(())
+    }
+}
+
+impl common::Frontend for Route53 {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        // Route 53 has no way to check change permission without making a change
+        let zone_id = self.get_zone_id()?;
+        self.read_records(&zone_id).map(|_| ())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let zone_id = self.get_zone_id()?;
+        let current = self.read_records(&zone_id)?;
+        let mut diff = self.registry.diff(current, group_rrsets(authority));
+        for record in diff.records.create.iter_mut() {
+            record.record.ttl = self.ttl;
+        }
+        for record in diff.create_owners.iter_mut() {
+            record.ttl = self.ttl;
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.records.create.len(),
+                update = diff.records.update.len(),
+                delete = diff.records.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.records.create.len(),
+            update = diff.records.update.len(),
+            delete = diff.records.delete.len(),
+            "Applying changes",
+        );
+
+        let mut changes: Vec<Change> = Vec::with_capacity(diff.len());
+
+        // Deletes first - to avoid conflicts with created records.
+        for record in diff.records.delete.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            changes.push(Change {
+                action: ACTION_DELETE,
+                resource_record_set: record.current(),
+            });
+        }
+        for record in diff.delete_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Deleting ownership record",
+            );
+            changes.push(Change {
+                action: ACTION_DELETE,
+                resource_record_set: record.current(),
+            });
+        }
+
+        for record in diff.records.update.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+            changes.push(Change {
+                action: ACTION_UPSERT,
+                resource_record_set: record.desired(),
+            });
+        }
+
+        // Creates fail if the record set was made since it was read
+        for record in diff.records.create.iter().map(|r| &r.record) {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            changes.push(Change {
+                action: ACTION_CREATE,
+                resource_record_set: record.desired(),
+            });
+        }
+        for record in diff.create_owners.iter() {
+            tracing::debug!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                "Creating ownership record",
+            );
+            changes.push(Change {
+                action: ACTION_CREATE,
+                resource_record_set: record.desired(),
+            });
+        }
+
+        self.change(&zone_id, changes)
+    }
+}
+
+impl From<super::Config> for Route53 {
+    fn from(value: super::Config) -> Self {
+        let secret_access_key =
+            key_file_or_string(value.secret_access_key, FRONTEND_NAME.into()).unwrap();
+
+        let base_url = value
+            .base_url
+            .unwrap_or_else(|| url::Url::parse(API_BASE_URL).unwrap());
+
+        Self {
+            credentials: Credentials {
+                access_key_id: value.access_key_id,
+                secret_access_key,
+                region: value.region.unwrap_or_else(|| DEFAULT_REGION.to_string()),
+            },
+            base_url,
+            domain: value.domain,
+            hosted_zone_id: value
+                .hosted_zone_id
+                .map(|id| id.trim_start_matches(HOSTED_ZONE_PREFIX).to_string()),
+            registry: TxtRegistry::new(value.registry_prefix, value.instance_id),
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod config;
+mod frontend;
+mod models;
+mod signing;
+
+pub use config::*;
+pub use frontend::*;
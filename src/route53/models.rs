@@ -0,0 +1,300 @@
+use crate::common::{
+    join_values, normalise_content, txt_from_zone_value, txt_to_zone_value, Match, Record, Update,
+    RECORD_KIND_TXT,
+};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+pub(super) const XMLNS: &str = "https://route53.amazonaws.com/doc/2013-04-01/";
+
+pub(super) const ACTION_CREATE: &str = "CREATE";
+pub(super) const ACTION_DELETE: &str = "DELETE";
+pub(super) const ACTION_UPSERT: &str = "UPSERT";
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ErrorResponse {
+    pub error: ErrorDetail,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ErrorDetail {
+    pub code: String,
+    pub message: String,
+}
+
+/// Returned instead of ErrorResponse when a change batch is rejected.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct InvalidChangeBatch {
+    pub messages: Messages,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Messages {
+    #[serde(rename = "Message", default)]
+    pub items: Vec<String>,
+}
+
+/// Finds the error message in a response body, which
+/// is one of two documents depending on what went wrong.
+pub(super) fn error_detail(body: String) -> Option<String> {
+    quick_xml::de::from_str::<ErrorResponse>(&body)
+        .map(|e| format!("{}: {}", e.error.code, e.error.message))
+        .or_else(|_| {
+            quick_xml::de::from_str::<InvalidChangeBatch>(&body)
+                .map(|e| e.messages.items.join("; "))
+        })
+        .ok()
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct HostedZone {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct HostedZones {
+    #[serde(rename = "HostedZone", default)]
+    pub items: Vec<HostedZone>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ListHostedZonesByNameResponse {
+    pub hosted_zones: HostedZones,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ListResourceRecordSetsResponse {
+    pub resource_record_sets: ResourceRecordSets,
+    pub is_truncated: bool,
+    pub next_record_name: Option<String>,
+    pub next_record_type: Option<String>,
+    pub next_record_identifier: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct ResourceRecordSets {
+    #[serde(rename = "ResourceRecordSet", default)]
+    pub items: Vec<ResourceRecordSet>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ResourceRecordSet {
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub kind: String,
+    // Routing policy and alias record sets are never managed
+    #[serde(skip_serializing)]
+    pub set_identifier: Option<String>,
+    #[serde(skip_serializing)]
+    pub alias_target: Option<serde::de::IgnoredAny>,
+    #[serde(rename = "TTL", skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    pub resource_records: Option<ResourceRecords>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub(super) struct ResourceRecords {
+    #[serde(rename = "ResourceRecord", default)]
+    pub items: Vec<ResourceRecord>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ResourceRecord {
+    pub value: String,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ChangeResourceRecordSetsRequest {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: &'static str,
+    pub change_batch: ChangeBatch,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct ChangeBatch {
+    pub comment: String,
+    pub changes: Changes,
+}
+
+#[derive(serde::Serialize)]
+pub(super) struct Changes {
+    #[serde(rename = "Change")]
+    pub items: Vec<Change>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct Change {
+    pub action: &'static str,
+    pub resource_record_set: ResourceRecordSet,
+}
+
+/// Decodes the \ddd escapes Route 53 uses for special characters in names.
+fn decode_name(name: &str) -> String {
+    let mut decoded = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            decoded.push(c);
+            continue;
+        }
+        let digits: String = chars.clone().take(3).collect();
+        match u8::from_str_radix(&digits, 8) {
+            Ok(b) if digits.len() == 3 => {
+                decoded.push(b as char);
+                chars.nth(2);
+            }
+            _ => decoded.push(c),
+        }
+    }
+    decoded.trim_end_matches('.').to_lowercase()
+}
+
+/// Converts a record value in zone file format to DNSSync content.
+fn value_to_content(kind: &str, value: &str) -> String {
+    if kind == RECORD_KIND_TXT {
+        return txt_from_zone_value(value);
+    }
+    normalise_content(kind, value)
+}
+
+fn content_to_value(kind: &str, content: &str) -> String {
+    match kind {
+        RECORD_KIND_TXT => txt_to_zone_value(content),
+        _ => content.to_string(),
+    }
+}
+
+/// A record set, with its values as one record separated by newlines.
+#[derive(Clone)]
+pub(super) struct RecordSet {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    pub ttl: u32,
+    // The values currently in the zone, as deleting a record set
+    // requires them exactly. Empty for record sets not yet created.
+    pub values: Vec<String>,
+}
+
+impl RecordSet {
+    /// Returns None for record sets which cannot be represented as a record.
+    pub(super) fn from_api(rrset: ResourceRecordSet) -> Option<Self> {
+        if rrset.set_identifier.is_some() || rrset.alias_target.is_some() {
+            return None;
+        }
+        let values: Vec<String> = rrset
+            .resource_records?
+            .items
+            .into_iter()
+            .map(|r| r.value)
+            .collect();
+        let content = join_values(
+            values
+                .iter()
+                .map(|value| value_to_content(&rrset.kind, value))
+                .collect(),
+        );
+        Some(Self {
+            name: decode_name(&rrset.name),
+            kind: rrset.kind,
+            content,
+            ttl: rrset.ttl.unwrap_or(DEFAULT_TTL),
+            values,
+        })
+    }
+
+    /// The record set as it should be once written.
+    pub(super) fn desired(&self) -> ResourceRecordSet {
+        self.to_api(
+            self.content
+                .split('\n')
+                .map(|content| content_to_value(&self.kind, content))
+                .collect(),
+        )
+    }
+
+    /// The record set as it currently is in the zone.
+    pub(super) fn current(&self) -> ResourceRecordSet {
+        match self.values.is_empty() {
+            true => self.desired(),
+            false => self.to_api(self.values.clone()),
+        }
+    }
+
+    fn to_api(&self, values: Vec<String>) -> ResourceRecordSet {
+        ResourceRecordSet {
+            name: format!("{}.", self.name),
+            kind: self.kind.clone(),
+            set_identifier: None,
+            alias_target: None,
+            ttl: Some(self.ttl),
+            resource_records: Some(ResourceRecords {
+                items: values
+                    .into_iter()
+                    .map(|value| ResourceRecord { value })
+                    .collect(),
+            }),
+        }
+    }
+}
+
+impl Match for RecordSet {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for RecordSet {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = RecordSet::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for RecordSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<RecordSet> for Record {
+    fn from(value: RecordSet) -> Self {
+        Record {
+            kind: value.kind,
+            name: url::Host::Domain(value.name),
+            content: value.content,
+            source: super::FRONTEND_NAME.to_string(),
+        }
+    }
+}
+
+impl From<Record> for RecordSet {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        let content = join_values(
+            value
+                .content
+                .split('\n')
+                .map(|content| normalise_content(&kind, content))
+                .collect(),
+        );
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            kind,
+            content,
+            ttl: DEFAULT_TTL,
+            values: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::SystemTime;
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const SERVICE: &str = "route53";
+
+pub(super) struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub region: String,
+}
+
+/// The headers which authenticate a request.
+pub(super) struct Signature {
+    pub amz_date: String,
+    pub authorization: String,
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Percent encodes everything but the unreserved characters, as SigV4 requires.
+pub(super) fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Builds the canonical query string. The same string must be sent in
+/// the request URL, as any other encoding would change the signature.
+pub(super) fn canonical_query(query: &[(&str, &str)]) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// Signs a request with AWS Signature Version 4.
+/// See https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_sigv.html
+pub(super) fn sign(
+    credentials: &Credentials,
+    method: &str,
+    url: &url::Url,
+    body: &str,
+    now: SystemTime,
+) -> Signature {
+    // 2015-08-30T12:36:00Z -> 20150830T123600Z
+    let amz_date: String = humantime::format_rfc3339_seconds(now)
+        .to_string()
+        .replace(['-', ':'], "");
+    let date = &amz_date[..8];
+
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let signed_headers = "host;x-amz-date";
+    let canonical_request = [
+        method,
+        url.path(),
+        url.query().unwrap_or_default(),
+        &format!("host:{host}\nx-amz-date:{amz_date}\n"),
+        signed_headers,
+        &sha256_hex(body.as_bytes()),
+    ]
+    .join("\n");
+
+    let scope = format!("{date}/{}/{SERVICE}/aws4_request", credentials.region);
+    let string_to_sign = [
+        ALGORITHM,
+        &amz_date,
+        &scope,
+        &sha256_hex(canonical_request.as_bytes()),
+    ]
+    .join("\n");
+
+    let key = hmac(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        date,
+    );
+    let key = hmac(&key, &credentials.region);
+    let key = hmac(&key, SERVICE);
+    let key = hmac(&key, "aws4_request");
+    let signature = hex::encode(hmac(&key, &string_to_sign));
+
+    Signature {
+        authorization: format!(
+            "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        ),
+        amz_date,
+    }
+}
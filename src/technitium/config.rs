@@ -0,0 +1,10 @@
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub api_key: String,
+    /// The web console URL of the Technitium DNS Server.
+    pub base_url: url::Url,
+    /// A primary zone on the server.
+    pub domain: String,
+    pub instance_id: String,
+    pub ttl: Option<u32>,
+}
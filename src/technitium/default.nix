@@ -0,0 +1,45 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.technitium;
+in
+{
+  options.dnssync.frontends.technitium = {
+    enable = lib.mkEnableOption "Technitium DNS Server frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The zone to update. Must be a primary zone on the Technitium server";
+    };
+    baseUrl = mkOption {
+      type = types.str;
+      example = "http://127.0.0.1:5380";
+      description = "URL of the Technitium web console";
+    };
+    apiKeyFile = mkOption {
+      type = types.path;
+      description = "Path to a file containing the Technitium API token. Must be owned by the dnssync user";
+    };
+    instanceId = mkOption {
+      type = types.str;
+      description = "A unique identifier for this deployment of dnssync."
+        + " Used to avoid collisions with other instances on the same domain";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "technitium";
+    systemd.services.dnssync.requires = [ "network-online.target" ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_TECHNITIUM_DOMAIN" = cfg.domain;
+      "DNSSYNC_TECHNITIUM_BASE_URL" = cfg.baseUrl;
+      "DNSSYNC_TECHNITIUM_API_KEY" = "@${cfg.apiKeyFile}";
+      "DNSSYNC_TECHNITIUM_INSTANCE_ID" = cfg.instanceId;
+      "DNSSYNC_TECHNITIUM_TTL" = toString cfg.ttl;
+    };
+  };
+}
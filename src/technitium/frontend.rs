@@ -0,0 +1,252 @@
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::common::{
+    self, diff_records, key_file_or_string, request_error, FrontendSnafu, Record, ResponseSnafu,
+    Result,
+};
+
+use super::models::{
+    is_supported, APIResponse, DNSRecord, RecordsResponse, DEFAULT_TTL, STATUS_OK,
+    ZONE_TYPE_PRIMARY,
+};
+
+pub const FRONTEND_NAME: &str = "Technitium";
+
+pub struct Technitium {
+    api_key: String,
+    base_url: url::Url,
+    domain: String,
+    instance_id: String,
+    ttl: u32,
+}
+
+impl Technitium {
+    /// Calls a records endpoint. Parameters are sent as a form
+    /// so that the token doesn't appear in the URL.
+    fn api_call<T: DeserializeOwned>(&self, action: &str, params: &[(&str, &str)]) -> Result<T> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("base_url should be a HTTP URL")
+            .pop_if_empty()
+            .extend(&["api", "zones", "records", action]);
+        tracing::debug!(
+            url = url.as_str(),
+            method = "POST",
+            frontend = "technitium",
+            "Sending request"
+        );
+
+        let mut form = vec![("token", self.api_key.as_str()), ("zone", &self.domain)];
+        form.extend_from_slice(params);
+        let resp: APIResponse<T> = ureq::post(url.as_str())
+            .send_form(&form)
+            .map_err(|err| {
+                request_error(err, url.as_str(), "POST", |resp: APIResponse<()>| {
+                    resp.error_message
+                })
+            })?
+            .into_json()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Failed to deserialize response",
+            })?;
+
+        match (resp.status.as_str(), resp.response) {
+            (STATUS_OK, Some(response)) => Ok(response),
+            (status, _) => ResponseSnafu {
+                message: format!(
+                    "POST {url} failed with status {status}: {}",
+                    resp.error_message.unwrap_or_default()
+                ),
+            }
+            .fail(),
+        }
+    }
+
+    fn read_records(&self) -> Result<RecordsResponse> {
+        self.api_call("get", &[("domain", &self.domain), ("listZone", "true")])
+    }
+
+    /// Calls a records endpoint for a single record, with the given rdata parameters.
+    fn write_record(
+        &self,
+        action: &str,
+        record: &DNSRecord,
+        rdata: Vec<(String, String)>,
+    ) -> Result<()> {
+        let ttl = record.ttl.to_string();
+        let mut params = vec![("domain", record.name.as_str()), ("type", &record.kind)];
+        if action != "delete" {
+            params.push(("ttl", &ttl));
+            params.push(("comments", record.comments.as_deref().unwrap_or_default()));
+        }
+        params.extend(rdata.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        self.api_call::<serde_json::Value>(action, &params)
+            .map(|_| ())
+    }
+
+    fn rdata_error(record: &DNSRecord) -> common::Error {
+        ResponseSnafu {
+            message: format!(
+                "Cannot write {} record {} with content {}",
+                record.kind, record.name, record.content
+            ),
+        }
+        .build()
+    }
+}
+
+impl common::Frontend for Technitium {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        let resp = self.read_records()?;
+        if resp.zone.kind != ZONE_TYPE_PRIMARY {
+            return ResponseSnafu {
+                message: format!(
+                    "Zone {} is a {} zone, only primary zones can be edited",
+                    resp.zone.name, resp.zone.kind
+                ),
+            }
+            .fail();
+        }
+        Ok(())
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let authority: Vec<Record> = authority
+            .into_iter()
+            .filter(|r| {
+                let supported = is_supported(&r.kind);
+                if !supported {
+                    tracing::debug!(
+                        frontend = FRONTEND_NAME,
+                        name = r.name.to_string(),
+                        kind = r.kind,
+                        "Skipping unsupported record kind"
+                    );
+                }
+                supported
+            })
+            .collect();
+
+        let current: Vec<DNSRecord> = self
+            .read_records()?
+            .records
+            .into_iter()
+            .filter_map(DNSRecord::from_api)
+            .collect();
+        let diff = diff_records::<DNSRecord>(current, authority);
+
+        // Evaluate deletions more precisely based on instance ID
+        let diff_len = diff.len() - diff.delete.len();
+        let deletions: Vec<DNSRecord> = diff
+            .delete
+            .into_iter()
+            .filter(|record| record.get_instance_id().eq(&Some(&self.instance_id)))
+            .collect();
+        let diff_len = diff_len + deletions.len();
+
+        // Short circuit on no changes
+        if diff_len == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = deletions.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = deletions.len(),
+            "Applying changes",
+        );
+
+        // Deletes first - to avoid conflicts with CNAMEs
+        for record in deletions {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+            let rdata = record.rdata().ok_or_else(|| Self::rdata_error(&record))?;
+            self.write_record("delete", &record, rdata)?;
+        }
+
+        for mut record in diff.update {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+
+            if record.get_instance_id().ne(&Some(&self.instance_id)) {
+                tracing::warn!(
+                    frontend = FRONTEND_NAME,
+                    name = record.name,
+                    kind = record.kind,
+                    content = record.content,
+                    "Record is not managed by this instance but we will update anyway"
+                )
+            }
+            record.set_instance_id(&self.instance_id);
+            let rdata = record
+                .update_rdata()
+                .ok_or_else(|| Self::rdata_error(&record))?;
+            self.write_record("update", &record, rdata)?;
+        }
+
+        for mut record in diff.create {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+            record.ttl = self.ttl;
+            record.set_instance_id(&self.instance_id);
+            let rdata = record.rdata().ok_or_else(|| Self::rdata_error(&record))?;
+            self.write_record("add", &record, rdata)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for Technitium {
+    fn from(value: super::Config) -> Self {
+        let api_key = key_file_or_string(value.api_key, FRONTEND_NAME.into()).unwrap();
+
+        Self {
+            api_key,
+            base_url: value.base_url,
+            domain: value.domain.trim_end_matches('.').to_string(),
+            instance_id: value.instance_id,
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
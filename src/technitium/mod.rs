@@ -0,0 +1,6 @@
+mod config;
+mod frontend;
+mod models;
+
+pub use config::*;
+pub use frontend::*;
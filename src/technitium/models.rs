@@ -0,0 +1,238 @@
+use serde_json::{Map, Value};
+
+use crate::common::{
+    format_watermark, merge_watermark, parse_watermark, Manage, Match, Record, Update,
+    COMMENT_WATERMARK,
+};
+
+pub(super) const DEFAULT_TTL: u32 = 300;
+
+pub(super) const STATUS_OK: &str = "ok";
+pub(super) const ZONE_TYPE_PRIMARY: &str = "Primary";
+
+/// Record kinds whose content is a domain name.
+const NAME_KINDS: [&str; 3] = ["CNAME", "NS", "PTR"];
+
+/// The record kinds DNSSync can write, and the API parameters holding
+/// each field of their content, in presentation order.
+const RDATA_PARAMS: [(&str, &[&str]); 9] = [
+    ("A", &["ipAddress"]),
+    ("AAAA", &["ipAddress"]),
+    ("CNAME", &["cname"]),
+    ("NS", &["nameServer"]),
+    ("PTR", &["ptrName"]),
+    ("TXT", &["text"]),
+    ("MX", &["preference", "exchange"]),
+    ("SRV", &["priority", "weight", "port", "target"]),
+    ("CAA", &["flags", "tag", "value"]),
+];
+
+fn rdata_params(kind: &str) -> Option<&'static [&'static str]> {
+    RDATA_PARAMS
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(kind))
+        .map(|(_, params)| *params)
+}
+
+pub(super) fn is_supported(kind: &str) -> bool {
+    rdata_params(kind).is_some()
+}
+
+/// Every response has this envelope, even errors, which are
+/// usually sent with a 200 status.
+#[derive(serde::Deserialize)]
+pub(super) struct APIResponse<T> {
+    pub status: String,
+    pub response: Option<T>,
+    #[serde(rename = "errorMessage")]
+    pub error_message: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct RecordsResponse {
+    pub zone: Zone,
+    #[serde(default)]
+    pub records: Vec<APIRecord>,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Zone {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct APIRecord {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub ttl: u32,
+    #[serde(rename = "rData", default)]
+    pub rdata: Map<String, Value>,
+    pub comments: Option<String>,
+}
+
+#[derive(Clone)]
+pub(super) struct DNSRecord {
+    pub name: String,
+    pub kind: String,
+    pub content: String,
+    /// The content as currently stored, None if not yet created.
+    pub current: Option<String>,
+    pub ttl: u32,
+    pub comments: Option<String>,
+}
+
+/// Normalises names in content, which may or may not be fully qualified.
+fn normalise_content(kind: &str, content: &str) -> String {
+    let kind = kind.to_uppercase();
+    if NAME_KINDS.contains(&kind.as_str()) {
+        return content.trim_end_matches('.').to_lowercase();
+    }
+    match kind.as_str() {
+        "MX" | "SRV" => match content.rsplit_once(' ') {
+            Some((fields, target)) => {
+                format!("{fields} {}", target.trim_end_matches('.').to_lowercase())
+            }
+            None => content.to_string(),
+        },
+        _ => content.to_string(),
+    }
+}
+
+/// Splits content into the values of the kind's rdata parameters.
+/// The last field takes the rest of the content, as TXT and CAA values may contain spaces.
+fn split_content(kind: &str, content: &str) -> Option<Vec<(&'static str, String)>> {
+    let params = rdata_params(kind)?;
+    let mut fields = content.splitn(params.len(), ' ');
+    params
+        .iter()
+        .map(|param| {
+            let value = fields.next().filter(|v| !v.is_empty())?;
+            // CAA values are quoted in presentation format, but not in the API
+            let value = match kind.eq_ignore_ascii_case("CAA") && *param == "value" {
+                true => value.trim_matches('"'),
+                false => value,
+            };
+            Some((*param, value.to_string()))
+        })
+        .collect()
+}
+
+impl DNSRecord {
+    /// Returns None for record kinds DNSSync doesn't manage, such as SOA.
+    pub(super) fn from_api(record: APIRecord) -> Option<Self> {
+        let kind = record.kind.to_uppercase();
+        let fields = rdata_params(&kind)?
+            .iter()
+            .map(|param| match record.rdata.get(*param) {
+                Some(Value::String(value)) => Some(value.clone()),
+                Some(Value::Number(value)) => Some(value.to_string()),
+                _ => None,
+            })
+            .collect::<Option<Vec<String>>>()?;
+        let content = match kind.as_str() {
+            "CAA" => format!("{} {} \"{}\"", fields[0], fields[1], fields[2]),
+            _ => normalise_content(&kind, &fields.join(" ")),
+        };
+        Some(Self {
+            name: record.name.trim_end_matches('.').to_lowercase(),
+            kind,
+            current: Some(content.clone()),
+            content,
+            ttl: record.ttl,
+            comments: record.comments.filter(|c| !c.is_empty()),
+        })
+    }
+
+    /// The API parameters describing the record's new content.
+    pub(super) fn rdata(&self) -> Option<Vec<(String, String)>> {
+        let fields = split_content(&self.kind, &self.content)?;
+        Some(
+            fields
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    /// The API parameters for an update, which identify the current content
+    /// by the usual parameters, and the new content by their `new` variants.
+    pub(super) fn update_rdata(&self) -> Option<Vec<(String, String)>> {
+        // A CNAME is the only record of its name, so there is nothing to identify
+        if self.kind == "CNAME" {
+            return self.rdata();
+        }
+        let new = split_content(&self.kind, &self.content)?;
+        let current = split_content(&self.kind, self.current.as_deref()?)?;
+        Some(
+            current
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .chain(new.into_iter().map(|(k, v)| {
+                    let mut chars = k.chars();
+                    let capitalised: String = chars
+                        .next()
+                        .map(|c| c.to_ascii_uppercase())
+                        .into_iter()
+                        .chain(chars)
+                        .collect();
+                    (format!("new{capitalised}"), v)
+                }))
+                .collect(),
+        )
+    }
+
+    pub(super) fn get_instance_id(&self) -> Option<&str> {
+        self.comments.as_deref().and_then(parse_watermark)
+    }
+
+    /// Marks the record as managed by instance_id.
+    /// Any text before the watermark is kept as is.
+    pub(super) fn set_instance_id(&mut self, instance_id: &str) {
+        let managed = format_watermark(Some(instance_id), None);
+        self.comments = merge_watermark(self.comments.as_deref(), Some(&managed));
+    }
+}
+
+impl Manage for DNSRecord {
+    fn is_managed(&self) -> bool {
+        self.comments
+            .as_ref()
+            .is_some_and(|c| c.contains(COMMENT_WATERMARK))
+    }
+}
+
+impl Match for DNSRecord {
+    fn matches(&self, other: &Self) -> bool {
+        self.kind.eq_ignore_ascii_case(&other.kind) && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Update for DNSRecord {
+    fn update(mut self, authority: Record) -> Self {
+        self.content = DNSRecord::from(authority).content;
+        self
+    }
+}
+
+impl PartialEq for DNSRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other) && self.content == other.content
+    }
+}
+
+impl From<Record> for DNSRecord {
+    fn from(value: Record) -> Self {
+        let kind = value.kind.to_uppercase();
+        Self {
+            name: value.name.to_string().trim_end_matches('.').to_lowercase(),
+            content: normalise_content(&kind, &value.content),
+            kind,
+            current: None,
+            ttl: DEFAULT_TTL,
+            comments: Some(COMMENT_WATERMARK.to_string()),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Config {
+    pub domain: String,
+    /// The config snippet to write, for use with `include:`. It is entirely managed by DNSSync.
+    pub path: PathBuf,
+    /// Also apply changes to the running server with unbound-control.
+    #[serde(default)]
+    pub live: bool,
+    /// Defaults to unbound-control. May include arguments, e.g. `unbound-control -c /etc/unbound/unbound.conf`.
+    pub control_command: Option<String>,
+    /// Shell command run after the file changes, e.g. `unbound-control reload`.
+    pub reload_command: Option<String>,
+    pub ttl: Option<u32>,
+}
@@ -0,0 +1,52 @@
+{ config, lib, ... }:
+let
+  inherit (lib) types mkOption;
+  cfg = config.dnssync.frontends.unbound;
+in
+{
+  options.dnssync.frontends.unbound = {
+    enable = lib.mkEnableOption "Unbound local-data frontend for records";
+    domain = mkOption {
+      type = types.str;
+      description = "The domain to write records for";
+    };
+    path = mkOption {
+      type = types.str;
+      example = "/var/lib/dnssync/unbound.conf";
+      description = "The local-data snippet to write. Include it with include: in a server clause";
+    };
+    live = lib.mkEnableOption "applying changes to the running server with unbound-control";
+    controlCommand = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "unbound-control -c /etc/unbound/unbound.conf";
+      description = "The unbound-control command, with any arguments. Defaults to unbound-control";
+    };
+    reloadCommand = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "unbound-control reload";
+      description = "Command to run after the snippet changes. Not needed when live is enabled";
+    };
+    ttl = mkOption {
+      type = types.ints.unsigned;
+      default = 300;
+      description = "TTL of created records";
+    };
+  };
+
+  config = lib.mkIf (cfg.enable) {
+    dnssync.enabledFrontends = "unbound";
+    systemd.services.dnssync.serviceConfig.ReadWritePaths = [ (builtins.dirOf cfg.path) ];
+    systemd.services.dnssync.environment = {
+      "DNSSYNC_UNBOUND_DOMAIN" = cfg.domain;
+      "DNSSYNC_UNBOUND_PATH" = cfg.path;
+      "DNSSYNC_UNBOUND_LIVE" = lib.boolToString cfg.live;
+      "DNSSYNC_UNBOUND_TTL" = toString cfg.ttl;
+    } // lib.optionalAttrs (cfg.controlCommand != null) {
+      "DNSSYNC_UNBOUND_CONTROL_COMMAND" = cfg.controlCommand;
+    } // lib.optionalAttrs (cfg.reloadCommand != null) {
+      "DNSSYNC_UNBOUND_RELOAD_COMMAND" = cfg.reloadCommand;
+    };
+  };
+}
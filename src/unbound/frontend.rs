@@ -0,0 +1,274 @@
+use std::path::PathBuf;
+
+use snafu::prelude::*;
+
+use crate::bind::ZoneRecord;
+use crate::common::{
+    self, check_writable, diff_records, read_to_string_or_empty, run_shell, write_atomic,
+    ConfigSnafu, FrontendSnafu, Record, Result,
+};
+
+use super::models::{from_local_data, to_local_data};
+
+pub const FRONTEND_NAME: &str = "Unbound";
+
+const HEADER: &str = "# Managed by DNSSync. Changes made by hand will be overwritten.";
+const DEFAULT_CONTROL_COMMAND: &str = "unbound-control";
+const DEFAULT_TTL: u32 = 300;
+
+pub struct Unbound {
+    domain: String,
+    path: PathBuf,
+    /// unbound-control and its arguments, if changes are applied live.
+    control_command: Option<Vec<String>>,
+    reload_command: Option<String>,
+    ttl: u32,
+}
+
+impl Unbound {
+    fn read_records(&self) -> Result<Vec<ZoneRecord>> {
+        let text = read_to_string_or_empty(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to read {}", self.path.display()),
+            })?;
+
+        Ok(text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let record = from_local_data(line);
+                if record.is_none() {
+                    tracing::warn!(
+                        frontend = FRONTEND_NAME,
+                        line = line,
+                        "Skipping unrecognized config line"
+                    );
+                }
+                record
+            })
+            .collect())
+    }
+
+    fn control(&self, args: &[&str]) -> Result<()> {
+        let Some(command) = &self.control_command else {
+            return Ok(());
+        };
+        tracing::debug!(
+            frontend = FRONTEND_NAME,
+            command = command.join(" "),
+            args = args.join(" "),
+            "Running control command"
+        );
+        let output = std::process::Command::new(&command[0])
+            .args(&command[1..])
+            .args(args)
+            .output()
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to run {}", command[0]),
+            })?;
+
+        // unbound-control reports some errors on stdout with a zero exit code
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || stdout.starts_with("error") {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(std::io::Error::other(format!("{stdout}{stderr}")))
+                .boxed_local()
+                .context(FrontendSnafu {
+                    frontend: FRONTEND_NAME,
+                    message: format!("{} {} failed", command[0], args[0]),
+                });
+        }
+
+        Ok(())
+    }
+
+    /// Applies the changed names to the running server. Removing data
+    /// removes every kind for a name, so the remaining kinds are re-added.
+    fn apply_live(&self, changed: &[String], records: &[ZoneRecord]) -> Result<()> {
+        for name in changed {
+            self.control(&["local_data_remove", name])?;
+            for record in records
+                .iter()
+                .filter(|r| &r.name == name && to_local_data(r).is_some())
+            {
+                self.control(&["local_data", &record.to_line()])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl common::Frontend for Unbound {
+    fn get_name(&self) -> &str {
+        FRONTEND_NAME
+    }
+
+    fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn check(&mut self) -> Result<()> {
+        self.read_records()?;
+        check_writable(&self.path)
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Cannot write to {}", self.path.display()),
+            })?;
+        self.control(&["status"])
+    }
+
+    fn set_records(&mut self, authority: Vec<Record>, dry_run: bool) -> Result<()> {
+        let current = self.read_records()?;
+        let mut diff = diff_records::<ZoneRecord>(current.clone(), authority);
+
+        // Records that cannot be written are dropped before counting
+        diff.create.retain(|r| {
+            let supported = to_local_data(r).is_some();
+            if !supported {
+                tracing::warn!(
+                    frontend = FRONTEND_NAME,
+                    name = r.name,
+                    kind = r.kind,
+                    "Skipping record containing a single quote"
+                );
+            }
+            supported
+        });
+        diff.update.retain(|r| to_local_data(r).is_some());
+        for record in diff.create.iter_mut() {
+            record.ttl = self.ttl;
+        }
+
+        // Short circuit on no changes
+        if diff.len() == 0 {
+            tracing::info!(frontend = FRONTEND_NAME, "No changes detected");
+            return Ok(());
+        }
+
+        // Stop on dry run
+        if dry_run {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                create = diff.create.len(),
+                update = diff.update.len(),
+                delete = diff.delete.len(),
+                "Dry run completed",
+            );
+            return Ok(());
+        }
+
+        tracing::info!(
+            frontend = FRONTEND_NAME,
+            create = diff.create.len(),
+            update = diff.update.len(),
+            delete = diff.delete.len(),
+            "Applying changes",
+        );
+
+        for record in diff.delete.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Deleting record",
+            );
+        }
+        for record in diff.update.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Updating record"
+            );
+        }
+        for record in diff.create.iter() {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                name = record.name,
+                kind = record.kind,
+                content = record.content,
+                "Creating record"
+            );
+        }
+
+        let mut records = diff.apply(current);
+        records.sort_by(|a, b| (&a.name, &a.kind).cmp(&(&b.name, &b.kind)));
+
+        // Apply live before writing the file, so that a failure is retried next sync
+        let mut changed: Vec<String> = diff
+            .delete
+            .iter()
+            .chain(diff.update.iter())
+            .chain(diff.create.iter())
+            .map(|r| r.name.clone())
+            .collect();
+        changed.sort();
+        changed.dedup();
+        self.apply_live(&changed, &records)?;
+
+        let mut lines = vec![HEADER.to_string()];
+        lines.extend(records.iter().filter_map(to_local_data));
+        lines.push(String::new());
+
+        write_atomic(&self.path, &lines.join("\n"))
+            .boxed_local()
+            .context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: format!("Failed to write {}", self.path.display()),
+            })?;
+
+        if let Some(command) = &self.reload_command {
+            tracing::info!(
+                frontend = FRONTEND_NAME,
+                command = command,
+                "Running reload command"
+            );
+            run_shell(command).boxed_local().context(FrontendSnafu {
+                frontend: FRONTEND_NAME,
+                message: "Reload command failed",
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<super::Config> for Unbound {
+    fn from(value: super::Config) -> Self {
+        let control_command = value
+            .live
+            .then(|| -> Result<Vec<String>> {
+                let command: Vec<String> = value
+                    .control_command
+                    .unwrap_or_else(|| DEFAULT_CONTROL_COMMAND.to_string())
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect();
+                ensure!(
+                    !command.is_empty(),
+                    ConfigSnafu {
+                        message: "control_command must not be empty",
+                        prefix: FRONTEND_NAME,
+                    }
+                );
+                Ok(command)
+            })
+            .transpose()
+            .unwrap();
+
+        Self {
+            domain: value.domain,
+            path: value.path,
+            control_command,
+            reload_command: value.reload_command,
+            ttl: value.ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::bind::ZoneRecord;
+
+const LOCAL_DATA: &str = "local-data:";
+
+/// Formats the record as a local-data entry. Single quotes are used so that
+/// TXT content can be double quoted, so records containing them are unsupported.
+pub(super) fn to_local_data(record: &ZoneRecord) -> Option<String> {
+    let rr = record.to_line();
+    match rr.contains('\'') {
+        true => None,
+        false => Some(format!("{LOCAL_DATA} '{rr}'")),
+    }
+}
+
+/// Parses an entry written by to_local_data.
+pub(super) fn from_local_data(line: &str) -> Option<ZoneRecord> {
+    let rr = line.trim().strip_prefix(LOCAL_DATA)?.trim();
+    ZoneRecord::from_line(rr.strip_prefix('\'')?.strip_suffix('\'')?)
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use dnssync_rs::adguard::{AdGuard, Config};
+use dnssync_rs::common::Frontend;
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, respond_text, FakeApi};
+
+/// Basic auth for admin:secret
+const CREDENTIALS: &str = "Basic YWRtaW46c2VjcmV0";
+
+#[derive(Default)]
+struct State {
+    rewrites: Vec<Value>,
+    /// Method, path and body of each write request received.
+    writes: Vec<String>,
+}
+
+/// An in-process stand-in for the AdGuard Home API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, password: &str, state_path: &Path) -> AdGuard {
+        AdGuard::from(Config {
+            base_url: self.url("/"),
+            username: "admin".into(),
+            password: password.into(),
+            domain: "lan".into(),
+            state_path: state_path.into(),
+        })
+    }
+
+    fn add_rewrite(&self, domain: &str, answer: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .rewrites
+            .push(json!({"domain": domain, "answer": answer, "enabled": true}));
+    }
+
+    fn rewrites(&self) -> Vec<Value> {
+        self.state.lock().unwrap().rewrites.clone()
+    }
+
+    fn writes(&self) -> Vec<String> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Authorization") && h.value.as_str() == CREDENTIALS);
+    if !authorized {
+        return respond_text(request, 403, "Forbidden\n", &[]);
+    }
+
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+    let method = request.method().clone();
+    let path = request.url().to_string();
+    if method != tiny_http::Method::Get {
+        state.writes.push(format!("{method} {path} {body}"));
+    }
+
+    let matches = |rewrite: &Value, target: &Value| {
+        rewrite["domain"] == target["domain"] && rewrite["answer"] == target["answer"]
+    };
+    match (method, path.as_str()) {
+        (tiny_http::Method::Get, "/control/rewrite/list") => {
+            let rewrites = Value::from(state.rewrites.clone());
+            respond(request, 200, rewrites)
+        }
+        (tiny_http::Method::Post, "/control/rewrite/add") => {
+            let mut rewrite: Value = serde_json::from_str(&body).unwrap();
+            rewrite["enabled"] = json!(true);
+            state.rewrites.push(rewrite);
+            respond_text(request, 200, "", &[])
+        }
+        (tiny_http::Method::Post, "/control/rewrite/delete") => {
+            let target: Value = serde_json::from_str(&body).unwrap();
+            state.rewrites.retain(|r| !matches(r, &target));
+            respond_text(request, 200, "", &[])
+        }
+        (tiny_http::Method::Put, "/control/rewrite/update") => {
+            let change: Value = serde_json::from_str(&body).unwrap();
+            match state
+                .rewrites
+                .iter_mut()
+                .find(|r| matches(r, &change["target"]))
+            {
+                Some(rewrite) => {
+                    rewrite["domain"] = change["update"]["domain"].clone();
+                    rewrite["answer"] = change["update"]["answer"].clone();
+                    respond_text(request, 200, "", &[])
+                }
+                None => respond_text(request, 400, "rewrite not found\n", &[]),
+            }
+        }
+        _ => respond_text(request, 404, "404 page not found\n", &[]),
+    }
+}
+
+fn write_state(path: &Path, records: Value) {
+    std::fs::write(path, json!({ "records": records }).to_string()).unwrap();
+}
+
+#[test]
+fn syncs_owned_rewrites() {
+    let api = FakeApi::start();
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("adguard.json");
+    api.add_rewrite("host.lan", "10.0.0.1");
+    api.add_rewrite("old.lan", "fd00::1");
+    write_state(
+        &state_path,
+        json!([
+            {"name": "host.lan", "kind": "A", "content": "10.0.0.1"},
+            {"name": "old.lan", "kind": "AAAA", "content": "fd00::1"},
+        ]),
+    );
+
+    let records = vec![
+        record("host.lan", "A", "10.0.0.2"),
+        record("alias.lan", "CNAME", "host.lan."),
+        record("host.lan", "MX", "10 mail.lan"),
+    ];
+
+    // A dry run writes nothing
+    let state = std::fs::read_to_string(&state_path).unwrap();
+    api.frontend("secret", &state_path)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+    assert_eq!(std::fs::read_to_string(&state_path).unwrap(), state);
+
+    api.frontend("secret", &state_path)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    assert_eq!(
+        api.writes(),
+        vec![
+            r#"POST /control/rewrite/delete {"domain":"old.lan","answer":"fd00::1"}"#,
+            r#"PUT /control/rewrite/update {"target":{"domain":"host.lan","answer":"10.0.0.1"},"update":{"domain":"host.lan","answer":"10.0.0.2"}}"#,
+            r#"POST /control/rewrite/add {"domain":"alias.lan","answer":"host.lan"}"#,
+        ]
+    );
+    assert_eq!(
+        api.rewrites(),
+        vec![
+            json!({"domain": "host.lan", "answer": "10.0.0.2", "enabled": true}),
+            json!({"domain": "alias.lan", "answer": "host.lan", "enabled": true}),
+        ]
+    );
+
+    let state: Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    assert_eq!(
+        state["records"],
+        json!([
+            {"name": "host.lan", "kind": "A", "content": "10.0.0.2"},
+            {"name": "alias.lan", "kind": "CNAME", "content": "host.lan"},
+        ])
+    );
+
+    // Syncing again is a no-op
+    api.frontend("secret", &state_path)
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(api.writes().len(), 3);
+}
+
+#[test]
+fn leaves_unowned_rewrites_alone() {
+    let api = FakeApi::start();
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("adguard.json");
+    api.add_rewrite("manual.lan", "10.0.0.1");
+    api.add_rewrite("*.ads.lan", "0.0.0.0");
+    // Keeps the upstream answer, so isn't a record at all
+    api.add_rewrite("host.lan", "A");
+
+    api.frontend("secret", &state_path)
+        .set_records(vec![record("manual.lan", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert!(api.writes().is_empty());
+    assert_eq!(api.rewrites().len(), 3);
+}
+
+#[test]
+fn bad_password_is_reported() {
+    let api = FakeApi::start();
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = api
+        .frontend("wrong", &dir.path().join("adguard.json"))
+        .check()
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 403: Forbidden"));
+}
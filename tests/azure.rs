@@ -0,0 +1,420 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::azure::{AzureDNS, Config};
+use dnssync_rs::common::Frontend;
+use serde_json::{json, Value};
+
+use common::{header, read_body, record, respond, FakeApi};
+
+const INSTANCE_ID: &str = "test";
+const TENANT_ID: &str = "tenant";
+const CLIENT_ID: &str = "client";
+const CLIENT_SECRET: &str = "secret";
+const ACCESS_TOKEN: &str = "eyJ0eXAi.test";
+const ZONE_PATH: &str =
+    "/subscriptions/sub/resourceGroups/dns/providers/Microsoft.Network/dnsZones/example.com";
+
+#[derive(Default)]
+struct State {
+    zone_missing: bool,
+    record_sets: Vec<Value>,
+    /// Method, path, precondition header and body of each write request received.
+    writes: Vec<(String, String, String, Value)>,
+    next_etag: usize,
+}
+
+impl State {
+    fn etag(&mut self) -> String {
+        self.next_etag += 1;
+        format!("etag-{}", self.next_etag)
+    }
+}
+
+/// An in-process stand-in for Azure Resource Manager and the Microsoft Entra ID token endpoint.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, client_secret: &str) -> AzureDNS {
+        AzureDNS::from(Config {
+            tenant_id: TENANT_ID.into(),
+            client_id: CLIENT_ID.into(),
+            client_secret: client_secret.into(),
+            subscription_id: "sub".into(),
+            resource_group: "dns".into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            authority_url: Some(self.url("/")),
+            base_url: Some(self.url("/")),
+            ttl: None,
+        })
+    }
+
+    fn add_record_set(&self, name: &str, kind: &str, records: Value, metadata: Value) {
+        let mut state = self.state.lock().unwrap();
+        let etag = state.etag();
+        let property = records.as_object().unwrap().keys().next().unwrap().clone();
+        let fqdn = match name {
+            "@" => "example.com.".to_string(),
+            name => format!("{name}.example.com."),
+        };
+        let mut properties = json!({
+            "TTL": 3600,
+            "fqdn": fqdn,
+            "metadata": metadata,
+            "provisioningState": "Succeeded",
+            "targetResource": {},
+        });
+        properties[property] = records[&property].clone();
+        state.record_sets.push(json!({
+            "id": format!("{ZONE_PATH}/{kind}/{name}"),
+            "name": name,
+            "type": format!("Microsoft.Network/dnszones/{kind}"),
+            "etag": etag,
+            "properties": properties,
+        }));
+    }
+
+    fn find_record_set(&self, name: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .record_sets
+            .iter()
+            .find(|r| {
+                r["name"] == name && r["type"] == format!("Microsoft.Network/dnszones/{kind}")
+            })
+            .cloned()
+    }
+
+    fn writes(&self) -> Vec<(String, String, String, Value)> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn error(request: tiny_http::Request, status: u16, code: &str, message: &str) {
+    respond(
+        request,
+        status,
+        json!({"error": {"code": code, "message": message}}),
+    )
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let host = header(&request, "Host").unwrap_or_default();
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+
+    if url.path() == format!("/{TENANT_ID}/oauth2/v2.0/token") {
+        let form: Vec<(String, String)> = url::form_urlencoded::parse(body.as_bytes())
+            .into_owned()
+            .collect();
+        let field = |name: &str| {
+            form.iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default()
+        };
+        if field("grant_type") != "client_credentials"
+            || field("client_id") != CLIENT_ID
+            || field("client_secret") != CLIENT_SECRET
+            || field("scope") != format!("http://{host}/.default")
+        {
+            return respond(
+                request,
+                401,
+                json!({
+                    "error": "invalid_client",
+                    "error_description": "AADSTS7000215: Invalid client secret provided.",
+                }),
+            );
+        }
+        return respond(
+            request,
+            200,
+            json!({"token_type": "Bearer", "expires_in": 3599, "access_token": ACCESS_TOKEN}),
+        );
+    }
+
+    if header(&request, "Authorization") != Some(format!("Bearer {ACCESS_TOKEN}")) {
+        return error(
+            request,
+            401,
+            "AuthenticationFailed",
+            "Authentication failed.",
+        );
+    }
+    assert!(url
+        .query_pairs()
+        .any(|(k, v)| k == "api-version" && v == "2018-05-01"));
+    if state.zone_missing {
+        return error(
+            request,
+            404,
+            "ResourceNotFound",
+            "The Resource 'Microsoft.Network/dnszones/example.com' under resource group 'dns' was not found.",
+        );
+    }
+
+    let method = request.method().clone();
+    let path = url.path().strip_prefix(ZONE_PATH).unwrap_or_default();
+    match (method, path) {
+        (tiny_http::Method::Get, "") => respond(
+            request,
+            200,
+            json!({"name": "example.com", "type": "Microsoft.Network/dnszones", "properties": {}}),
+        ),
+        (tiny_http::Method::Get, "/recordsets") => {
+            // Two record sets a page, to exercise pagination
+            let start: usize = url
+                .query_pairs()
+                .find(|(k, _)| k == "$skipToken")
+                .map(|(_, v)| v.parse().unwrap())
+                .unwrap_or(0);
+            let page: Vec<Value> = state
+                .record_sets
+                .iter()
+                .skip(start)
+                .take(2)
+                .cloned()
+                .collect();
+            let mut body = json!({"value": page});
+            if start + 2 < state.record_sets.len() {
+                body["nextLink"] = json!(format!(
+                    "http://{host}{ZONE_PATH}/recordsets?api-version=2018-05-01&$skipToken={}",
+                    start + 2
+                ));
+            }
+            respond(request, 200, body)
+        }
+        (method, path) => {
+            let Some((kind, name)) = path.trim_start_matches('/').split_once('/') else {
+                return error(request, 404, "NotFound", "The requested URL was not found.");
+            };
+            let if_match = header(&request, "If-Match");
+            let if_none_match = header(&request, "If-None-Match");
+            let position = state.record_sets.iter().position(|r| {
+                r["name"] == name && r["type"] == format!("Microsoft.Network/dnszones/{kind}")
+            });
+            let precondition_met = match (&if_match, &if_none_match, position) {
+                (Some(etag), _, Some(i)) => state.record_sets[i]["etag"] == *etag,
+                (Some(_), _, None) => false,
+                (None, Some(_), Some(_)) => false,
+                _ => true,
+            };
+            let precondition = if_match
+                .map(|etag| format!("If-Match: {etag}"))
+                .or(if_none_match.map(|etag| format!("If-None-Match: {etag}")))
+                .unwrap_or_default();
+            let body: Value = serde_json::from_str(&body).unwrap_or_default();
+            state.writes.push((
+                method.to_string(),
+                format!("{kind}/{name}"),
+                precondition,
+                body.clone(),
+            ));
+            if !precondition_met {
+                return error(
+                    request,
+                    412,
+                    "PreconditionFailed",
+                    "The condition specified using HTTP conditional header(s) is not met.",
+                );
+            }
+
+            match method {
+                tiny_http::Method::Put => {
+                    let mut properties = body["properties"].clone();
+                    properties["fqdn"] = json!(match name {
+                        "@" => "example.com.".to_string(),
+                        name => format!("{name}.example.com."),
+                    });
+                    let record_set = json!({
+                        "id": format!("{ZONE_PATH}/{kind}/{name}"),
+                        "name": name,
+                        "type": format!("Microsoft.Network/dnszones/{kind}"),
+                        "etag": state.etag(),
+                        "properties": properties,
+                    });
+                    if let Some(i) = position {
+                        state.record_sets.remove(i);
+                    }
+                    state.record_sets.push(record_set.clone());
+                    respond(request, 200, record_set)
+                }
+                tiny_http::Method::Delete => {
+                    if let Some(i) = position {
+                        state.record_sets.remove(i);
+                    }
+                    respond(request, 200, json!({}))
+                }
+                _ => error(request, 405, "MethodNotAllowed", "Method not allowed."),
+            }
+        }
+    }
+}
+
+#[test]
+fn syncs_record_sets() {
+    let api = FakeApi::start();
+    api.add_record_set(
+        "@",
+        "SOA",
+        json!({"SOARecord": {"email": "azuredns-hostmaster.microsoft.com"}}),
+        json!(null),
+    );
+    api.add_record_set(
+        "@",
+        "NS",
+        json!({"NSRecords": [{"nsdname": "ns1-01.azure-dns.com."}]}),
+        json!(null),
+    );
+    api.add_record_set(
+        "host",
+        "A",
+        json!({"ARecords": [{"ipv4Address": "10.0.0.1"}]}),
+        json!({"dnssync-instance": INSTANCE_ID, "owner": "ops"}),
+    );
+    api.add_record_set(
+        "old",
+        "A",
+        json!({"ARecords": [{"ipv4Address": "10.0.0.1"}]}),
+        json!({"dnssync-instance": INSTANCE_ID}),
+    );
+
+    let long_txt = "x".repeat(300);
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("www.example.com", "A", "10.0.0.4"),
+        record("www.example.com", "A", "10.0.0.3"),
+        record("alias.example.com", "CNAME", "host.example.com."),
+        record("example.com", "MX", "10 mail.example.com."),
+        record("example.com", "TXT", &long_txt),
+    ];
+
+    // A dry run writes nothing
+    api.frontend(CLIENT_SECRET)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+
+    api.frontend(CLIENT_SECRET)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    let writes = api.writes();
+    let requests: Vec<(&str, &str, &str)> = writes
+        .iter()
+        .map(|(method, path, precondition, _)| {
+            (method.as_str(), path.as_str(), precondition.as_str())
+        })
+        .collect();
+    assert_eq!(
+        requests,
+        vec![
+            ("DELETE", "A/old", "If-Match: etag-4"),
+            ("PUT", "A/host", "If-Match: etag-3"),
+            ("PUT", "A/www", "If-None-Match: *"),
+            ("PUT", "CNAME/alias", "If-None-Match: *"),
+            ("PUT", "MX/@", "If-None-Match: *"),
+            ("PUT", "TXT/@", "If-None-Match: *"),
+        ]
+    );
+
+    let host = api.find_record_set("host", "A").unwrap();
+    assert_eq!(
+        host["properties"]["ARecords"],
+        json!([{"ipv4Address": "10.0.0.2"}])
+    );
+    assert_eq!(host["properties"]["TTL"], 3600);
+    assert_eq!(
+        host["properties"]["metadata"],
+        json!({"dnssync-instance": INSTANCE_ID, "owner": "ops"})
+    );
+
+    let www = api.find_record_set("www", "A").unwrap();
+    assert_eq!(
+        www["properties"]["ARecords"],
+        json!([{"ipv4Address": "10.0.0.3"}, {"ipv4Address": "10.0.0.4"}])
+    );
+    assert_eq!(www["properties"]["TTL"], 300);
+    assert_eq!(
+        www["properties"]["metadata"],
+        json!({"dnssync-instance": INSTANCE_ID})
+    );
+    assert_eq!(
+        api.find_record_set("alias", "CNAME").unwrap()["properties"]["CNAMERecord"],
+        json!({"cname": "host.example.com"})
+    );
+    assert_eq!(
+        api.find_record_set("@", "MX").unwrap()["properties"]["MXRecords"],
+        json!([{"preference": 10, "exchange": "mail.example.com"}])
+    );
+    assert_eq!(
+        api.find_record_set("@", "TXT").unwrap()["properties"]["TXTRecords"],
+        json!([{"value": [&long_txt[..255], &long_txt[255..]]}])
+    );
+    assert!(api.find_record_set("old", "A").is_none());
+    assert!(api.find_record_set("@", "SOA").is_some());
+    assert!(api.find_record_set("@", "NS").is_some());
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend(CLIENT_SECRET)
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(api.writes().len(), 6);
+}
+
+#[test]
+fn only_deletes_owned_records() {
+    let api = FakeApi::start();
+    api.add_record_set(
+        "manual",
+        "A",
+        json!({"ARecords": [{"ipv4Address": "10.0.0.1"}]}),
+        json!({"owner": "ops"}),
+    );
+    api.add_record_set(
+        "theirs",
+        "A",
+        json!({"ARecords": [{"ipv4Address": "10.0.0.1"}]}),
+        json!({"dnssync-instance": "other"}),
+    );
+
+    api.frontend(CLIENT_SECRET)
+        .set_records(vec![record("manual.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert!(api.writes().is_empty());
+    assert!(api.find_record_set("theirs", "A").is_some());
+    assert_eq!(
+        api.find_record_set("manual", "A").unwrap()["properties"]["ARecords"],
+        json!([{"ipv4Address": "10.0.0.1"}])
+    );
+}
+
+#[test]
+fn check_reports_missing_zone() {
+    let api = FakeApi::start();
+    api.frontend(CLIENT_SECRET).check().unwrap();
+
+    api.state.lock().unwrap().zone_missing = true;
+    let err = api.frontend(CLIENT_SECRET).check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 404: ResourceNotFound: The Resource"));
+}
+
+#[test]
+fn auth_errors_are_reported() {
+    let api = FakeApi::start();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 401: AADSTS7000215: Invalid client secret provided."));
+}
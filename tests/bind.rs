@@ -0,0 +1,156 @@
+mod common;
+
+use std::path::Path;
+
+use dnssync_rs::bind::{Bind, Config};
+use dnssync_rs::common::Frontend;
+
+use common::record;
+
+fn frontend(path: &Path, fragment: bool, reload_command: Option<String>) -> Bind {
+    Bind::from(Config {
+        domain: "example.com".into(),
+        path: path.to_path_buf(),
+        fragment,
+        primary_ns: Some("ns1.example.com".into()),
+        hostmaster: Some("ops@example.com".into()),
+        nameservers: None,
+        reload_command,
+        ttl: None,
+    })
+}
+
+fn serial(text: &str) -> u32 {
+    let soa = text.lines().find(|l| l.contains("\tSOA\t")).unwrap();
+    soa.split_whitespace().nth(6).unwrap().parse().unwrap()
+}
+
+#[test]
+fn writes_full_zone() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.zone");
+
+    frontend(&path, false, None)
+        .set_records(
+            vec![
+                record("host.example.com", "A", "10.0.0.1"),
+                record("alias.example.com", "CNAME", "host.example.com"),
+                record("info.example.com", "TXT", "say \"hi\""),
+            ],
+            false,
+        )
+        .unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = text.lines().skip(1).collect();
+    assert_eq!(
+        lines[2..],
+        [
+            "example.com.\t300\tIN\tNS\tns1.example.com.",
+            "alias.example.com.\t300\tIN\tCNAME\thost.example.com.",
+            "host.example.com.\t300\tIN\tA\t10.0.0.1",
+            "info.example.com.\t300\tIN\tTXT\t\"say \\\"hi\\\"\"",
+        ]
+    );
+    assert!(lines[0].starts_with("$TTL"));
+    assert!(text.contains("\tSOA\tns1.example.com. ops.example.com. "));
+}
+
+#[test]
+fn bumps_serial_only_on_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.zone");
+    let records = vec![record("host.example.com", "A", "10.0.0.1")];
+
+    frontend(&path, false, None)
+        .set_records(records.clone(), false)
+        .unwrap();
+    let first = std::fs::read_to_string(&path).unwrap();
+
+    frontend(&path, false, None)
+        .set_records(records, false)
+        .unwrap();
+    let unchanged = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(first, unchanged);
+
+    frontend(&path, false, None)
+        .set_records(vec![record("host.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+    let changed = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(serial(&changed), serial(&first) + 1);
+    assert!(changed.contains("host.example.com.\t300\tIN\tA\t10.0.0.2"));
+}
+
+#[test]
+fn long_multi_byte_txt_is_unchanged_on_next_sync() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.zone");
+    let records = vec![record("info.example.com", "TXT", &"é".repeat(200))];
+
+    frontend(&path, false, None)
+        .set_records(records.clone(), false)
+        .unwrap();
+    let first = std::fs::read_to_string(&path).unwrap();
+    assert!(!first.contains('\u{FFFD}'));
+
+    frontend(&path, false, None)
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), first);
+}
+
+#[test]
+fn dry_run_makes_no_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.zone");
+
+    frontend(&path, false, None)
+        .set_records(vec![record("host.example.com", "A", "10.0.0.1")], true)
+        .unwrap();
+
+    assert!(!path.exists());
+}
+
+#[test]
+fn fragment_deletes_and_reloads() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dnssync.inc");
+    let marker = dir.path().join("reloaded");
+    let reload = Some(format!("touch {}", marker.display()));
+
+    frontend(&path, true, reload.clone())
+        .set_records(
+            vec![
+                record("a.example.com", "A", "10.0.0.1"),
+                record("b.example.com", "A", "10.0.0.2"),
+            ],
+            false,
+        )
+        .unwrap();
+    assert!(marker.exists());
+    std::fs::remove_file(&marker).unwrap();
+
+    frontend(&path, true, reload)
+        .set_records(vec![record("b.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+    assert!(marker.exists());
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(!text.contains("SOA"));
+    assert_eq!(
+        text.lines().skip(1).collect::<Vec<&str>>(),
+        ["b.example.com.\t300\tIN\tA\t10.0.0.2"]
+    );
+}
+
+#[test]
+fn failed_reload_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.zone");
+
+    let err = frontend(&path, false, Some("echo nope >&2; exit 3".into()))
+        .set_records(vec![record("host.example.com", "A", "10.0.0.1")], false)
+        .unwrap_err();
+
+    assert!(err.to_string().contains("nope"));
+}
@@ -0,0 +1,423 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::cloudflare::{Cloudflare, Config};
+use dnssync_rs::common::{Frontend, Record};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, respond_text, FakeApi};
+
+const ZONE_ID: &str = "zone-example";
+const INSTANCE_ID: &str = "test";
+/// Deliberately small so that every listing spans several pages.
+const PAGE_SIZE: usize = 2;
+
+#[derive(Default)]
+struct State {
+    zones: Vec<Value>,
+    records: Vec<Value>,
+    next_id: usize,
+    /// Write requests received, as "METHOD id".
+    writes: Vec<String>,
+    /// Fail every request with success: false.
+    fail: bool,
+}
+
+/// An in-process stand-in for the parts of the Cloudflare API used by the frontend.
+impl FakeApi<State> {
+    fn start() -> Self {
+        let mut state = State::default();
+        // Pad the zone list so that finding the zone requires pagination
+        for i in 0..3 {
+            state
+                .zones
+                .push(json!({"id": format!("zone-{i}"), "name": format!("other{i}.com")}));
+        }
+        state
+            .zones
+            .push(json!({"id": ZONE_ID, "name": "example.com"}));
+
+        Self::serve(state, handle_request)
+    }
+
+    fn frontend(&self) -> Cloudflare {
+        self.frontend_with(false, None)
+    }
+
+    fn frontend_with(&self, use_tags: bool, comment_template: Option<&str>) -> Cloudflare {
+        Cloudflare::from(Config {
+            api_key: "token".into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            use_tags,
+            comment_template: comment_template.map(String::from),
+            base_url: Some(self.url("/client/v4")),
+        })
+    }
+
+    fn add_record(&self, name: &str, kind: &str, content: &str, comment: Option<&str>) {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let id = format!("existing-{}", state.next_id);
+        state.records.push(json!({
+            "id": id,
+            "type": kind,
+            "name": name,
+            "content": content,
+            "comment": comment,
+            "ttl": 1,
+        }));
+    }
+
+    fn add_tagged_record(&self, name: &str, kind: &str, content: &str, instance_id: &str) {
+        self.add_record(name, kind, content, None);
+        let mut state = self.state.lock().unwrap();
+        let record = state.records.last_mut().unwrap();
+        record["tags"] = json!(managed_tags(instance_id));
+    }
+
+    fn find_record(&self, name: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .records
+            .iter()
+            .find(|r| r["name"] == name && r["type"] == kind)
+            .cloned()
+    }
+
+    fn writes(&self) -> Vec<String> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn paginate(items: &[Value], url: &url::Url) -> Value {
+    let page: usize = url
+        .query_pairs()
+        .find(|(k, _)| k == "page")
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(1);
+    let result: Vec<&Value> = items
+        .iter()
+        .skip((page - 1) * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .collect();
+    json!({
+        "success": true,
+        "errors": [],
+        "result": result,
+        "result_info": {"count": items.len(), "page": page, "per_page": PAGE_SIZE},
+    })
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let path = url.path().trim_start_matches("/client/v4").to_string();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+
+    let response = if state.fail {
+        json!({
+            "success": false,
+            "errors": [{"code": 10000, "message": "Authentication error"}],
+            "result": [],
+        })
+    } else {
+        match (request.method(), segments.as_slice()) {
+            (tiny_http::Method::Get, ["zones"]) => paginate(&state.zones, &url),
+            (tiny_http::Method::Get, ["zones", ZONE_ID, "dns_records"]) => {
+                paginate(&state.records, &url)
+            }
+            (tiny_http::Method::Post, ["zones", ZONE_ID, "dns_records"]) => {
+                state.next_id += 1;
+                let mut record: Value = serde_json::from_str(&body).unwrap();
+                record["id"] = json!(format!("created-{}", state.next_id));
+                state
+                    .writes
+                    .push(format!("POST {}", record["name"].as_str().unwrap()));
+                state.records.push(record.clone());
+                json!({"success": true, "errors": [], "result": record})
+            }
+            (tiny_http::Method::Put, ["zones", ZONE_ID, "dns_records", id]) => {
+                let mut record: Value = serde_json::from_str(&body).unwrap();
+                record["id"] = json!(id);
+                state.writes.push(format!("PUT {id}"));
+                for existing in state.records.iter_mut() {
+                    if existing["id"] == *id {
+                        *existing = record.clone();
+                    }
+                }
+                json!({"success": true, "errors": [], "result": record})
+            }
+            (tiny_http::Method::Delete, ["zones", ZONE_ID, "dns_records", id]) => {
+                state.writes.push(format!("DELETE {id}"));
+                state.records.retain(|r| r["id"] != *id);
+                json!({"success": true, "errors": [], "result": {"id": id}})
+            }
+            _ => return respond_text(request, 404, "not found", &[]),
+        }
+    };
+
+    respond(request, 200, response)
+}
+
+fn managed_comment(instance_id: &str) -> String {
+    format!("Managed by DNSSync instance:{instance_id}")
+}
+
+fn managed_tags(instance_id: &str) -> Vec<String> {
+    vec![
+        "dnssync:managed".into(),
+        format!("dnssync:instance={instance_id}"),
+    ]
+}
+
+#[test]
+fn creates_and_updates_across_pages() {
+    let api = FakeApi::start();
+    for i in 0..5 {
+        api.add_record(
+            &format!("host{i}.example.com"),
+            "A",
+            "10.0.0.1",
+            Some(&managed_comment(INSTANCE_ID)),
+        );
+    }
+
+    let mut authority: Vec<Record> = (0..5)
+        .map(|i| record(&format!("host{i}.example.com"), "A", "10.0.0.1"))
+        .collect();
+    // Change the record on the last page, and add a new one
+    authority[4].content = "10.0.0.2".into();
+    authority.push(record("new.example.com", "A", "10.0.0.3"));
+
+    // A dry run writes nothing
+    api.frontend().set_records(authority.clone(), true).unwrap();
+    assert!(api.writes().is_empty());
+
+    api.frontend().set_records(authority, false).unwrap();
+
+    assert_eq!(api.writes(), vec!["PUT existing-5", "POST new.example.com"]);
+    let updated = api.find_record("host4.example.com", "A").unwrap();
+    assert_eq!(updated["content"], "10.0.0.2");
+    let created = api.find_record("new.example.com", "A").unwrap();
+    assert_eq!(created["content"], "10.0.0.3");
+    assert_eq!(created["comment"], managed_comment(INSTANCE_ID));
+}
+
+#[test]
+fn unsuccessful_response_is_an_error() {
+    let api = FakeApi::start();
+    api.state.lock().unwrap().fail = true;
+
+    let err = api
+        .frontend()
+        .set_records(vec![record("host.example.com", "A", "10.0.0.1")], false)
+        .unwrap_err();
+
+    assert!(err.to_string().contains("10000 Authentication error"));
+    assert!(api.writes().is_empty());
+}
+
+#[test]
+fn unmanaged_records_are_left_alone() {
+    let api = FakeApi::start();
+    api.add_record("host.example.com", "A", "10.0.0.1", None);
+    api.add_record("other.example.com", "A", "10.0.0.1", Some("Hand made"));
+
+    api.frontend()
+        .set_records(vec![record("host.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert!(api.writes().is_empty());
+    let unchanged = api.find_record("host.example.com", "A").unwrap();
+    assert_eq!(unchanged["content"], "10.0.0.1");
+    assert!(api.find_record("other.example.com", "A").is_some());
+}
+
+#[test]
+fn deletes_only_records_of_this_instance() {
+    let api = FakeApi::start();
+    api.add_record(
+        "ours.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&managed_comment(INSTANCE_ID)),
+    );
+    api.add_record(
+        "theirs.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&managed_comment("other")),
+    );
+    api.add_record("manual.example.com", "A", "10.0.0.1", None);
+
+    api.frontend().set_records(Vec::new(), false).unwrap();
+
+    assert_eq!(api.writes(), vec!["DELETE existing-1"]);
+    assert!(api.find_record("ours.example.com", "A").is_none());
+    assert!(api.find_record("theirs.example.com", "A").is_some());
+    assert!(api.find_record("manual.example.com", "A").is_some());
+}
+
+#[test]
+fn comment_template_keeps_user_notes() {
+    let api = FakeApi::start();
+    api.add_record(
+        "host.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&format!("Ask ops first. {}", managed_comment(INSTANCE_ID))),
+    );
+
+    api.frontend_with(false, Some("from {source}"))
+        .set_records(vec![record("host.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    let updated = api.find_record("host.example.com", "A").unwrap();
+    assert_eq!(
+        updated["comment"],
+        format!("Ask ops first. Managed by DNSSync from test instance:{INSTANCE_ID}")
+    );
+}
+
+#[test]
+fn reads_instance_id_as_the_rest_of_the_comment() {
+    let api = FakeApi::start();
+    // Written by an instance whose ID contains a space, before notes could
+    // follow the watermark, so it belongs to "test 2" rather than "test"
+    api.add_record(
+        "theirs.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&managed_comment("test 2")),
+    );
+    api.add_record(
+        "ours.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&format!("{} ", managed_comment(INSTANCE_ID))),
+    );
+
+    api.frontend().set_records(Vec::new(), false).unwrap();
+
+    assert_eq!(api.writes(), vec!["DELETE existing-2"]);
+    assert!(api.find_record("theirs.example.com", "A").is_some());
+}
+
+#[test]
+fn creates_and_deletes_with_tags() {
+    let api = FakeApi::start();
+    api.add_tagged_record("old.example.com", "A", "10.0.0.1", INSTANCE_ID);
+
+    api.frontend_with(true, None)
+        .set_records(vec![record("new.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert_eq!(
+        api.writes(),
+        vec!["DELETE existing-1", "POST new.example.com"]
+    );
+    let created = api.find_record("new.example.com", "A").unwrap();
+    assert_eq!(created["tags"], json!(managed_tags(INSTANCE_ID)));
+    assert_eq!(created["comment"], Value::Null);
+}
+
+#[test]
+fn migrates_comment_ownership_to_tags() {
+    let api = FakeApi::start();
+    api.add_record(
+        "host.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&format!("Ask ops first. {}", managed_comment(INSTANCE_ID))),
+    );
+    let authority = vec![record("host.example.com", "A", "10.0.0.1")];
+
+    api.frontend_with(true, None)
+        .set_records(authority.clone(), false)
+        .unwrap();
+
+    assert_eq!(api.writes(), vec!["PUT existing-1"]);
+    let migrated = api.find_record("host.example.com", "A").unwrap();
+    assert_eq!(migrated["tags"], json!(managed_tags(INSTANCE_ID)));
+    assert_eq!(migrated["comment"], "Ask ops first.");
+
+    // Once migrated, the record is left alone
+    api.frontend_with(true, None)
+        .set_records(authority, false)
+        .unwrap();
+    assert_eq!(api.writes().len(), 1);
+}
+
+#[test]
+fn tags_leave_records_of_other_instances_alone() {
+    let api = FakeApi::start();
+    api.add_tagged_record("tagged.example.com", "A", "10.0.0.1", "other");
+    api.add_record(
+        "commented.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&managed_comment("other")),
+    );
+
+    // Neither is deleted, nor is the one still in the authority migrated
+    api.frontend_with(true, None)
+        .set_records(
+            vec![record("commented.example.com", "A", "10.0.0.1")],
+            false,
+        )
+        .unwrap();
+
+    assert!(api.writes().is_empty());
+    let commented = api.find_record("commented.example.com", "A").unwrap();
+    assert_eq!(commented["comment"], managed_comment("other"));
+}
+
+#[test]
+fn comment_template_reaches_unchanged_records() {
+    let api = FakeApi::start();
+    api.add_record(
+        "host.example.com",
+        "A",
+        "10.0.0.1",
+        Some(&managed_comment(INSTANCE_ID)),
+    );
+    let authority = vec![record("host.example.com", "A", "10.0.0.1")];
+
+    api.frontend_with(false, Some("from {source}"))
+        .set_records(authority.clone(), false)
+        .unwrap();
+
+    assert_eq!(api.writes(), vec!["PUT existing-1"]);
+    let updated = api.find_record("host.example.com", "A").unwrap();
+    assert_eq!(
+        updated["comment"],
+        format!("Managed by DNSSync from test instance:{INSTANCE_ID}")
+    );
+
+    // Once the comment matches, the record is left alone
+    api.frontend_with(false, Some("from {source}"))
+        .set_records(authority, false)
+        .unwrap();
+    assert_eq!(api.writes().len(), 1);
+}
+
+#[test]
+fn tagged_records_with_an_instance_in_the_template_are_left_alone() {
+    let api = FakeApi::start();
+    let authority = vec![record("host.example.com", "A", "10.0.0.1")];
+    let template = Some("synced by instance:{instance_id}");
+
+    api.frontend_with(true, template)
+        .set_records(authority.clone(), false)
+        .unwrap();
+    assert_eq!(api.writes(), vec!["POST host.example.com"]);
+
+    api.frontend_with(true, template)
+        .set_records(authority, false)
+        .unwrap();
+    assert_eq!(api.writes().len(), 1);
+}
@@ -0,0 +1,116 @@
+//! Helpers shared by the frontend tests, including an in-process HTTP server
+//! for testing against a stand-in for their API. Each test file keeps its
+//! own state and routes.
+
+// Each test file is its own crate, and none of them use every helper
+#![allow(dead_code)]
+
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use dnssync_rs::common::Record;
+use serde_json::Value;
+
+/// Handles a request to the fake API, with access to its state.
+pub type Handler<S> = fn(tiny_http::Request, &Mutex<S>);
+
+pub struct FakeApi<S> {
+    server: Arc<tiny_http::Server>,
+    pub state: Arc<Mutex<S>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<S: Send + 'static> FakeApi<S> {
+    /// Starts serving on a free local port, passing every request to the handler.
+    pub fn serve(state: S, handler: Handler<S>) -> Self {
+        let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").unwrap());
+        let state = Arc::new(Mutex::new(state));
+
+        let handle = {
+            let server = server.clone();
+            let state = state.clone();
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handler(request, &state);
+                }
+            })
+        };
+
+        Self {
+            server,
+            state,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl<S> FakeApi<S> {
+    /// The URL of the given path on the server.
+    pub fn url(&self, path: &str) -> url::Url {
+        let addr = self.server.server_addr().to_ip().unwrap();
+        url::Url::parse(&format!("http://{addr}{path}")).unwrap()
+    }
+}
+
+impl<S> Drop for FakeApi<S> {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            handle.join().unwrap();
+        }
+    }
+}
+
+/// Responds with a body and the given headers.
+pub fn respond_text(request: tiny_http::Request, status: u16, body: &str, headers: &[&str]) {
+    let mut resp = tiny_http::Response::from_string(body).with_status_code(status);
+    for header in headers {
+        resp.add_header(header.parse::<tiny_http::Header>().unwrap());
+    }
+    request.respond(resp).unwrap();
+}
+
+/// Responds with a JSON body, or no body if it is null.
+pub fn respond(request: tiny_http::Request, status: u16, body: Value) {
+    respond_with_headers(request, status, body, &[])
+}
+
+pub fn respond_with_headers(
+    request: tiny_http::Request,
+    status: u16,
+    body: Value,
+    headers: &[&str],
+) {
+    let body = match body {
+        Value::Null => String::new(),
+        body => body.to_string(),
+    };
+    let mut headers = headers.to_vec();
+    headers.push("Content-Type: application/json");
+    respond_text(request, status, &body, &headers)
+}
+
+/// Reads the body of a request as text.
+pub fn read_body(request: &mut tiny_http::Request) -> String {
+    let mut body = String::new();
+    request.as_reader().read_to_string(&mut body).unwrap();
+    body
+}
+
+pub fn header(request: &tiny_http::Request, name: &'static str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.to_string())
+}
+
+/// A record from the test backend.
+pub fn record(name: &str, kind: &str, content: &str) -> Record {
+    Record {
+        name: url::Host::Domain(name.into()),
+        kind: kind.into(),
+        content: content.into(),
+        source: "test".into(),
+    }
+}
@@ -0,0 +1,322 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::desec::{Config, DeSEC};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, respond_with_headers, FakeApi};
+
+const API_KEY: &str = "secret";
+const INSTANCE_ID: &str = "test";
+const MINIMUM_TTL: u64 = 3600;
+const PAGE_SIZE: usize = 2;
+const DOMAIN_PATH: &str = "/api/v1/domains/example.com/";
+const RRSETS_PATH: &str = "/api/v1/domains/example.com/rrsets/";
+
+#[derive(Default)]
+struct State {
+    rrsets: Vec<Value>,
+    /// The bodies of each bulk PATCH received.
+    patches: Vec<Value>,
+    /// How many of the next requests to throttle.
+    throttle: usize,
+    throttled: usize,
+}
+
+/// An in-process stand-in for the deSEC API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, api_key: &str) -> DeSEC {
+        DeSEC::from(Config {
+            api_key: api_key.into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            base_url: Some(self.url("/api/v1/")),
+            registry_prefix: None,
+            ttl: Some(60),
+            max_retries: Some(1),
+        })
+    }
+
+    fn add_rrset(&self, subname: &str, kind: &str, records: &[&str]) {
+        self.state.lock().unwrap().rrsets.push(json!({
+            "domain": "example.com",
+            "subname": subname,
+            "name": format!("{subname}.example.com.").trim_start_matches('.'),
+            "type": kind,
+            "records": records,
+            "ttl": MINIMUM_TTL,
+            "created": "2024-01-01T00:00:00.000000Z",
+            "touched": "2024-01-01T00:00:00.000000Z",
+        }));
+    }
+
+    fn add_owner(&self, subname: &str, kind: &str, instance_id: &str) {
+        let name = match subname {
+            "" => format!("_dnssync.{}", kind.to_lowercase()),
+            subname => format!("_dnssync.{}.{subname}", kind.to_lowercase()),
+        };
+        self.add_rrset(
+            &name,
+            "TXT",
+            &[&format!(
+                "\"heritage=dnssync,dnssync/instance={instance_id},dnssync/source=test\""
+            )],
+        );
+    }
+
+    fn find_rrset(&self, subname: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .rrsets
+            .iter()
+            .find(|r| r["subname"] == subname && r["type"] == kind)
+            .cloned()
+    }
+
+    fn patches(&self) -> Vec<Value> {
+        self.state.lock().unwrap().patches.clone()
+    }
+}
+
+/// Applies a bulk change the way deSEC does, all or nothing.
+fn apply_patch(state: &mut State, changes: &[Value]) -> std::result::Result<(), Value> {
+    let mut rrsets = state.rrsets.clone();
+    for (i, change) in changes.iter().enumerate() {
+        let duplicate = changes[..i]
+            .iter()
+            .any(|c| c["subname"] == change["subname"] && c["type"] == change["type"]);
+        if duplicate {
+            return Err(
+                json!([{"non_field_errors": ["Same subname and type as in another item."]}]),
+            );
+        }
+        if let Some(ttl) = change["ttl"].as_u64() {
+            if ttl < MINIMUM_TTL {
+                return Err(
+                    json!([{"ttl": [format!("Ensure this value is greater than or equal to {MINIMUM_TTL}.")]}]),
+                );
+            }
+        }
+        let records = change["records"].as_array().unwrap();
+        if change["type"] == "CNAME" && records.iter().any(|r| !r.as_str().unwrap().ends_with('.'))
+        {
+            return Err(json!([{"records": ["Hostname must be fully qualified."]}]));
+        }
+
+        let index = rrsets
+            .iter()
+            .position(|r| r["subname"] == change["subname"] && r["type"] == change["type"]);
+        match (index, records.is_empty()) {
+            (Some(index), true) => {
+                rrsets.remove(index);
+            }
+            (Some(index), false) => {
+                rrsets[index]["records"] = change["records"].clone();
+                if !change["ttl"].is_null() {
+                    rrsets[index]["ttl"] = change["ttl"].clone();
+                }
+            }
+            (None, false) => {
+                assert!(!change["ttl"].is_null(), "new RRsets need a TTL");
+                rrsets.push(change.clone());
+            }
+            (None, true) => {}
+        }
+    }
+    state.rrsets = rrsets;
+    Ok(())
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Authorization") && h.value.as_str() == format!("Token {API_KEY}"));
+    if !authorized {
+        return respond(request, 401, json!({"detail": "Invalid token."}));
+    }
+
+    let mut state = state.lock().unwrap();
+    if state.throttle > 0 {
+        state.throttle -= 1;
+        state.throttled += 1;
+        return respond_with_headers(
+            request,
+            429,
+            json!({"detail": "Request was throttled. Expected available in 1 second."}),
+            &["Retry-After: 1"],
+        );
+    }
+
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let body = read_body(&mut request);
+
+    match (request.method(), url.path()) {
+        (tiny_http::Method::Get, DOMAIN_PATH) => respond(
+            request,
+            200,
+            json!({"name": "example.com", "minimum_ttl": MINIMUM_TTL}),
+        ),
+        (tiny_http::Method::Get, RRSETS_PATH) => {
+            let cursor = url
+                .query_pairs()
+                .find(|(k, _)| k == "cursor")
+                .map(|(_, v)| v.to_string())
+                .expect("pagination should be requested");
+            let start: usize = match cursor.as_str() {
+                "" => 0,
+                cursor => cursor.parse().unwrap(),
+            };
+            let page: Vec<Value> = state
+                .rrsets
+                .iter()
+                .skip(start)
+                .take(PAGE_SIZE)
+                .cloned()
+                .collect();
+            let mut links = vec![format!(
+                "<https://desec.io{RRSETS_PATH}?cursor=>; rel=\"first\""
+            )];
+            if start + PAGE_SIZE < state.rrsets.len() {
+                links.push(format!(
+                    "<https://desec.io{RRSETS_PATH}?cursor={}>; rel=\"next\"",
+                    start + PAGE_SIZE
+                ));
+            }
+            respond_with_headers(
+                request,
+                200,
+                json!(page),
+                &[&format!("Link: {}", links.join(", "))],
+            )
+        }
+        (tiny_http::Method::Patch, RRSETS_PATH) => {
+            let changes: Value = serde_json::from_str(&body).unwrap();
+            state.patches.push(changes.clone());
+            match apply_patch(&mut state, changes.as_array().unwrap()) {
+                Ok(()) => respond(request, 200, json!([])),
+                Err(errors) => respond(request, 400, errors),
+            }
+        }
+        _ => respond(request, 404, json!({"detail": "Not found."})),
+    }
+}
+
+#[test]
+fn applies_changes_in_one_request() {
+    let api = FakeApi::start();
+    api.add_rrset("", "NS", &["ns1.desec.io.", "ns2.desec.org."]);
+    api.add_rrset("host", "A", &["10.0.0.1"]);
+    api.add_owner("host", "A", INSTANCE_ID);
+    api.add_rrset("old", "A", &["10.0.0.1"]);
+    api.add_owner("old", "A", INSTANCE_ID);
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("alias.example.com", "CNAME", "host.example.com"),
+        record("example.com", "TXT", "hello \"world\""),
+    ];
+
+    // A dry run writes nothing
+    api.frontend(API_KEY)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.patches().is_empty());
+
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    let patches = api.patches();
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0].as_array().unwrap().len(), 7);
+
+    assert!(api.find_rrset("old", "A").is_none());
+    assert!(api.find_rrset("_dnssync.a.old", "TXT").is_none());
+    assert!(api.find_rrset("", "NS").is_some());
+    assert_eq!(
+        api.find_rrset("host", "A").unwrap()["records"],
+        json!(["10.0.0.2"])
+    );
+
+    // The configured TTL is below the minimum, so the minimum is used
+    let alias = api.find_rrset("alias", "CNAME").unwrap();
+    assert_eq!(alias["records"], json!(["host.example.com."]));
+    assert_eq!(alias["ttl"], MINIMUM_TTL);
+    assert!(api.find_rrset("_dnssync.cname.alias", "TXT").is_some());
+
+    assert_eq!(
+        api.find_rrset("", "TXT").unwrap()["records"],
+        json!(["\"hello \\\"world\\\"\""])
+    );
+    assert!(api.find_rrset("_dnssync.txt", "TXT").is_some());
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.patches().len(), 1);
+}
+
+#[test]
+fn writes_values_of_a_name_as_one_rrset() {
+    let api = FakeApi::start();
+    api.add_rrset("many", "A", &["10.0.0.2", "10.0.0.1"]);
+    api.add_owner("many", "A", INSTANCE_ID);
+
+    let records = vec![
+        record("web.example.com", "A", "10.0.0.1"),
+        record("web.example.com", "A", "10.0.0.2"),
+        record("many.example.com", "A", "10.0.0.1"),
+        record("many.example.com", "A", "10.0.0.2"),
+    ];
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    // The new RRset and its owner, as the existing one is unchanged
+    let patches = api.patches();
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0].as_array().unwrap().len(), 2);
+    assert_eq!(
+        api.find_rrset("web", "A").unwrap()["records"],
+        json!(["10.0.0.1", "10.0.0.2"])
+    );
+
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.patches().len(), 1);
+}
+
+#[test]
+fn retries_when_rate_limited() {
+    let api = FakeApi::start();
+    api.state.lock().unwrap().throttle = 1;
+
+    api.frontend(API_KEY)
+        .set_records(vec![record("new.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+    assert_eq!(api.state.lock().unwrap().throttled, 1);
+    assert!(api.find_rrset("new", "A").is_some());
+
+    // Gives up once out of retries
+    api.state.lock().unwrap().throttle = 2;
+    let err = api.frontend(API_KEY).check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 429: Request was throttled"));
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 401: Invalid token."));
+}
@@ -0,0 +1,258 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::digitalocean::{Config, DigitalOcean};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, FakeApi};
+
+const API_KEY: &str = "secret";
+const INSTANCE_ID: &str = "test";
+const PAGE_SIZE: usize = 2;
+const RECORDS_PATH: &str = "/v2/domains/example.com/records";
+
+#[derive(Default)]
+struct State {
+    records: Vec<Value>,
+    next_id: u64,
+    /// Method and path of each write request received.
+    writes: Vec<String>,
+}
+
+/// An in-process stand-in for the DigitalOcean domain records API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, api_key: &str) -> DigitalOcean {
+        DigitalOcean::from(Config {
+            api_key: api_key.into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            base_url: Some(self.url("/v2/")),
+            registry_prefix: None,
+            ttl: None,
+        })
+    }
+
+    fn add_record(&self, name: &str, kind: &str, data: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        state.records.push(json!({
+            "id": id,
+            "type": kind,
+            "name": name,
+            "data": data,
+            "priority": null,
+            "ttl": 1800,
+        }));
+    }
+
+    fn add_owner(&self, name: &str, kind: &str, instance_id: &str) {
+        self.add_record(
+            &format!("_dnssync.{}.{name}", kind.to_lowercase()),
+            "TXT",
+            &format!("heritage=dnssync,dnssync/instance={instance_id},dnssync/source=test"),
+        );
+    }
+
+    fn find_record(&self, name: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .records
+            .iter()
+            .find(|r| r["name"] == name && r["type"] == kind)
+            .cloned()
+    }
+
+    fn writes(&self) -> Vec<String> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn not_found(request: tiny_http::Request) {
+    respond(
+        request,
+        404,
+        json!({"id": "not_found", "message": "The resource you were accessing could not be found."}),
+    )
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Authorization") && h.value.as_str() == format!("Bearer {API_KEY}"));
+    if !authorized {
+        return respond(
+            request,
+            401,
+            json!({"id": "unauthorized", "message": "Unable to authenticate you"}),
+        );
+    }
+
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+
+    let method = request.method().clone();
+    let path = url.path().to_string();
+    if method != tiny_http::Method::Get {
+        state.writes.push(format!("{method} {path}"));
+    }
+
+    let id = path
+        .strip_prefix(RECORDS_PATH)
+        .and_then(|id| id.strip_prefix('/'))
+        .map(|id| id.parse::<u64>().unwrap());
+
+    match (method, path.as_str(), id) {
+        (tiny_http::Method::Get, "/v2/domains/example.com", None) => respond(
+            request,
+            200,
+            json!({"domain": {"name": "example.com", "ttl": 1800}}),
+        ),
+        (tiny_http::Method::Get, RECORDS_PATH, None) => {
+            let page: usize = url
+                .query_pairs()
+                .find(|(k, _)| k == "page")
+                .map(|(_, v)| v.parse().unwrap())
+                .unwrap_or(1);
+            let records: Vec<&Value> = state
+                .records
+                .iter()
+                .skip((page - 1) * PAGE_SIZE)
+                .take(PAGE_SIZE)
+                .collect();
+            let mut pages = json!({});
+            if page * PAGE_SIZE < state.records.len() {
+                pages["next"] = json!(format!(
+                    "https://api.digitalocean.com{RECORDS_PATH}?page={}",
+                    page + 1
+                ));
+            }
+            respond(
+                request,
+                200,
+                json!({
+                    "domain_records": records,
+                    "links": {"pages": pages},
+                    "meta": {"total": state.records.len()},
+                }),
+            )
+        }
+        (tiny_http::Method::Post, RECORDS_PATH, None) => {
+            let mut record: Value = serde_json::from_str(&body).unwrap();
+            assert!(record.get("id").is_none());
+            state.next_id += 1;
+            record["id"] = json!(state.next_id);
+            state.records.push(record.clone());
+            respond(request, 201, json!({"domain_record": record}))
+        }
+        (tiny_http::Method::Put, _, Some(id)) => {
+            let update: Value = serde_json::from_str(&body).unwrap();
+            match state.records.iter_mut().find(|r| r["id"] == id) {
+                Some(existing) => {
+                    for (key, value) in update.as_object().unwrap() {
+                        existing[key] = value.clone();
+                    }
+                    let record = existing.clone();
+                    respond(request, 200, json!({"domain_record": record}))
+                }
+                None => not_found(request),
+            }
+        }
+        (tiny_http::Method::Delete, _, Some(id)) => {
+            let before = state.records.len();
+            state.records.retain(|r| r["id"] != id);
+            match state.records.len() < before {
+                true => respond(request, 204, Value::Null),
+                false => not_found(request),
+            }
+        }
+        _ => not_found(request),
+    }
+}
+
+#[test]
+fn applies_changes() {
+    let api = FakeApi::start();
+    api.add_record("@", "NS", "ns1.digitalocean.com");
+    api.add_record("host", "A", "10.0.0.1");
+    api.add_owner("host", "A", INSTANCE_ID);
+    api.add_record("old", "A", "10.0.0.1");
+    api.add_owner("old", "A", INSTANCE_ID);
+    // @ in values is the zone apex
+    api.add_record("www", "CNAME", "@");
+    api.add_owner("www", "CNAME", INSTANCE_ID);
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("www.example.com", "CNAME", "example.com"),
+        record("alias.example.com", "CNAME", "host.example.com"),
+        record("example.com", "TXT", "hello world"),
+    ];
+
+    // A dry run writes nothing
+    api.frontend(API_KEY)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    assert_eq!(
+        api.writes(),
+        vec![
+            format!("DELETE {RECORDS_PATH}/4"),
+            format!("DELETE {RECORDS_PATH}/5"),
+            format!("PUT {RECORDS_PATH}/2"),
+            format!("POST {RECORDS_PATH}"),
+            format!("POST {RECORDS_PATH}"),
+            format!("POST {RECORDS_PATH}"),
+            format!("POST {RECORDS_PATH}"),
+        ]
+    );
+
+    assert!(api.find_record("@", "NS").is_some());
+    assert_eq!(api.find_record("host", "A").unwrap()["data"], "10.0.0.2");
+    assert_eq!(api.find_record("host", "A").unwrap()["ttl"], 1800);
+
+    let alias = api.find_record("alias", "CNAME").unwrap();
+    assert_eq!(alias["data"], "host.example.com.");
+    assert_eq!(alias["ttl"], 300);
+    assert!(api.find_record("_dnssync.cname.alias", "TXT").is_some());
+
+    assert_eq!(api.find_record("@", "TXT").unwrap()["data"], "hello world");
+    assert!(api.find_record("_dnssync.txt", "TXT").is_some());
+
+    // The API returns names in data without the trailing dot
+    api.state
+        .lock()
+        .unwrap()
+        .records
+        .iter_mut()
+        .find(|r| r["name"] == "alias")
+        .unwrap()["data"] = json!("host.example.com");
+
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.writes().len(), 7);
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 401: Unable to authenticate you"));
+    api.frontend(API_KEY).check().unwrap();
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::path::Path;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::dnsmasq::{Config, Dnsmasq};
+
+use common::record;
+
+fn frontend(path: &Path) -> Dnsmasq {
+    Dnsmasq::from(Config {
+        domain: "example.com".into(),
+        path: path.to_path_buf(),
+        reload_command: None,
+    })
+}
+
+#[test]
+fn renders_and_updates_snippet() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dnssync.conf");
+
+    frontend(&path)
+        .set_records(
+            vec![
+                record("host.example.com", "A", "10.0.0.1"),
+                record("host.example.com", "AAAA", "fd00::1"),
+                record("alias.example.com", "CNAME", "host.example.com."),
+                record("info.example.com", "TXT", "a, \"b\""),
+                record("mail.example.com", "MX", "10 host.example.com"),
+            ],
+            false,
+        )
+        .unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        text.lines().skip(1).collect::<Vec<&str>>(),
+        [
+            "cname=alias.example.com,host.example.com",
+            "host-record=host.example.com,10.0.0.1",
+            "host-record=host.example.com,fd00::1",
+            "txt-record=info.example.com,\"a, \\\"b\\\"\"",
+        ]
+    );
+
+    // Syncing the same records again changes nothing
+    let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+    frontend(&path)
+        .set_records(
+            vec![
+                record("host.example.com", "A", "10.0.0.1"),
+                record("host.example.com", "AAAA", "fd00::1"),
+                record("alias.example.com", "CNAME", "host.example.com."),
+                record("info.example.com", "TXT", "a, \"b\""),
+            ],
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().modified().unwrap(),
+        modified
+    );
+
+    frontend(&path)
+        .set_records(vec![record("host.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        text.lines().skip(1).collect::<Vec<&str>>(),
+        ["host-record=host.example.com,10.0.0.2"]
+    );
+}
+
+#[test]
+fn dry_run_makes_no_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dnssync.conf");
+
+    frontend(&path)
+        .set_records(vec![record("host.example.com", "A", "10.0.0.1")], true)
+        .unwrap();
+
+    assert!(!path.exists());
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::time::Duration;
+
+use hickory_proto::op::{Message, MessageType, Query, ResponseCode};
+use hickory_proto::rr::{Name, RData, RecordType};
+
+use dnssync_rs::common::{Frontend, Record};
+use dnssync_rs::dnsserver::{Config, DnsServer};
+
+use common::record;
+
+/// Starts a server on a free local port with the given records.
+fn serve(records: Vec<Record>) -> (DnsServer, SocketAddr) {
+    let addr = UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let mut server = DnsServer::from(Config {
+        domain: "ts.example.com".into(),
+        listen: Some(addr.to_string()),
+        nameservers: "ns1.example.com,ns2.example.com".into(),
+        hostmaster: Some("admin@example.com".into()),
+        ttl: None,
+    });
+    server.set_records(records, false).unwrap();
+    (server, addr)
+}
+
+fn request(name: &str, kind: RecordType) -> Message {
+    let mut message = Message::new();
+    message
+        .set_id(4242)
+        .set_message_type(MessageType::Query)
+        .add_query(Query::query(Name::from_ascii(name).unwrap(), kind));
+    message
+}
+
+fn query(addr: SocketAddr, name: &str, kind: RecordType) -> Message {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    socket
+        .send_to(&request(name, kind).to_vec().unwrap(), addr)
+        .unwrap();
+    let mut buf = [0u8; 4096];
+    let len = socket.recv(&mut buf).unwrap();
+    let response = Message::from_vec(&buf[..len]).unwrap();
+    assert_eq!(response.id(), 4242);
+    response
+}
+
+fn answers(response: &Message) -> Vec<String> {
+    response
+        .answers()
+        .iter()
+        .map(|r| r.data().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn answers_records_and_apex() {
+    let (_server, addr) = serve(vec![
+        record("host.ts.example.com", "A", "100.64.0.1"),
+        record("host.ts.example.com", "AAAA", "fd7a::1"),
+        record("www.ts.example.com", "CNAME", "host.ts.example.com"),
+    ]);
+
+    let response = query(addr, "host.ts.example.com.", RecordType::A);
+    assert_eq!(response.response_code(), ResponseCode::NoError);
+    assert!(response.authoritative());
+    assert_eq!(answers(&response), vec!["100.64.0.1"]);
+
+    // CNAMEs within the zone are followed
+    let response = query(addr, "www.ts.example.com.", RecordType::AAAA);
+    assert_eq!(answers(&response), vec!["host.ts.example.com.", "fd7a::1"]);
+
+    let response = query(addr, "ts.example.com.", RecordType::NS);
+    assert_eq!(
+        answers(&response),
+        vec!["ns1.example.com.", "ns2.example.com."]
+    );
+
+    let response = query(addr, "ts.example.com.", RecordType::SOA);
+    let Some(RData::SOA(soa)) = response.answers()[0].data() else {
+        panic!("expected an SOA answer");
+    };
+    assert_eq!(soa.mname().to_string(), "ns1.example.com.");
+    assert_eq!(soa.rname().to_string(), "admin.example.com.");
+}
+
+#[test]
+fn distinguishes_nxdomain_from_nodata() {
+    let (_server, addr) = serve(vec![record("a.b.ts.example.com", "A", "100.64.0.1")]);
+
+    let response = query(addr, "missing.ts.example.com.", RecordType::A);
+    assert_eq!(response.response_code(), ResponseCode::NXDomain);
+    assert_eq!(response.name_servers()[0].record_type(), RecordType::SOA);
+
+    // The name exists, but not with this kind
+    let response = query(addr, "a.b.ts.example.com.", RecordType::TXT);
+    assert_eq!(response.response_code(), ResponseCode::NoError);
+    assert!(response.answers().is_empty());
+    assert_eq!(response.name_servers()[0].record_type(), RecordType::SOA);
+
+    // Empty non-terminal
+    let response = query(addr, "b.ts.example.com.", RecordType::A);
+    assert_eq!(response.response_code(), ResponseCode::NoError);
+    assert!(response.answers().is_empty());
+
+    let response = query(addr, "host.example.org.", RecordType::A);
+    assert_eq!(response.response_code(), ResponseCode::Refused);
+}
+
+#[test]
+fn serves_updates_over_tcp() {
+    let (mut server, addr) = serve(vec![record("host.ts.example.com", "A", "100.64.0.1")]);
+    server
+        .set_records(
+            vec![record("host.ts.example.com", "A", "100.64.0.2")],
+            false,
+        )
+        .unwrap();
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let bytes = request("HOST.ts.example.com.", RecordType::A)
+        .to_vec()
+        .unwrap();
+    stream
+        .write_all(&(bytes.len() as u16).to_be_bytes())
+        .unwrap();
+    stream.write_all(&bytes).unwrap();
+
+    let mut len = [0u8; 2];
+    stream.read_exact(&mut len).unwrap();
+    let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut buf).unwrap();
+    let response = Message::from_vec(&buf).unwrap();
+
+    assert_eq!(answers(&response), vec!["100.64.0.2"]);
+    // The name is answered in the case it was asked in
+    assert_eq!(
+        response.answers()[0].name().to_string(),
+        "HOST.ts.example.com."
+    );
+}
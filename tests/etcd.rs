@@ -0,0 +1,311 @@
+//! Most tests run against an in-process stand-in for the etcd v3 HTTP
+//! gateway. The ignored test runs against a local etcd instead, e.g.:
+//!
+//! ```bash
+//! docker run -d -p 2379:2379 quay.io/coreos/etcd:v3.5.15 etcd \
+//!   --listen-client-urls http://0.0.0.0:2379 --advertise-client-urls http://127.0.0.1:2379
+//! DNSSYNC_TEST_ETCD_URL=http://127.0.0.1:2379 cargo test --test etcd -- --ignored
+//! ```
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use dnssync_rs::common::Frontend;
+use dnssync_rs::etcd::{Config, Etcd};
+use serde_json::{json, Value};
+
+use common::{header, read_body, record, respond, FakeApi};
+
+const INSTANCE_ID: &str = "test";
+const TOKEN: &str = "token.secret";
+
+#[derive(Default)]
+struct State {
+    keys: BTreeMap<String, String>,
+    /// Only accept requests with a token, once a user is set.
+    auth: Option<(String, String)>,
+    /// The path and key of each write request received.
+    writes: Vec<(String, String)>,
+}
+
+/// An in-process stand-in for the etcd v3 HTTP gateway.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn put(&self, key: &str, value: Value) {
+        self.state
+            .lock()
+            .unwrap()
+            .keys
+            .insert(key.into(), value.to_string());
+    }
+
+    fn keys(&self) -> BTreeMap<String, Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .keys
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::from_str(v).unwrap()))
+            .collect()
+    }
+
+    fn writes(&self) -> Vec<(String, String)> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn error(status: u16, code: u16, message: &str) -> (u16, Value) {
+    (
+        status,
+        json!({"error": message, "code": code, "message": message}),
+    )
+}
+
+fn decode(value: &Value) -> String {
+    String::from_utf8(STANDARD.decode(value.as_str().unwrap()).unwrap()).unwrap()
+}
+
+fn encode(value: &str) -> Value {
+    json!(STANDARD.encode(value))
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let body = read_body(&mut request);
+    let body: Value = serde_json::from_str(&body).unwrap();
+    let path = request.url().to_string();
+    let token = header(&request, "Authorization");
+
+    let mut state = state.lock().unwrap();
+    let (status, resp) = match path.as_str() {
+        "/v3/auth/authenticate" => match &state.auth {
+            Some((name, password)) if body["name"] == *name && body["password"] == *password => {
+                (200, json!({"header": {}, "token": TOKEN}))
+            }
+            _ => error(
+                400,
+                3,
+                "etcdserver: authentication failed, invalid user ID or password",
+            ),
+        },
+        _ if state.auth.is_some() && token.as_deref() != Some(TOKEN) => {
+            error(401, 16, "etcdserver: user name is empty")
+        }
+        "/v3/kv/range" => {
+            let (start, end) = (decode(&body["key"]), decode(&body["range_end"]));
+            let kvs: Vec<Value> = state
+                .keys
+                .range(start..end)
+                .map(|(k, v)| json!({"key": encode(k), "value": encode(v), "version": "1"}))
+                .collect();
+            match kvs.is_empty() {
+                true => (200, json!({"header": {}})),
+                false => (
+                    200,
+                    json!({"header": {}, "kvs": kvs, "count": kvs.len().to_string()}),
+                ),
+            }
+        }
+        "/v3/kv/put" => {
+            let key = decode(&body["key"]);
+            state.writes.push((path.clone(), key.clone()));
+            state.keys.insert(key, decode(&body["value"]));
+            (200, json!({"header": {}}))
+        }
+        "/v3/kv/deleterange" => {
+            let key = decode(&body["key"]);
+            state.writes.push((path.clone(), key.clone()));
+            let deleted = state.keys.remove(&key).is_some() as u32;
+            (200, json!({"header": {}, "deleted": deleted.to_string()}))
+        }
+        _ => error(404, 5, "Not Found"),
+    };
+    respond(request, status, resp)
+}
+
+fn frontend(url: url::Url, instance_id: &str) -> Etcd {
+    Etcd::from(Config {
+        base_url: url,
+        domain: "example.com".into(),
+        prefix: None,
+        instance_id: instance_id.into(),
+        username: None,
+        password: None,
+        ttl: None,
+    })
+}
+
+/// The key written for a record, whose last segment is this instance's.
+fn owned_key(keys: &BTreeMap<String, Value>, path: &str, value: &Value) -> String {
+    keys.iter()
+        .find(|(k, v)| {
+            k.strip_prefix(path)
+                .is_some_and(|leaf| leaf.starts_with(&format!("/dnssync-{INSTANCE_ID}-")))
+                && *v == value
+        })
+        .map(|(k, _)| k.clone())
+        .unwrap_or_else(|| panic!("no key under {path} with {value}"))
+}
+
+#[test]
+fn writes_skydns_keys() {
+    let api = FakeApi::start();
+    api.put("/skydns/com/example/manual", json!({"host": "10.0.0.9"}));
+    api.put("/skydns/org/example/web", json!({"host": "10.0.0.9"}));
+
+    let records = vec![
+        record("web.example.com", "A", "10.0.0.1"),
+        record("web.example.com", "A", "10.0.0.2"),
+        record("web.example.com", "AAAA", "fd00::1"),
+        record("alias.example.com", "CNAME", "web.example.com."),
+        record("example.com", "TXT", "hello world"),
+        record("example.com", "MX", "10 mail.example.com."),
+    ];
+
+    // A dry run writes nothing
+    frontend(api.url("/"), INSTANCE_ID)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+
+    frontend(api.url("/"), INSTANCE_ID)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    let keys = api.keys();
+    assert_eq!(keys.len(), 7);
+    for (path, value) in [
+        (
+            "/skydns/com/example/web",
+            json!({"host": "10.0.0.1", "ttl": 300}),
+        ),
+        (
+            "/skydns/com/example/web",
+            json!({"host": "10.0.0.2", "ttl": 300}),
+        ),
+        (
+            "/skydns/com/example/web",
+            json!({"host": "fd00::1", "ttl": 300}),
+        ),
+        (
+            "/skydns/com/example/alias",
+            json!({"host": "web.example.com", "ttl": 300}),
+        ),
+        (
+            "/skydns/com/example",
+            json!({"text": "hello world", "ttl": 300}),
+        ),
+    ] {
+        owned_key(&keys, path, &value);
+    }
+
+    // Syncing again is a no-op now that the formats agree
+    frontend(api.url("/"), INSTANCE_ID)
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(api.writes().len(), 5);
+
+    // A changed value is a new key, replacing the old one
+    frontend(api.url("/"), INSTANCE_ID)
+        .set_records(vec![record("web.example.com", "A", "10.0.0.3")], false)
+        .unwrap();
+    let keys = api.keys();
+    assert_eq!(keys.len(), 3);
+    owned_key(
+        &keys,
+        "/skydns/com/example/web",
+        &json!({"host": "10.0.0.3", "ttl": 300}),
+    );
+    assert!(keys.contains_key("/skydns/com/example/manual"));
+    assert!(keys.contains_key("/skydns/org/example/web"));
+}
+
+#[test]
+fn only_deletes_owned_keys() {
+    let api = FakeApi::start();
+    api.put("/skydns/com/example/manual", json!({"host": "10.0.0.1"}));
+    api.put("/skydns/com/example/manual/x1", json!({"host": "10.0.0.2"}));
+    api.put(
+        "/skydns/com/example/theirs/dnssync-other-0123456789ab",
+        json!({"host": "10.0.0.3", "ttl": 300}),
+    );
+    api.put(
+        &format!("/skydns/com/example/mine/dnssync-{INSTANCE_ID}-0123456789ab"),
+        json!({"host": "10.0.0.4", "ttl": 300}),
+    );
+
+    // An existing key with the same value isn't written twice
+    frontend(api.url("/"), INSTANCE_ID)
+        .set_records(vec![record("manual.example.com", "A", "10.0.0.1")], false)
+        .unwrap();
+
+    assert_eq!(
+        api.writes(),
+        vec![(
+            "/v3/kv/deleterange".to_string(),
+            format!("/skydns/com/example/mine/dnssync-{INSTANCE_ID}-0123456789ab")
+        )]
+    );
+    assert_eq!(api.keys().len(), 3);
+}
+
+#[test]
+fn authenticates_with_token() {
+    let api = FakeApi::start();
+    api.state.lock().unwrap().auth = Some(("dnssync".into(), "secret".into()));
+
+    let config = |password: &str| Config {
+        base_url: api.url("/"),
+        domain: "example.com.".into(),
+        prefix: Some("/coredns/".into()),
+        instance_id: INSTANCE_ID.into(),
+        username: Some("dnssync".into()),
+        password: Some(password.into()),
+        ttl: Some(60),
+    };
+
+    let err = Etcd::from(config("wrong")).check().unwrap_err();
+    assert!(err.to_string().contains("authentication failed"), "{err}");
+
+    let mut frontend = Etcd::from(config("secret"));
+    frontend.check().unwrap();
+    frontend
+        .set_records(vec![record("web.example.com", "A", "10.0.0.1")], false)
+        .unwrap();
+    owned_key(
+        &api.keys(),
+        "/coredns/com/example/web",
+        &json!({"host": "10.0.0.1", "ttl": 60}),
+    );
+}
+
+#[test]
+#[ignore = "requires etcd, see the module docs"]
+fn against_local_etcd() {
+    let url = std::env::var("DNSSYNC_TEST_ETCD_URL").expect("DNSSYNC_TEST_ETCD_URL must be set");
+    let mut frontend = frontend(url::Url::parse(&url).unwrap(), "integration-test");
+    frontend.check().unwrap();
+
+    let records = vec![
+        record("dnssync-test-a.example.com", "A", "192.0.2.1"),
+        record("dnssync-test-a.example.com", "A", "192.0.2.2"),
+        record("dnssync-test-txt.example.com", "TXT", "hello world"),
+    ];
+    frontend.set_records(records.clone(), false).unwrap();
+    frontend.set_records(records, false).unwrap();
+
+    frontend
+        .set_records(
+            vec![record("dnssync-test-a.example.com", "A", "192.0.2.3")],
+            false,
+        )
+        .unwrap();
+
+    frontend.set_records(Vec::new(), false).unwrap();
+}
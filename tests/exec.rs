@@ -0,0 +1,156 @@
+mod common;
+
+use std::path::Path;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::exec::{Config, Exec};
+
+use common::record;
+
+fn frontend(command: String, state_path: &Path) -> Exec {
+    Exec::from(Config {
+        domain: "example.com".into(),
+        command,
+        state_path: Some(state_path.to_path_buf()),
+    })
+}
+
+fn read_json(path: &Path) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn sends_changes_on_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+    let request = dir.path().join("request.json");
+    let command = format!("cat > {}", request.display());
+
+    frontend(command.clone(), &state)
+        .set_records(
+            vec![
+                record("web.example.com", "A", "10.0.0.2"),
+                record("web.example.com", "A", "10.0.0.1"),
+                record("db.example.com", "AAAA", "fd00::1"),
+            ],
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        read_json(&request),
+        serde_json::json!({
+            "domain": "example.com",
+            "records": [
+                {"name": "db.example.com", "kind": "AAAA", "content": "fd00::1"},
+                {"name": "web.example.com", "kind": "A", "content": "10.0.0.1"},
+                {"name": "web.example.com", "kind": "A", "content": "10.0.0.2"},
+            ],
+            "changes": {
+                "create": [
+                    {"name": "web.example.com", "kind": "A", "values": ["10.0.0.1", "10.0.0.2"]},
+                    {"name": "db.example.com", "kind": "AAAA", "values": ["fd00::1"]},
+                ],
+                "update": [],
+                "delete": [],
+            },
+        })
+    );
+
+    frontend(command, &state)
+        .set_records(vec![record("web.example.com", "A", "10.0.0.3")], false)
+        .unwrap();
+    assert_eq!(
+        read_json(&request)["changes"],
+        serde_json::json!({
+            "create": [],
+            "update": [{
+                "name": "web.example.com",
+                "kind": "A",
+                "values": ["10.0.0.3"],
+                "previous": ["10.0.0.1", "10.0.0.2"],
+            }],
+            "delete": [{"name": "db.example.com", "kind": "AAAA", "values": ["fd00::1"]}],
+        })
+    );
+    assert_eq!(
+        read_json(&state),
+        serde_json::json!([{"name": "web.example.com", "kind": "A", "content": "10.0.0.3"}])
+    );
+}
+
+#[test]
+fn reported_state_is_diffed_next_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+    let request = dir.path().join("request.json");
+
+    // The command only managed to create one of the records
+    let command = format!(
+        r#"cat > {}; echo '[{{"name": "web.example.com", "kind": "A", "content": "10.0.0.1"}}]'"#,
+        request.display()
+    );
+    let records = vec![
+        record("web.example.com", "A", "10.0.0.1"),
+        record("db.example.com", "A", "10.0.0.2"),
+    ];
+    frontend(command.clone(), &state)
+        .set_records(records.clone(), false)
+        .unwrap();
+    assert_eq!(
+        read_json(&state),
+        serde_json::json!([{"name": "web.example.com", "kind": "A", "content": "10.0.0.1"}])
+    );
+
+    frontend(command, &state)
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(
+        read_json(&request)["changes"]["create"],
+        serde_json::json!([{"name": "db.example.com", "kind": "A", "values": ["10.0.0.2"]}])
+    );
+}
+
+#[test]
+fn unchanged_records_do_not_run_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+    let ran = dir.path().join("ran");
+    let command = format!("cat > /dev/null; echo run >> {}", ran.display());
+    let records = vec![record("web.example.com", "A", "10.0.0.1")];
+
+    // Nothing runs on a dry run
+    frontend(command.clone(), &state)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(!ran.exists());
+
+    frontend(command.clone(), &state)
+        .set_records(records.clone(), false)
+        .unwrap();
+    frontend(command, &state)
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&ran).unwrap(), "run\n");
+}
+
+#[test]
+fn failing_command_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+
+    let err = frontend("echo 'zone is locked' >&2; exit 3".into(), &state)
+        .set_records(vec![record("web.example.com", "A", "10.0.0.1")], false)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("exit status: 3"), "{err}");
+    assert!(err.contains("zone is locked"), "{err}");
+    // The changes are sent again next run
+    assert!(!state.exists());
+
+    let err = frontend("echo 'not json'".into(), &state)
+        .set_records(vec![record("web.example.com", "A", "10.0.0.1")], false)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Failed to parse records"), "{err}");
+    assert!(!state.exists());
+}
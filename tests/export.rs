@@ -0,0 +1,168 @@
+mod common;
+
+use std::path::Path;
+
+use dnssync_rs::common::{Backend, Frontend, Record};
+use dnssync_rs::export::{Config, Export, Format};
+use dnssync_rs::jsonfile;
+
+use common::record;
+
+fn frontend(path: &Path, format: Format, ttl: Option<u32>) -> Export {
+    Export::from(Config {
+        domain: "example.com".into(),
+        path: path.to_path_buf(),
+        format,
+        ttl,
+    })
+}
+
+fn records() -> Vec<Record> {
+    vec![
+        record("www.example.com", "A", "10.0.0.2"),
+        record("host10.example.com", "A", "10.0.0.10"),
+        record("host9.example.com", "AAAA", "fd00::9"),
+        record("www.example.com", "A", "10.0.0.1"),
+        record("alias.example.com", "CNAME", "www.example.com"),
+        record("example.com", "TXT", "v=spf1 -all; it's"),
+        record("example.com", "MX", "10 mail.example.com"),
+        record("example.com", "CAA", "0 issue \"letsencrypt.org\""),
+        record("_sip._tcp.example.com", "SRV", "10 5 5060 sip.example.com."),
+    ]
+}
+
+#[test]
+fn writes_octodns_yaml() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.yaml");
+
+    let mut records = records();
+    records.push(record("host9.example.com", "SSHFP", "1 1 abcdef"));
+    frontend(&path, Format::Yaml, Some(300))
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        r#"---
+'':
+  - ttl: 300
+    type: CAA
+    value:
+      flags: 0
+      tag: 'issue'
+      value: 'letsencrypt.org'
+  - ttl: 300
+    type: MX
+    value:
+      exchange: 'mail.example.com.'
+      preference: 10
+  - ttl: 300
+    type: TXT
+    value: 'v=spf1 -all\; it''s'
+'_sip._tcp':
+  ttl: 300
+  type: SRV
+  value:
+    port: 5060
+    priority: 10
+    target: 'sip.example.com.'
+    weight: 5
+'alias':
+  ttl: 300
+  type: CNAME
+  value: 'www.example.com.'
+'host9':
+  ttl: 300
+  type: AAAA
+  value: 'fd00::9'
+'host10':
+  ttl: 300
+  type: A
+  value: '10.0.0.10'
+'www':
+  ttl: 300
+  type: A
+  values:
+    - '10.0.0.1'
+    - '10.0.0.2'
+"#
+    );
+}
+
+#[test]
+fn json_is_read_by_jsonfile() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.json");
+
+    frontend(&path, Format::Json, None)
+        .set_records(records(), false)
+        .unwrap();
+
+    let backend = jsonfile::Backend::from(jsonfile::Config {
+        source: path.clone(),
+    });
+    let read: Vec<(String, String, String)> = backend
+        .read_records()
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.name.to_string(), r.kind, r.content))
+        .collect();
+    let expected: Vec<(String, String, String)> = [
+        ("_sip._tcp.example.com", "SRV", "10 5 5060 sip.example.com."),
+        ("alias.example.com", "CNAME", "www.example.com"),
+        ("example.com", "CAA", "0 issue \"letsencrypt.org\""),
+        ("example.com", "MX", "10 mail.example.com"),
+        ("example.com", "TXT", "v=spf1 -all; it's"),
+        ("host9.example.com", "AAAA", "fd00::9"),
+        ("host10.example.com", "A", "10.0.0.10"),
+        ("www.example.com", "A", "10.0.0.1"),
+        ("www.example.com", "A", "10.0.0.2"),
+    ]
+    .into_iter()
+    .map(|(n, k, c)| (n.to_string(), k.to_string(), c.to_string()))
+    .collect();
+    assert_eq!(read, expected);
+}
+
+#[test]
+fn unchanged_file_is_not_rewritten() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.yaml");
+
+    // Dry runs write nothing
+    frontend(&path, Format::Yaml, None)
+        .set_records(records(), true)
+        .unwrap();
+    assert!(!path.exists());
+
+    frontend(&path, Format::Yaml, None)
+        .set_records(records(), false)
+        .unwrap();
+    let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+
+    // The order records arrive in doesn't matter
+    let mut reversed = records();
+    reversed.reverse();
+    frontend(&path, Format::Yaml, None)
+        .set_records(reversed, false)
+        .unwrap();
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().modified().unwrap(),
+        modified
+    );
+}
+
+#[test]
+fn invalid_content_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("example.com.yaml");
+
+    let err = frontend(&path, Format::Yaml, None)
+        .set_records(vec![record("example.com", "MX", "mail.example.com")], false)
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Cannot export MX record example.com with content mail.example.com"));
+    assert!(!path.exists());
+}
@@ -0,0 +1,294 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::gandi::{Config, Gandi};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, FakeApi};
+
+const API_KEY: &str = "secret";
+const INSTANCE_ID: &str = "test";
+const DOMAIN_PATH: &str = "/v5/livedns/domains/example.com";
+
+#[derive(Default)]
+struct State {
+    rrsets: Vec<Value>,
+    /// Method and path of each write request received.
+    writes: Vec<String>,
+}
+
+/// An in-process stand-in for the Gandi LiveDNS API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, api_key: &str) -> Gandi {
+        Gandi::from(self.config(api_key))
+    }
+
+    fn config(&self, api_key: &str) -> Config {
+        Config {
+            api_key: api_key.into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            base_url: Some(self.url("/v5/livedns")),
+            registry_prefix: None,
+            ttl: None,
+        }
+    }
+
+    fn add_rrset(&self, name: &str, kind: &str, values: &[&str]) {
+        self.state.lock().unwrap().rrsets.push(json!({
+            "rrset_name": name,
+            "rrset_type": kind,
+            "rrset_ttl": 10800,
+            "rrset_values": values,
+            "rrset_href": format!("https://api.gandi.net{DOMAIN_PATH}/records/{name}/{kind}"),
+        }));
+    }
+
+    fn add_owner(&self, name: &str, kind: &str, instance_id: &str) {
+        self.add_rrset(
+            &format!("_dnssync.{}.{name}", kind.to_lowercase()),
+            "TXT",
+            &[&format!(
+                "\"heritage=dnssync,dnssync/instance={instance_id},dnssync/source=test\""
+            )],
+        );
+    }
+
+    fn find_rrset(&self, name: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .rrsets
+            .iter()
+            .find(|r| r["rrset_name"] == name && r["rrset_type"] == kind)
+            .cloned()
+    }
+
+    fn writes(&self) -> Vec<String> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Authorization") && h.value.as_str() == format!("Bearer {API_KEY}"));
+    if !authorized {
+        return respond(
+            request,
+            401,
+            json!({
+                "code": 401,
+                "message": "The server could not verify that you are authorized to access the document you requested.",
+                "object": "HTTPUnauthorized",
+                "cause": "Unauthorized",
+            }),
+        );
+    }
+
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+
+    let method = request.method().clone();
+    let path = url.path().to_string();
+    if method != tiny_http::Method::Get {
+        state.writes.push(format!("{method} {path}"));
+    }
+
+    let rrset = path
+        .strip_prefix(DOMAIN_PATH)
+        .and_then(|p| p.strip_prefix("/records/"))
+        .and_then(|p| p.split_once('/'))
+        .map(|(name, kind)| (name.to_string(), kind.to_string()));
+
+    match (method, path.as_str(), rrset) {
+        (tiny_http::Method::Get, DOMAIN_PATH, None) => respond(
+            request,
+            200,
+            json!({"fqdn": "example.com", "automatic_snapshots": true}),
+        ),
+        (tiny_http::Method::Get, p, None) if p == format!("{DOMAIN_PATH}/records") => {
+            respond(request, 200, json!(state.rrsets))
+        }
+        (tiny_http::Method::Put, _, Some((name, kind))) => {
+            let values: Value = serde_json::from_str(&body).unwrap();
+            if values["rrset_ttl"].as_u64().unwrap() < 300 {
+                return respond(
+                    request,
+                    400,
+                    json!({
+                        "code": 400,
+                        "message": "Bad Request",
+                        "object": "HTTPBadRequest",
+                        "cause": "",
+                        "errors": [{
+                            "location": "body",
+                            "name": "rrset_ttl",
+                            "description": "300 is the minimum value allowed",
+                        }],
+                    }),
+                );
+            }
+            let rrset = json!({
+                "rrset_name": name,
+                "rrset_type": kind,
+                "rrset_ttl": values["rrset_ttl"],
+                "rrset_values": values["rrset_values"],
+            });
+            match state
+                .rrsets
+                .iter_mut()
+                .find(|r| r["rrset_name"] == name && r["rrset_type"] == kind)
+            {
+                Some(existing) => *existing = rrset,
+                None => state.rrsets.push(rrset),
+            }
+            respond(request, 201, json!({"message": "DNS Record Created"}))
+        }
+        (tiny_http::Method::Delete, _, Some((name, kind))) => {
+            let before = state.rrsets.len();
+            state
+                .rrsets
+                .retain(|r| r["rrset_name"] != name || r["rrset_type"] != kind);
+            match state.rrsets.len() < before {
+                true => respond(request, 204, Value::Null),
+                false => respond(
+                    request,
+                    404,
+                    json!({"code": 404, "message": "Can't find the DNS record", "cause": "Not Found"}),
+                ),
+            }
+        }
+        _ => respond(
+            request,
+            404,
+            json!({"code": 404, "message": "The resource could not be found.", "cause": "Not Found"}),
+        ),
+    }
+}
+
+#[test]
+fn replaces_rrsets() {
+    let api = FakeApi::start();
+    api.add_rrset("@", "NS", &["ns-1.gandi.net.", "ns-2.gandi.net."]);
+    api.add_rrset("host", "A", &["10.0.0.1"]);
+    api.add_owner("host", "A", INSTANCE_ID);
+    api.add_rrset("old", "A", &["10.0.0.1"]);
+    api.add_owner("old", "A", INSTANCE_ID);
+    // Relative values are resolved against the zone
+    api.add_rrset("www", "CNAME", &["host"]);
+    api.add_owner("www", "CNAME", INSTANCE_ID);
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("www.example.com", "CNAME", "host.example.com"),
+        record("alias.example.com", "CNAME", "host.example.com"),
+        record("example.com", "TXT", "hello \"world\""),
+    ];
+
+    // A dry run writes nothing
+    api.frontend(API_KEY)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    let records_path = format!("{DOMAIN_PATH}/records");
+    assert_eq!(
+        api.writes(),
+        vec![
+            format!("DELETE {records_path}/old/A"),
+            format!("DELETE {records_path}/_dnssync.a.old/TXT"),
+            format!("PUT {records_path}/host/A"),
+            format!("PUT {records_path}/alias/CNAME"),
+            format!("PUT {records_path}/@/TXT"),
+            format!("PUT {records_path}/_dnssync.cname.alias/TXT"),
+            format!("PUT {records_path}/_dnssync.txt/TXT"),
+        ]
+    );
+
+    assert!(api.find_rrset("@", "NS").is_some());
+    let host = api.find_rrset("host", "A").unwrap();
+    assert_eq!(host["rrset_values"], json!(["10.0.0.2"]));
+    assert_eq!(host["rrset_ttl"], 10800);
+
+    let alias = api.find_rrset("alias", "CNAME").unwrap();
+    assert_eq!(alias["rrset_values"], json!(["host.example.com."]));
+    assert_eq!(alias["rrset_ttl"], 300);
+
+    assert_eq!(
+        api.find_rrset("@", "TXT").unwrap()["rrset_values"],
+        json!(["\"hello \\\"world\\\"\""])
+    );
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.writes().len(), 7);
+}
+
+#[test]
+fn writes_values_of_a_name_as_one_rrset() {
+    let api = FakeApi::start();
+    api.add_rrset("many", "A", &["10.0.0.2", "10.0.0.1"]);
+    api.add_owner("many", "A", INSTANCE_ID);
+
+    let records = vec![
+        record("web.example.com", "A", "10.0.0.1"),
+        record("web.example.com", "A", "10.0.0.2"),
+        record("many.example.com", "A", "10.0.0.1"),
+        record("many.example.com", "A", "10.0.0.2"),
+    ];
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    let records_path = format!("{DOMAIN_PATH}/records");
+    assert_eq!(
+        api.writes(),
+        vec![
+            format!("PUT {records_path}/web/A"),
+            format!("PUT {records_path}/_dnssync.a.web/TXT"),
+        ]
+    );
+    assert_eq!(
+        api.find_rrset("web", "A").unwrap()["rrset_values"],
+        json!(["10.0.0.1", "10.0.0.2"])
+    );
+
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.writes().len(), 2);
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 401: The server could not verify"));
+}
+
+#[test]
+fn ttl_is_raised_to_minimum() {
+    let api = FakeApi::start();
+
+    let mut config = api.config(API_KEY);
+    config.ttl = Some(60);
+    Gandi::from(config)
+        .set_records(vec![record("new.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert_eq!(api.find_rrset("new", "A").unwrap()["rrset_ttl"], 300);
+}
@@ -0,0 +1,246 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::hetzner::{Config, Hetzner};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, FakeApi};
+
+const API_KEY: &str = "secret";
+const ZONE_ID: &str = "zone1";
+const INSTANCE_ID: &str = "test";
+const PAGE_SIZE: usize = 2;
+
+#[derive(Default)]
+struct State {
+    records: Vec<Value>,
+    next_id: usize,
+    /// Method and path of each write request received.
+    writes: Vec<String>,
+}
+
+/// An in-process stand-in for the Hetzner DNS API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, api_key: &str) -> Hetzner {
+        Hetzner::from(Config {
+            api_key: api_key.into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            base_url: Some(self.url("/api/v1")),
+            registry_prefix: None,
+            ttl: None,
+        })
+    }
+
+    fn add_record(&self, name: &str, kind: &str, value: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let id = format!("r{}", state.next_id);
+        state.records.push(json!({
+            "id": id,
+            "zone_id": ZONE_ID,
+            "type": kind,
+            "name": name,
+            "value": value,
+            "ttl": 3600,
+        }));
+    }
+
+    fn add_owner(&self, name: &str, kind: &str, instance_id: &str) {
+        self.add_record(
+            &format!("_dnssync.{}.{name}", kind.to_lowercase()),
+            "TXT",
+            &format!("\"heritage=dnssync,dnssync/instance={instance_id},dnssync/source=test\""),
+        );
+    }
+
+    fn find_record(&self, name: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .records
+            .iter()
+            .find(|r| r["name"] == name && r["type"] == kind)
+            .cloned()
+    }
+
+    fn writes(&self) -> Vec<String> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Auth-API-Token") && h.value == API_KEY);
+    if !authorized {
+        return respond(
+            request,
+            401,
+            json!({"message": "Invalid authentication credentials"}),
+        );
+    }
+
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.to_string())
+    };
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+
+    let method = request.method().clone();
+    let path = url.path().to_string();
+    if method != tiny_http::Method::Get {
+        state.writes.push(format!("{method} {path}"));
+    }
+
+    match (method, path.as_str()) {
+        (tiny_http::Method::Get, "/api/v1/zones") => {
+            assert_eq!(query("name").as_deref(), Some("example.com"));
+            respond(
+                request,
+                200,
+                json!({"zones": [{"id": ZONE_ID, "name": "example.com"}]}),
+            )
+        }
+        (tiny_http::Method::Get, "/api/v1/records") => {
+            assert_eq!(query("zone_id").as_deref(), Some(ZONE_ID));
+            let page: usize = query("page").unwrap().parse().unwrap();
+            let last_page = state.records.len().div_ceil(PAGE_SIZE).max(1);
+            let records: Vec<&Value> = state
+                .records
+                .iter()
+                .skip((page - 1) * PAGE_SIZE)
+                .take(PAGE_SIZE)
+                .collect();
+            respond(
+                request,
+                200,
+                json!({
+                    "records": records,
+                    "meta": {"pagination": {
+                        "page": page,
+                        "per_page": PAGE_SIZE,
+                        "last_page": last_page,
+                        "total_entries": state.records.len(),
+                    }},
+                }),
+            )
+        }
+        (tiny_http::Method::Post, "/api/v1/records/bulk") => {
+            let bulk: Value = serde_json::from_str(&body).unwrap();
+            for record in bulk["records"].as_array().unwrap() {
+                assert!(record.get("id").is_none());
+                state.next_id += 1;
+                let mut record = record.clone();
+                record["id"] = json!(format!("r{}", state.next_id));
+                state.records.push(record);
+            }
+            respond(request, 200, json!({"records": [], "invalid_records": []}))
+        }
+        (tiny_http::Method::Put, "/api/v1/records/bulk") => {
+            let bulk: Value = serde_json::from_str(&body).unwrap();
+            for record in bulk["records"].as_array().unwrap() {
+                let existing = state
+                    .records
+                    .iter_mut()
+                    .find(|r| r["id"] == record["id"])
+                    .unwrap();
+                *existing = record.clone();
+            }
+            respond(request, 200, json!({"records": [], "failed_records": []}))
+        }
+        (tiny_http::Method::Delete, path) => {
+            let id = path.rsplit('/').next().unwrap();
+            let before = state.records.len();
+            state.records.retain(|r| r["id"] != id);
+            match state.records.len() < before {
+                true => respond(request, 200, json!({})),
+                false => respond(
+                    request,
+                    404,
+                    json!({"error": {"message": "record not found", "code": 404}}),
+                ),
+            }
+        }
+        _ => respond(
+            request,
+            404,
+            json!({"error": {"message": "not found", "code": 404}}),
+        ),
+    }
+}
+
+#[test]
+fn applies_changes_in_bulk() {
+    let api = FakeApi::start();
+    api.add_record("@", "NS", "hydrogen.ns.hetzner.com.");
+    api.add_record("host", "A", "10.0.0.1");
+    api.add_owner("host", "A", INSTANCE_ID);
+    api.add_record("old", "A", "10.0.0.1");
+    api.add_owner("old", "A", INSTANCE_ID);
+    // Relative names in values are resolved against the zone
+    api.add_record("www", "CNAME", "host");
+    api.add_owner("www", "CNAME", INSTANCE_ID);
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("www.example.com", "CNAME", "host.example.com"),
+        record("alias.example.com", "CNAME", "host.example.com"),
+        record("example.com", "TXT", "hello \"world\""),
+    ];
+
+    // A dry run writes nothing
+    api.frontend(API_KEY)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    let writes = api.writes();
+    assert_eq!(writes.len(), 4);
+    assert!(writes[0].starts_with("DELETE"));
+    assert!(writes[1].starts_with("DELETE"));
+    assert_eq!(writes[2], "PUT /api/v1/records/bulk");
+    assert_eq!(writes[3], "POST /api/v1/records/bulk");
+
+    assert!(api.find_record("old", "A").is_none());
+    assert!(api.find_record("_dnssync.a.old", "TXT").is_none());
+    assert!(api.find_record("@", "NS").is_some());
+    assert_eq!(api.find_record("host", "A").unwrap()["value"], "10.0.0.2");
+
+    let alias = api.find_record("alias", "CNAME").unwrap();
+    assert_eq!(alias["value"], "host.example.com.");
+    assert_eq!(alias["ttl"], 300);
+    assert!(api.find_record("_dnssync.cname.alias", "TXT").is_some());
+
+    let info = api.find_record("@", "TXT").unwrap();
+    assert_eq!(info["value"], "\"hello \\\"world\\\"\"");
+    assert!(api.find_record("_dnssync.txt", "TXT").is_some());
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.writes().len(), 4);
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 401: Invalid authentication credentials"));
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use std::path::Path;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::hosts::{Config, Hosts};
+
+use common::record;
+
+const EXISTING: &str = "127.0.0.1\tlocalhost\n::1\tlocalhost\n";
+
+fn frontend(path: &Path, instance_id: &str) -> Hosts {
+    Hosts::from(Config {
+        domain: "example.com".into(),
+        path: Some(path.to_path_buf()),
+        instance_id: instance_id.into(),
+    })
+}
+
+#[test]
+fn maintains_block_and_keeps_other_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hosts");
+    std::fs::write(&path, EXISTING).unwrap();
+
+    frontend(&path, "test")
+        .set_records(
+            vec![
+                record("b.example.com", "A", "10.0.0.2"),
+                record("a.example.com", "AAAA", "fd00::1"),
+                record("alias.example.com", "CNAME", "a.example.com"),
+            ],
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        format!(
+            "{EXISTING}# BEGIN dnssync test\nfd00::1\ta.example.com\n10.0.0.2\tb.example.com\n# END dnssync test\n"
+        )
+    );
+
+    // A hand made line after the block is kept in place
+    let text = std::fs::read_to_string(&path).unwrap() + "10.9.9.9\tmanual\n";
+    std::fs::write(&path, text).unwrap();
+
+    frontend(&path, "test")
+        .set_records(vec![record("b.example.com", "A", "10.0.0.3")], false)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        format!("{EXISTING}# BEGIN dnssync test\n10.0.0.3\tb.example.com\n# END dnssync test\n10.9.9.9\tmanual\n")
+    );
+
+    // The block is removed once empty
+    frontend(&path, "test")
+        .set_records(Vec::new(), false)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        format!("{EXISTING}10.9.9.9\tmanual\n")
+    );
+}
+
+#[test]
+fn instances_have_separate_blocks() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hosts");
+
+    frontend(&path, "one")
+        .set_records(vec![record("a.example.com", "A", "10.0.0.1")], false)
+        .unwrap();
+    frontend(&path, "two")
+        .set_records(vec![record("b.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+    frontend(&path, "one")
+        .set_records(Vec::new(), false)
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "# BEGIN dnssync two\n10.0.0.2\tb.example.com\n# END dnssync two\n"
+    );
+}
+
+#[test]
+fn dry_run_makes_no_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hosts");
+    std::fs::write(&path, EXISTING).unwrap();
+
+    frontend(&path, "test")
+        .set_records(vec![record("a.example.com", "A", "10.0.0.1")], true)
+        .unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), EXISTING);
+}
+
+#[test]
+fn refuses_unterminated_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hosts");
+    let text =
+        format!("{EXISTING}# BEGIN dnssync test\n10.0.0.1\ta.example.com\n192.168.1.1\trouter\n");
+    std::fs::write(&path, &text).unwrap();
+
+    let err = frontend(&path, "test").check().unwrap_err();
+    assert!(err.to_string().contains("# END dnssync test"), "{err}");
+
+    frontend(&path, "test")
+        .set_records(vec![record("b.example.com", "A", "10.0.0.2")], false)
+        .unwrap_err();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::path::Path;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::magicdns::{Config, MagicDNS};
+
+use common::record;
+
+fn frontend(path: &Path) -> MagicDNS {
+    MagicDNS::from(Config {
+        domain: "ts.example.com".into(),
+        path: path.to_path_buf(),
+    })
+}
+
+fn read_records(path: &Path) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn writes_extra_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("extra-records.json");
+
+    frontend(&path)
+        .set_records(
+            vec![
+                record("web.ts.example.com", "A", "100.64.0.2"),
+                record("db.ts.example.com", "AAAA", "fd7a:115c:a1e0::1"),
+                record("web.ts.example.com", "A", "100.64.0.1"),
+                record("alias.ts.example.com", "CNAME", "web.ts.example.com"),
+            ],
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        read_records(&path),
+        serde_json::json!([
+            {"name": "db.ts.example.com", "type": "AAAA", "value": "fd7a:115c:a1e0::1"},
+            {"name": "web.ts.example.com", "type": "A", "value": "100.64.0.1"},
+            {"name": "web.ts.example.com", "type": "A", "value": "100.64.0.2"},
+        ])
+    );
+
+    frontend(&path)
+        .set_records(vec![record("web.ts.example.com", "A", "100.64.0.3")], false)
+        .unwrap();
+    assert_eq!(
+        read_records(&path),
+        serde_json::json!([
+            {"name": "web.ts.example.com", "type": "A", "value": "100.64.0.3"},
+        ])
+    );
+
+    // Once empty, Headscale still gets a valid file
+    frontend(&path).set_records(Vec::new(), false).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "[]\n");
+}
+
+#[test]
+fn unchanged_file_is_not_rewritten() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("extra-records.json");
+    let records = vec![record("web.ts.example.com", "A", "100.64.0.1")];
+
+    frontend(&path).set_records(records.clone(), false).unwrap();
+    let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+
+    frontend(&path).set_records(records.clone(), false).unwrap();
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().modified().unwrap(),
+        modified
+    );
+
+    // Hand edits are overwritten
+    std::fs::write(
+        &path,
+        r#"[{"name": "web.ts.example.com", "type": "A", "value": "100.64.0.1"}, {"name": "manual.ts.example.com", "type": "A", "value": "100.64.0.9"}]"#,
+    )
+    .unwrap();
+    frontend(&path).set_records(records, false).unwrap();
+    assert_eq!(
+        read_records(&path),
+        serde_json::json!([
+            {"name": "web.ts.example.com", "type": "A", "value": "100.64.0.1"},
+        ])
+    );
+}
+
+#[test]
+fn dry_run_makes_no_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("extra-records.json");
+
+    frontend(&path)
+        .set_records(vec![record("web.ts.example.com", "A", "100.64.0.1")], true)
+        .unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn invalid_file_is_not_overwritten() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("extra-records.json");
+    std::fs::write(&path, "not json").unwrap();
+
+    let mut frontend = frontend(&path);
+    assert!(frontend.check().is_err());
+    let err = frontend
+        .set_records(vec![record("web.ts.example.com", "A", "100.64.0.1")], false)
+        .unwrap_err();
+    assert!(err.to_string().contains("Failed to parse"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not json");
+}
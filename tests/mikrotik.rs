@@ -0,0 +1,246 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::mikrotik::{Config, MikroTik};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, FakeApi};
+
+const INSTANCE_ID: &str = "test";
+
+/// Basic auth for admin:secret
+const CREDENTIALS: &str = "Basic YWRtaW46c2VjcmV0";
+
+#[derive(Default)]
+struct State {
+    entries: Vec<Value>,
+    next_id: u32,
+    /// Method and path of each write request received.
+    writes: Vec<String>,
+}
+
+/// An in-process stand-in for the RouterOS REST API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, password: &str) -> MikroTik {
+        MikroTik::from(Config {
+            base_url: self.url("/"),
+            username: "admin".into(),
+            password: password.into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            ttl: None,
+        })
+    }
+
+    /// Adds an entry, leaving out the properties RouterOS leaves out when unset.
+    fn add_entry(&self, mut entry: Value) {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        entry[".id"] = json!(format!("*{:X}", state.next_id));
+        entry["ttl"] = json!("1d");
+        entry["disabled"] = json!("false");
+        if entry.get("dynamic").is_none() {
+            entry["dynamic"] = json!("false");
+        }
+        state.entries.push(entry);
+    }
+
+    fn find_entry(&self, name: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|e| e["name"] == name)
+            .cloned()
+    }
+
+    fn writes(&self) -> Vec<String> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn error(status: u16, message: &str, detail: &str) -> Value {
+    json!({"error": status, "message": message, "detail": detail})
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Authorization") && h.value.as_str() == CREDENTIALS);
+    if !authorized {
+        return respond(request, 401, error(401, "Unauthorized", ""));
+    }
+
+    let body = read_body(&mut request);
+    let method = request.method().to_string();
+    let path = request.url().to_string();
+    let id = path
+        .strip_prefix("/rest/ip/dns/static")
+        .map(|rest| rest.trim_start_matches('/').to_string());
+    let Some(id) = id else {
+        return respond(request, 400, error(400, "Bad Request", "no such command"));
+    };
+
+    let mut state = state.lock().unwrap();
+    if method != "GET" {
+        state.writes.push(format!("{method} {path}"));
+    }
+    match (method.as_str(), id.as_str()) {
+        ("GET", "") => {
+            let entries = Value::Array(state.entries.clone());
+            respond(request, 200, entries)
+        }
+        ("PUT", "") => {
+            let mut entry: Value = serde_json::from_str(&body).unwrap();
+            if entry["type"] == "A" && entry.get("address").is_none() {
+                return respond(
+                    request,
+                    400,
+                    error(400, "Bad Request", "failure: missing address"),
+                );
+            }
+            state.next_id += 1;
+            entry[".id"] = json!(format!("*{:X}", state.next_id));
+            entry["disabled"] = json!("false");
+            entry["dynamic"] = json!("false");
+            state.entries.push(entry.clone());
+            respond(request, 201, entry)
+        }
+        ("PATCH", id) => match state.entries.iter_mut().find(|e| e[".id"] == id) {
+            Some(entry) => {
+                let changes: serde_json::Map<String, Value> = serde_json::from_str(&body).unwrap();
+                for (key, value) in changes {
+                    entry[key] = value;
+                }
+                let entry = entry.clone();
+                respond(request, 200, entry)
+            }
+            None => respond(request, 404, error(404, "Not Found", "no such item")),
+        },
+        ("DELETE", id) => {
+            state.entries.retain(|e| e[".id"] != id);
+            respond(request, 204, Value::Null)
+        }
+        _ => respond(request, 400, error(400, "Bad Request", "no such command")),
+    }
+}
+
+fn managed(instance_id: &str) -> String {
+    format!("Managed by DNSSync instance:{instance_id}")
+}
+
+#[test]
+fn syncs_entries() {
+    let api = FakeApi::start();
+    // A entries have no type
+    api.add_entry(json!({
+        "name": "host.example.com",
+        "address": "10.0.0.1",
+        "comment": format!("NAS in the loft {}", managed(INSTANCE_ID)),
+    }));
+    api.add_entry(json!({
+        "name": "old.example.com",
+        "type": "AAAA",
+        "address": "fd00::1",
+        "comment": managed(INSTANCE_ID),
+    }));
+    api.add_entry(json!({"regexp": ".*\\.ads\\.example\\.com", "address": "0.0.0.0"}));
+    api.add_entry(json!({"name": "router.lan", "address": "192.168.88.1"}));
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("alias.example.com", "CNAME", "host.example.com."),
+        record("example.com", "TXT", "hello world"),
+    ];
+
+    // A dry run writes nothing
+    api.frontend("secret")
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+
+    api.frontend("secret")
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    assert_eq!(
+        api.writes(),
+        vec![
+            "DELETE /rest/ip/dns/static/*2",
+            "PATCH /rest/ip/dns/static/*1",
+            "PUT /rest/ip/dns/static",
+        ]
+    );
+
+    let host = api.find_entry("host.example.com").unwrap();
+    assert_eq!(host["address"], "10.0.0.2");
+    assert_eq!(host["ttl"], "1d");
+    assert_eq!(
+        host["comment"],
+        format!("NAS in the loft {}", managed(INSTANCE_ID))
+    );
+
+    let alias = api.find_entry("alias.example.com").unwrap();
+    assert_eq!(alias["type"], "CNAME");
+    assert_eq!(alias["cname"], "host.example.com");
+    assert_eq!(alias["ttl"], "300s");
+    assert_eq!(alias["comment"], managed(INSTANCE_ID));
+
+    assert!(api.find_entry("old.example.com").is_none());
+    assert!(api.find_entry("router.lan").is_some());
+    assert_eq!(api.state.lock().unwrap().entries.len(), 4);
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend("secret").set_records(records, false).unwrap();
+    assert_eq!(api.writes().len(), 3);
+}
+
+#[test]
+fn only_deletes_owned_entries() {
+    let api = FakeApi::start();
+    api.add_entry(json!({"name": "manual.example.com", "address": "10.0.0.1"}));
+    api.add_entry(json!({
+        "name": "theirs.example.com",
+        "address": "10.0.0.1",
+        "comment": managed("other"),
+    }));
+    api.add_entry(json!({
+        "name": "dhcp.example.com",
+        "address": "10.0.0.9",
+        "comment": managed(INSTANCE_ID),
+        "dynamic": "true",
+    }));
+
+    api.frontend("secret")
+        .set_records(vec![record("manual.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert!(api.writes().is_empty());
+    assert!(api.find_entry("theirs.example.com").is_some());
+    assert!(api.find_entry("dhcp.example.com").is_some());
+    assert_eq!(
+        api.find_entry("manual.example.com").unwrap()["address"],
+        "10.0.0.1"
+    );
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+    api.frontend("secret").check().unwrap();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("failed with status 401: Unauthorized"),
+        "{err}"
+    );
+}
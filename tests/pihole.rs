@@ -0,0 +1,266 @@
+mod common;
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::pihole::{Config, PiHole};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, FakeApi};
+
+const PASSWORD: &str = "secret";
+const SID: &str = "vFA+EP4MQ5JJvJg+3Q2Jnw=";
+
+#[derive(Default)]
+struct State {
+    hosts: Vec<String>,
+    cname_records: Vec<String>,
+    /// Method and path of each write request received.
+    writes: Vec<String>,
+    logged_out: bool,
+}
+
+/// An in-process stand-in for the Pi-hole v6 API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, password: &str, state_path: &Path) -> PiHole {
+        PiHole::from(Config {
+            base_url: self.url("/"),
+            password: Some(password.into()),
+            domain: "lan".into(),
+            state_path: state_path.into(),
+        })
+    }
+
+    fn add_host(&self, entry: &str) {
+        self.state.lock().unwrap().hosts.push(entry.into());
+    }
+
+    fn add_cname(&self, entry: &str) {
+        self.state.lock().unwrap().cname_records.push(entry.into());
+    }
+
+    fn hosts(&self) -> Vec<String> {
+        self.state.lock().unwrap().hosts.clone()
+    }
+
+    fn cname_records(&self) -> Vec<String> {
+        self.state.lock().unwrap().cname_records.clone()
+    }
+
+    fn writes(&self) -> Vec<String> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+    let method = request.method().clone();
+    let path = url.path().to_string();
+
+    if method == tiny_http::Method::Post && path == "/api/auth" {
+        let body: Value = serde_json::from_str(&body).unwrap();
+        return match body["password"] == PASSWORD {
+            true => respond(
+                request,
+                200,
+                json!({
+                    "session": {"valid": true, "totp": false, "sid": SID, "validity": 1800, "message": "password correct"},
+                }),
+            ),
+            false => respond(
+                request,
+                401,
+                json!({
+                    "session": {"valid": false, "totp": false, "sid": null, "validity": -1, "message": "password incorrect"},
+                }),
+            ),
+        };
+    }
+
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("X-FTL-SID") && h.value.as_str() == SID);
+    if !authorized {
+        return respond(
+            request,
+            401,
+            json!({
+                "error": {"key": "unauthorized", "message": "Unauthorized", "hint": null},
+            }),
+        );
+    }
+
+    if method != tiny_http::Method::Get {
+        state.writes.push(format!("{method} {path}"));
+    }
+
+    let entry = path
+        .strip_prefix("/api/config/dns/")
+        .and_then(|p| p.split_once('/'))
+        .map(|(key, entry)| (key.to_string(), entry.replace("%20", " ")));
+
+    match (method, path.as_str(), entry) {
+        (tiny_http::Method::Delete, "/api/auth", None) => {
+            state.logged_out = true;
+            respond(request, 204, Value::Null)
+        }
+        (tiny_http::Method::Get, "/api/config/dns", None) => respond(
+            request,
+            200,
+            json!({
+                "config": {"dns": {
+                    "upstreams": ["1.1.1.1"],
+                    "hosts": state.hosts,
+                    "cnameRecords": state.cname_records,
+                }},
+                "took": 0.001,
+            }),
+        ),
+        (method, _, Some((key, entry))) => {
+            let entries = match key.as_str() {
+                "hosts" => &mut state.hosts,
+                "cnameRecords" => &mut state.cname_records,
+                _ => return respond(request, 404, Value::Null),
+            };
+            match (method, entries.contains(&entry)) {
+                (tiny_http::Method::Put, false) => {
+                    entries.push(entry);
+                    respond(request, 201, json!({"took": 0.001}))
+                }
+                (tiny_http::Method::Put, true) => respond(
+                    request,
+                    400,
+                    json!({
+                        "error": {"key": "bad_request", "message": "Item already present", "hint": "Uniqueness of items is enforced"},
+                    }),
+                ),
+                (tiny_http::Method::Delete, true) => {
+                    entries.retain(|e| *e != entry);
+                    respond(request, 204, Value::Null)
+                }
+                _ => respond(
+                    request,
+                    404,
+                    json!({
+                        "error": {"key": "not_found", "message": "Item not found", "hint": null},
+                    }),
+                ),
+            }
+        }
+        _ => respond(request, 404, Value::Null),
+    }
+}
+
+fn write_state(path: &Path, records: Value) {
+    std::fs::write(path, json!({ "records": records }).to_string()).unwrap();
+}
+
+#[test]
+fn syncs_owned_entries() {
+    let api = FakeApi::start();
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("pihole.json");
+    api.add_host("10.0.0.1 host.lan");
+    api.add_host("10.0.0.1 old.lan");
+    write_state(
+        &state_path,
+        json!([
+            {"name": "host.lan", "kind": "A", "content": "10.0.0.1"},
+            {"name": "old.lan", "kind": "A", "content": "10.0.0.1"},
+        ]),
+    );
+
+    let records = vec![
+        record("host.lan", "A", "10.0.0.2"),
+        record("alias.lan", "CNAME", "host.lan."),
+        record("host.lan", "TXT", "ignored"),
+    ];
+
+    // A dry run writes nothing, other than logging out
+    let state = std::fs::read_to_string(&state_path).unwrap();
+    api.frontend(PASSWORD, &state_path)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert_eq!(api.writes(), vec!["DELETE /api/auth"]);
+    assert_eq!(std::fs::read_to_string(&state_path).unwrap(), state);
+    api.state.lock().unwrap().writes.clear();
+
+    api.frontend(PASSWORD, &state_path)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    assert_eq!(
+        api.writes(),
+        vec![
+            "DELETE /api/config/dns/hosts/10.0.0.1%20old.lan",
+            "DELETE /api/config/dns/hosts/10.0.0.1%20host.lan",
+            "PUT /api/config/dns/hosts/10.0.0.2%20host.lan",
+            "PUT /api/config/dns/cnameRecords/alias.lan,host.lan",
+            "DELETE /api/auth",
+        ]
+    );
+    assert_eq!(api.hosts(), vec!["10.0.0.2 host.lan"]);
+    assert_eq!(api.cname_records(), vec!["alias.lan,host.lan"]);
+    assert!(api.state.lock().unwrap().logged_out);
+
+    let state: Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    assert_eq!(
+        state["records"],
+        json!([
+            {"name": "host.lan", "kind": "A", "content": "10.0.0.2"},
+            {"name": "alias.lan", "kind": "CNAME", "content": "host.lan"},
+        ])
+    );
+
+    // Syncing again is a no-op
+    api.frontend(PASSWORD, &state_path)
+        .set_records(records, false)
+        .unwrap();
+    assert_eq!(api.writes().len(), 6);
+}
+
+#[test]
+fn leaves_unowned_entries_alone() {
+    let api = FakeApi::start();
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("pihole.json");
+    api.add_host("10.0.0.1 manual.lan");
+    api.add_host("10.0.0.1 nas.lan nas");
+    api.add_cname("www.lan,manual.lan,300");
+    // Listed as ours, but shares its entry with another name
+    write_state(
+        &state_path,
+        json!([{"name": "nas.lan", "kind": "A", "content": "10.0.0.1"}]),
+    );
+
+    api.frontend(PASSWORD, &state_path)
+        .set_records(vec![record("manual.lan", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert_eq!(api.writes(), vec!["DELETE /api/auth"]);
+    assert_eq!(api.hosts().len(), 2);
+    assert_eq!(api.cname_records().len(), 1);
+}
+
+#[test]
+fn bad_password_is_reported() {
+    let api = FakeApi::start();
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = api
+        .frontend("wrong", &dir.path().join("pihole.json"))
+        .check()
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status 401: password incorrect"));
+}
@@ -0,0 +1,293 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::powerdns::{Config, PowerDNS};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, FakeApi};
+
+const ZONE_PATH: &str = "/api/v1/servers/localhost/zones/example.com.";
+const API_KEY: &str = "secret";
+const INSTANCE_ID: &str = "test";
+
+#[derive(Default)]
+struct State {
+    rrsets: Vec<Value>,
+    /// Bodies of PATCH requests received.
+    patches: Vec<Value>,
+}
+
+/// An in-process stand-in for the zone endpoints of the PowerDNS API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self, api_key: &str) -> PowerDNS {
+        PowerDNS::from(Config {
+            api_key: api_key.into(),
+            base_url: self.url("/"),
+            server_id: None,
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            ttl: None,
+        })
+    }
+
+    fn add_rrset(&self, name: &str, kind: &str, contents: &[&str], comments: &[&str]) {
+        let records: Vec<Value> = contents
+            .iter()
+            .map(|c| json!({"content": c, "disabled": false}))
+            .collect();
+        let comments: Vec<Value> = comments
+            .iter()
+            .map(|c| json!({"content": c, "account": ""}))
+            .collect();
+        self.state.lock().unwrap().rrsets.push(json!({
+            "name": name,
+            "type": kind,
+            "ttl": 3600,
+            "records": records,
+            "comments": comments,
+        }));
+    }
+
+    fn find_rrset(&self, name: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .rrsets
+            .iter()
+            .find(|r| r["name"] == name && r["type"] == kind)
+            .cloned()
+    }
+
+    fn patches(&self) -> Vec<Value> {
+        self.state.lock().unwrap().patches.clone()
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("X-API-Key") && h.value == API_KEY);
+    if !authorized {
+        return respond(request, 401, json!({"error": "Unauthorized"}));
+    }
+    if request.url() != ZONE_PATH {
+        return respond(request, 404, json!({"error": "Not Found"}));
+    }
+
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+
+    match request.method() {
+        tiny_http::Method::Get => {
+            let zone = json!({"name": "example.com.", "rrsets": state.rrsets});
+            respond(request, 200, zone)
+        }
+        tiny_http::Method::Patch => {
+            let patch: Value = serde_json::from_str(&body).unwrap();
+            let rrsets = patch["rrsets"].as_array().unwrap();
+            for (i, rrset) in rrsets.iter().enumerate() {
+                if rrsets[..i]
+                    .iter()
+                    .any(|r| r["name"] == rrset["name"] && r["type"] == rrset["type"])
+                {
+                    let error = format!(
+                        "Duplicate RRset {} IN {} with changetype: REPLACE",
+                        rrset["name"].as_str().unwrap(),
+                        rrset["type"].as_str().unwrap()
+                    );
+                    return respond(request, 422, json!({ "error": error }));
+                }
+            }
+            for rrset in patch["rrsets"].as_array().unwrap() {
+                state
+                    .rrsets
+                    .retain(|r| !(r["name"] == rrset["name"] && r["type"] == rrset["type"]));
+                if rrset["changetype"] == "REPLACE" {
+                    let mut stored = rrset.clone();
+                    stored.as_object_mut().unwrap().remove("changetype");
+                    state.rrsets.push(stored);
+                }
+            }
+            state.patches.push(patch);
+            respond(request, 204, Value::Null)
+        }
+        _ => respond(request, 405, Value::Null),
+    }
+}
+
+fn managed_comment(instance_id: &str) -> String {
+    format!("Managed by DNSSync instance:{instance_id}")
+}
+
+#[test]
+fn sends_changes_in_one_patch() {
+    let api = FakeApi::start();
+    api.add_rrset(
+        "host.example.com.",
+        "A",
+        &["10.0.0.1"],
+        &[&managed_comment(INSTANCE_ID)],
+    );
+    api.add_rrset(
+        "old.example.com.",
+        "A",
+        &["10.0.0.1"],
+        &[&managed_comment(INSTANCE_ID)],
+    );
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("alias.example.com", "CNAME", "host.example.com"),
+        record("info.example.com", "TXT", "hello \"world\""),
+    ];
+
+    // A dry run writes nothing
+    api.frontend(API_KEY)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.patches().is_empty());
+
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    assert_eq!(api.patches().len(), 1);
+    assert!(api.find_rrset("old.example.com.", "A").is_none());
+
+    let updated = api.find_rrset("host.example.com.", "A").unwrap();
+    assert_eq!(updated["records"][0]["content"], "10.0.0.2");
+    assert_eq!(updated["ttl"], 3600);
+
+    let alias = api.find_rrset("alias.example.com.", "CNAME").unwrap();
+    assert_eq!(alias["records"][0]["content"], "host.example.com.");
+    assert_eq!(alias["ttl"], 300);
+    assert_eq!(
+        alias["comments"][0]["content"],
+        managed_comment(INSTANCE_ID)
+    );
+
+    let info = api.find_rrset("info.example.com.", "TXT").unwrap();
+    assert_eq!(info["records"][0]["content"], "\"hello \\\"world\\\"\"");
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.patches().len(), 1);
+}
+
+#[test]
+fn writes_values_of_a_name_as_one_rrset() {
+    let api = FakeApi::start();
+    api.add_rrset(
+        "many.example.com.",
+        "A",
+        &["10.0.0.2", "10.0.0.1"],
+        &[&managed_comment(INSTANCE_ID)],
+    );
+
+    let records = vec![
+        record("web.example.com", "A", "10.0.0.1"),
+        record("web.example.com", "A", "10.0.0.2"),
+        record("many.example.com", "A", "10.0.0.1"),
+        record("many.example.com", "A", "10.0.0.2"),
+    ];
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    // Only the new RRset is written, as the existing one is unchanged
+    let patches = api.patches();
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0]["rrsets"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        api.find_rrset("web.example.com.", "A").unwrap()["records"],
+        json!([
+            {"content": "10.0.0.1", "disabled": false},
+            {"content": "10.0.0.2", "disabled": false},
+        ])
+    );
+
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.patches().len(), 1);
+}
+
+#[test]
+fn parses_txt_character_strings() {
+    let api = FakeApi::start();
+    api.add_rrset(
+        "info.example.com.",
+        "TXT",
+        &["\"hello \" \"\\\"world\\\"\""],
+        &[&managed_comment(INSTANCE_ID)],
+    );
+
+    api.frontend(API_KEY)
+        .set_records(
+            vec![record("info.example.com", "TXT", "hello \"world\"")],
+            false,
+        )
+        .unwrap();
+
+    assert!(api.patches().is_empty());
+}
+
+#[test]
+fn keeps_unmanaged_records_and_other_comments() {
+    let api = FakeApi::start();
+    api.add_rrset("manual.example.com.", "A", &["10.0.0.1"], &[]);
+    api.add_rrset(
+        "theirs.example.com.",
+        "A",
+        &["10.0.0.1"],
+        &[&managed_comment("other")],
+    );
+    api.add_rrset(
+        "noted.example.com.",
+        "A",
+        &["10.0.0.1"],
+        &["Ask ops first", &managed_comment(INSTANCE_ID)],
+    );
+
+    api.frontend(API_KEY)
+        .set_records(
+            vec![
+                record("manual.example.com", "A", "10.0.0.2"),
+                record("noted.example.com", "A", "10.0.0.2"),
+            ],
+            false,
+        )
+        .unwrap();
+
+    // Only our RRset is sent, keeping the comment someone else left on it
+    let patches = api.patches();
+    assert_eq!(patches[0]["rrsets"].as_array().unwrap().len(), 1);
+    let noted = api.find_rrset("noted.example.com.", "A").unwrap();
+    let comments: Vec<&str> = noted["comments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        comments,
+        vec!["Ask ops first", &managed_comment(INSTANCE_ID)]
+    );
+    let manual = api.find_rrset("manual.example.com.", "A").unwrap();
+    assert_eq!(manual["records"][0]["content"], "10.0.0.1");
+    assert!(api.find_rrset("theirs.example.com.", "A").is_some());
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+
+    assert!(err.to_string().contains("Unauthorized"));
+}
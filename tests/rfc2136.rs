@@ -0,0 +1,68 @@
+//! Runs against a real primary server, as there is no in-process fake for
+//! DNS UPDATE. For example with BIND, configure a zone with
+//! `allow-transfer { key dnssync; }; update-policy { grant dnssync zonesub ANY; };`
+//! then run:
+//!
+//! ```bash
+//! DNSSYNC_TEST_RFC2136_SERVER=127.0.0.1:5353 \
+//! DNSSYNC_TEST_RFC2136_DOMAIN=example.test \
+//! DNSSYNC_TEST_RFC2136_KEY_NAME=dnssync \
+//! DNSSYNC_TEST_RFC2136_KEY_SECRET=base64secret== \
+//!   cargo test --test rfc2136 -- --ignored
+//! ```
+use dnssync_rs::common::{Frontend, Record};
+use dnssync_rs::rfc2136::{Config, Rfc2136};
+
+fn env(name: &str) -> String {
+    let var = format!("DNSSYNC_TEST_RFC2136_{name}");
+    std::env::var(&var).unwrap_or_else(|_| panic!("{var} must be set"))
+}
+
+fn frontend() -> Rfc2136 {
+    Rfc2136::from(Config {
+        domain: env("DOMAIN"),
+        server: env("SERVER"),
+        key_name: env("KEY_NAME"),
+        key_secret: env("KEY_SECRET"),
+        key_algorithm: std::env::var("DNSSYNC_TEST_RFC2136_KEY_ALGORITHM").ok(),
+        instance_id: "integration-test".into(),
+        registry_prefix: None,
+        ttl: Some(60),
+    })
+}
+
+fn record(name: &str, kind: &str, content: &str) -> Record {
+    Record {
+        name: url::Host::Domain(format!("{name}.{}", env("DOMAIN"))),
+        kind: kind.into(),
+        content: content.into(),
+        source: "test".into(),
+    }
+}
+
+#[test]
+#[ignore = "requires a DNS server, see the module docs"]
+fn check_passes() {
+    frontend().check().unwrap();
+}
+
+#[test]
+#[ignore = "requires a DNS server, see the module docs"]
+fn create_update_delete() {
+    let mut frontend = frontend();
+    let records = vec![
+        record("dnssync-test-a", "A", "192.0.2.1"),
+        record("dnssync-test-txt", "TXT", "hello world"),
+    ];
+
+    // Creates are conditional on the records not existing, so repeating
+    // the sync fails unless the first run's records were read back as ours.
+    frontend.set_records(records.clone(), false).unwrap();
+    frontend.set_records(records, false).unwrap();
+
+    frontend
+        .set_records(vec![record("dnssync-test-a", "A", "192.0.2.2")], false)
+        .unwrap();
+
+    frontend.set_records(Vec::new(), false).unwrap();
+}
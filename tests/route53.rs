@@ -0,0 +1,385 @@
+mod common;
+
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::route53::{Config, Route53};
+
+use common::{read_body, record, respond_text, FakeApi};
+
+const ACCESS_KEY_ID: &str = "AKIDEXAMPLE";
+const ZONE_ID: &str = "Z0123456789";
+const RRSET_PATH: &str = "/2013-04-01/hostedzone/Z0123456789/rrset";
+const INSTANCE_ID: &str = "test";
+const PAGE_SIZE: usize = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+struct RRSet {
+    name: String,
+    kind: String,
+    ttl: u32,
+    values: Vec<String>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChangeRequest {
+    change_batch: ChangeBatch,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChangeBatch {
+    changes: Changes,
+}
+
+#[derive(serde::Deserialize)]
+struct Changes {
+    #[serde(rename = "Change")]
+    items: Vec<Change>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Change {
+    action: String,
+    resource_record_set: ChangeRRSet,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChangeRRSet {
+    name: String,
+    #[serde(rename = "Type")]
+    kind: String,
+    #[serde(rename = "TTL")]
+    ttl: u32,
+    resource_records: ResourceRecords,
+}
+
+#[derive(serde::Deserialize)]
+struct ResourceRecords {
+    #[serde(rename = "ResourceRecord")]
+    items: Vec<ResourceRecord>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ResourceRecord {
+    value: String,
+}
+
+#[derive(Default)]
+struct State {
+    rrsets: Vec<RRSet>,
+    /// Number of changes in each batch received.
+    batches: Vec<usize>,
+    list_requests: usize,
+}
+
+/// An in-process stand-in for the hosted zone endpoints of the Route 53 API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(State::default(), handle_request)
+    }
+
+    fn frontend(&self) -> Route53 {
+        Route53::from(Config {
+            access_key_id: ACCESS_KEY_ID.into(),
+            secret_access_key: "secret".into(),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            hosted_zone_id: None,
+            base_url: Some(self.url("/")),
+            region: None,
+            registry_prefix: None,
+            ttl: None,
+        })
+    }
+
+    fn add_rrset(&self, name: &str, kind: &str, values: &[&str]) {
+        self.state.lock().unwrap().rrsets.push(RRSet {
+            name: name.into(),
+            kind: kind.into(),
+            ttl: 3600,
+            values: values.iter().map(|v| v.to_string()).collect(),
+        });
+    }
+
+    fn add_owner(&self, name: &str, kind: &str, instance_id: &str) {
+        self.add_rrset(
+            &format!("_dnssync.{}.{name}", kind.to_lowercase()),
+            "TXT",
+            &[&format!(
+                "\"heritage=dnssync,dnssync/instance={instance_id},dnssync/source=test\""
+            )],
+        );
+    }
+
+    fn find_rrset(&self, name: &str, kind: &str) -> Option<RRSet> {
+        self.state
+            .lock()
+            .unwrap()
+            .rrsets
+            .iter()
+            .find(|r| r.name == name && r.kind == kind)
+            .cloned()
+    }
+
+    fn batches(&self) -> Vec<usize> {
+        self.state.lock().unwrap().batches.clone()
+    }
+}
+
+fn respond(request: tiny_http::Request, status: u16, body: String) {
+    respond_text(request, status, &body, &["Content-Type: text/xml"])
+}
+
+fn error(request: tiny_http::Request, status: u16, code: &str, message: &str) {
+    respond(
+        request,
+        status,
+        format!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>{code}</Code>\
+             <Message>{message}</Message></Error></ErrorResponse>"
+        ),
+    )
+}
+
+fn rrset_xml(rrset: &RRSet) -> String {
+    let values: String = rrset
+        .values
+        .iter()
+        .map(|v| {
+            let v = v.replace('&', "&amp;").replace('"', "&quot;");
+            format!("<ResourceRecord><Value>{v}</Value></ResourceRecord>")
+        })
+        .collect();
+    format!(
+        "<ResourceRecordSet><Name>{}</Name><Type>{}</Type><TTL>{}</TTL>\
+         <ResourceRecords>{values}</ResourceRecords></ResourceRecordSet>",
+        rrset.name, rrset.kind, rrset.ttl
+    )
+}
+
+fn list_rrsets(request: tiny_http::Request, state: &mut State) {
+    state.list_requests += 1;
+    let url = url::Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+    let start = url
+        .query_pairs()
+        .find(|(k, _)| k == "name")
+        .and_then(|(_, name)| state.rrsets.iter().position(|r| r.name == name))
+        .unwrap_or_default();
+
+    let page: Vec<&RRSet> = state.rrsets.iter().skip(start).take(PAGE_SIZE).collect();
+    let next = state.rrsets.get(start + PAGE_SIZE);
+    let truncated = match next {
+        Some(next) => format!(
+            "<IsTruncated>true</IsTruncated><NextRecordName>{}</NextRecordName>\
+             <NextRecordType>{}</NextRecordType>",
+            next.name, next.kind
+        ),
+        None => "<IsTruncated>false</IsTruncated>".to_string(),
+    };
+    let rrsets: String = page.into_iter().map(rrset_xml).collect();
+    respond(
+        request,
+        200,
+        format!(
+            "<ListResourceRecordSetsResponse><ResourceRecordSets>{rrsets}</ResourceRecordSets>\
+             {truncated}<MaxItems>{PAGE_SIZE}</MaxItems></ListResourceRecordSetsResponse>"
+        ),
+    )
+}
+
+/// Applies the batch atomically, rejecting it like Route 53 would.
+fn change_rrsets(request: tiny_http::Request, body: &str, state: &mut State) {
+    let batch: ChangeRequest = quick_xml::de::from_str(body).unwrap();
+    let mut rrsets = state.rrsets.clone();
+    for change in batch.change_batch.changes.items.iter() {
+        let set = &change.resource_record_set;
+        let rrset = RRSet {
+            name: set.name.clone(),
+            kind: set.kind.clone(),
+            ttl: set.ttl,
+            values: set
+                .resource_records
+                .items
+                .iter()
+                .map(|r| r.value.clone())
+                .collect(),
+        };
+        let existing = rrsets
+            .iter()
+            .position(|r| r.name == rrset.name && r.kind == rrset.kind);
+        match (change.action.as_str(), existing) {
+            ("CREATE", None) => rrsets.push(rrset),
+            ("UPSERT", Some(i)) => rrsets[i] = rrset,
+            ("UPSERT", None) => rrsets.push(rrset),
+            ("DELETE", Some(i)) if rrsets[i] == rrset => {
+                rrsets.remove(i);
+            }
+            (action, _) => {
+                let message = format!(
+                    "<Message>{action} of {} {} failed</Message>",
+                    set.name, set.kind
+                );
+                return respond(
+                    request,
+                    400,
+                    format!(
+                        "<InvalidChangeBatch><Messages>{message}</Messages></InvalidChangeBatch>"
+                    ),
+                );
+            }
+        }
+    }
+    state.rrsets = rrsets;
+    state.batches.push(batch.change_batch.changes.items.len());
+    respond(
+        request,
+        200,
+        "<ChangeResourceRecordSetsResponse><ChangeInfo><Id>/change/C1</Id>\
+         <Status>PENDING</Status></ChangeInfo></ChangeResourceRecordSetsResponse>"
+            .to_string(),
+    )
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let signed = request.headers().iter().any(|h| {
+        h.field.equiv("Authorization")
+            && h.value
+                .as_str()
+                .starts_with(&format!("AWS4-HMAC-SHA256 Credential={ACCESS_KEY_ID}/"))
+            && h.value.as_str().contains("/us-east-1/route53/aws4_request")
+    });
+    let dated = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("X-Amz-Date"));
+    if !signed || !dated {
+        return error(request, 403, "InvalidSignatureException", "Bad signature");
+    }
+
+    let body = read_body(&mut request);
+    let mut state = state.lock().unwrap();
+
+    let path = request.url().split('?').next().unwrap().to_string();
+    match (request.method(), path.as_str()) {
+        (tiny_http::Method::Get, "/2013-04-01/hostedzonesbyname") => respond(
+            request,
+            200,
+            format!(
+                "<ListHostedZonesByNameResponse><HostedZones><HostedZone>\
+                 <Id>/hostedzone/{ZONE_ID}</Id><Name>example.com.</Name></HostedZone>\
+                 </HostedZones><IsTruncated>false</IsTruncated><MaxItems>1</MaxItems>\
+                 </ListHostedZonesByNameResponse>"
+            ),
+        ),
+        (tiny_http::Method::Get, RRSET_PATH) => list_rrsets(request, &mut state),
+        (tiny_http::Method::Post, p) if p == format!("{RRSET_PATH}/") => {
+            change_rrsets(request, &body, &mut state)
+        }
+        _ => error(request, 404, "NotFound", "Not Found"),
+    }
+}
+
+#[test]
+fn sends_changes_in_one_batch() {
+    let api = FakeApi::start();
+    api.add_rrset("example.com.", "NS", &["ns-1.awsdns-00.com."]);
+    api.add_rrset("host.example.com.", "A", &["10.0.0.1"]);
+    api.add_owner("host.example.com.", "A", INSTANCE_ID);
+    api.add_rrset("old.example.com.", "A", &["10.0.0.1", "10.0.0.2"]);
+    api.add_owner("old.example.com.", "A", INSTANCE_ID);
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("alias.example.com", "CNAME", "host.example.com"),
+        record("info.example.com", "TXT", "hello \"world\""),
+    ];
+
+    // A dry run writes nothing
+    api.frontend().set_records(records.clone(), true).unwrap();
+    assert!(api.batches().is_empty());
+
+    api.frontend().set_records(records.clone(), false).unwrap();
+
+    // The listing was paginated
+    assert!(api.state.lock().unwrap().list_requests > 1);
+
+    // Delete, owner delete, update, 2 creates and their owners
+    assert_eq!(api.batches(), vec![7]);
+    // The whole record set is deleted, even with several values
+    assert!(api.find_rrset("old.example.com.", "A").is_none());
+    assert!(api
+        .find_rrset("_dnssync.a.old.example.com.", "TXT")
+        .is_none());
+    assert!(api.find_rrset("example.com.", "NS").is_some());
+
+    let host = api.find_rrset("host.example.com.", "A").unwrap();
+    assert_eq!(host.values, vec!["10.0.0.2"]);
+    assert_eq!(host.ttl, 3600);
+
+    let alias = api.find_rrset("alias.example.com.", "CNAME").unwrap();
+    assert_eq!(alias.values, vec!["host.example.com"]);
+    assert_eq!(alias.ttl, 300);
+    let owner = api
+        .find_rrset("_dnssync.cname.alias.example.com.", "TXT")
+        .unwrap();
+    assert!(owner.values[0].contains("dnssync/instance=test"));
+
+    let info = api.find_rrset("info.example.com.", "TXT").unwrap();
+    assert_eq!(info.values, vec!["\"hello \\\"world\\\"\""]);
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend().set_records(records, false).unwrap();
+    assert_eq!(api.batches().len(), 1);
+}
+
+#[test]
+fn writes_values_of_a_name_as_one_record_set() {
+    let api = FakeApi::start();
+    api.add_rrset("many.example.com.", "A", &["10.0.0.2", "10.0.0.1"]);
+    api.add_owner("many.example.com.", "A", INSTANCE_ID);
+
+    let records = vec![
+        record("web.example.com", "A", "10.0.0.1"),
+        record("web.example.com", "A", "10.0.0.2"),
+        record("many.example.com", "A", "10.0.0.1"),
+        record("many.example.com", "A", "10.0.0.2"),
+    ];
+    api.frontend().set_records(records.clone(), false).unwrap();
+
+    // One create and its owner, as the existing set is unchanged
+    assert_eq!(api.batches(), vec![2]);
+    assert_eq!(
+        api.find_rrset("web.example.com.", "A").unwrap().values,
+        vec!["10.0.0.1", "10.0.0.2"]
+    );
+
+    api.frontend().set_records(records, false).unwrap();
+    assert_eq!(api.batches().len(), 1);
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+    api.frontend().check().unwrap();
+
+    let mut frontend = Route53::from(Config {
+        access_key_id: "AKIDOTHER".into(),
+        secret_access_key: "secret".into(),
+        domain: "example.com".into(),
+        instance_id: INSTANCE_ID.into(),
+        hosted_zone_id: Some(format!("/hostedzone/{ZONE_ID}")),
+        base_url: Some(api.url("/")),
+        region: None,
+        registry_prefix: None,
+        ttl: None,
+    });
+    let err = frontend.check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("status 403: InvalidSignatureException: Bad signature"));
+}
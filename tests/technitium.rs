@@ -0,0 +1,321 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::technitium::{Config, Technitium};
+use serde_json::{json, Value};
+
+use common::{read_body, record, respond, FakeApi};
+
+const API_KEY: &str = "secret";
+const INSTANCE_ID: &str = "test";
+
+#[derive(Default)]
+struct State {
+    zone_type: String,
+    records: Vec<Value>,
+    /// Action and form parameters of each write request received.
+    writes: Vec<(String, HashMap<String, String>)>,
+}
+
+/// An in-process stand-in for the Technitium DNS Server API.
+impl FakeApi<State> {
+    fn start() -> Self {
+        Self::serve(
+            State {
+                zone_type: "Primary".into(),
+                ..Default::default()
+            },
+            handle_request,
+        )
+    }
+
+    fn frontend(&self, api_key: &str) -> Technitium {
+        Technitium::from(Config {
+            api_key: api_key.into(),
+            base_url: self.url("/"),
+            domain: "example.com".into(),
+            instance_id: INSTANCE_ID.into(),
+            ttl: None,
+        })
+    }
+
+    fn add_record(&self, name: &str, kind: &str, rdata: Value, comments: &str) {
+        self.state.lock().unwrap().records.push(json!({
+            "disabled": false,
+            "name": name,
+            "type": kind,
+            "ttl": 3600,
+            "rData": rdata,
+            "dnssecStatus": "Disabled",
+            "comments": comments,
+        }));
+    }
+
+    fn find_record(&self, name: &str, kind: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .records
+            .iter()
+            .find(|r| r["name"] == name && r["type"] == kind)
+            .cloned()
+    }
+
+    fn writes(&self) -> Vec<(String, HashMap<String, String>)> {
+        self.state.lock().unwrap().writes.clone()
+    }
+}
+
+fn error(message: &str) -> Value {
+    json!({"status": "error", "errorMessage": message, "stackTrace": "", "innerErrorMessage": null})
+}
+
+/// Builds the rData of a record from the form parameters with the given prefix.
+fn rdata(kind: &str, params: &HashMap<String, String>, prefix: &str) -> Value {
+    let fields: &[&str] = match kind {
+        "A" | "AAAA" => &["ipAddress"],
+        "CNAME" => &["cname"],
+        "TXT" => &["text"],
+        "MX" => &["preference", "exchange"],
+        _ => panic!("unexpected record kind {kind}"),
+    };
+    let mut rdata = serde_json::Map::new();
+    for field in fields {
+        let param = match prefix {
+            "" => field.to_string(),
+            prefix => format!("{prefix}{}{}", field[..1].to_uppercase(), &field[1..]),
+        };
+        let value = params.get(&param).cloned().unwrap_or_default();
+        let value = match value.parse::<u64>() {
+            Ok(number) => json!(number),
+            Err(_) => json!(value),
+        };
+        rdata.insert(field.to_string(), value);
+    }
+    Value::Object(rdata)
+}
+
+fn handle_request(mut request: tiny_http::Request, state: &Mutex<State>) {
+    let body = read_body(&mut request);
+    let params: HashMap<String, String> = url::form_urlencoded::parse(body.as_bytes())
+        .into_owned()
+        .collect();
+    if params.get("token").map(String::as_str) != Some(API_KEY) {
+        return respond(
+            request,
+            200,
+            json!({"status": "invalid-token", "errorMessage": "Invalid token or session expired."}),
+        );
+    }
+
+    let mut state = state.lock().unwrap();
+    let action = request
+        .url()
+        .strip_prefix("/api/zones/records/")
+        .unwrap_or_default()
+        .to_string();
+    if action != "get" {
+        state.writes.push((action.clone(), params.clone()));
+    }
+
+    let name = params.get("domain").cloned().unwrap_or_default();
+    let kind = params.get("type").cloned().unwrap_or_default();
+    match action.as_str() {
+        "get" => {
+            let zone = json!({"name": "example.com", "type": state.zone_type, "disabled": false});
+            respond(
+                request,
+                200,
+                json!({"status": "ok", "response": {"zone": zone, "records": state.records}}),
+            )
+        }
+        "add" => {
+            let record = json!({
+                "disabled": false,
+                "name": name,
+                "type": kind,
+                "ttl": params["ttl"].parse::<u64>().unwrap(),
+                "rData": rdata(&kind, &params, ""),
+                "comments": params["comments"],
+            });
+            state.records.push(record);
+            respond(request, 200, json!({"status": "ok", "response": {}}))
+        }
+        "update" => {
+            let (current, new) = match kind.as_str() {
+                "CNAME" => (None, rdata(&kind, &params, "")),
+                _ => (
+                    Some(rdata(&kind, &params, "")),
+                    rdata(&kind, &params, "new"),
+                ),
+            };
+            match state.records.iter_mut().find(|r| {
+                r["name"] == name
+                    && r["type"] == kind
+                    && current.as_ref().is_none_or(|c| r["rData"] == *c)
+            }) {
+                Some(record) => {
+                    record["rData"] = new;
+                    record["ttl"] = json!(params["ttl"].parse::<u64>().unwrap());
+                    record["comments"] = json!(params["comments"]);
+                    respond(request, 200, json!({"status": "ok", "response": {}}))
+                }
+                None => respond(
+                    request,
+                    200,
+                    error("Cannot update record: record does not exist."),
+                ),
+            }
+        }
+        "delete" => {
+            let target = rdata(&kind, &params, "");
+            state
+                .records
+                .retain(|r| r["name"] != name || r["type"] != kind || r["rData"] != target);
+            respond(request, 200, json!({"status": "ok", "response": {}}))
+        }
+        _ => respond(request, 200, error("Invalid API call.")),
+    }
+}
+
+fn managed(instance_id: &str) -> String {
+    format!("Managed by DNSSync instance:{instance_id}")
+}
+
+#[test]
+fn syncs_records() {
+    let api = FakeApi::start();
+    api.add_record(
+        "example.com",
+        "SOA",
+        json!({"primaryNameServer": "ns1.example.com", "serial": 1}),
+        "",
+    );
+    api.add_record(
+        "host.example.com",
+        "A",
+        json!({"ipAddress": "10.0.0.1"}),
+        &format!("NAS in the loft {}", managed(INSTANCE_ID)),
+    );
+    api.add_record(
+        "old.example.com",
+        "A",
+        json!({"ipAddress": "10.0.0.1"}),
+        &managed(INSTANCE_ID),
+    );
+
+    let records = vec![
+        record("host.example.com", "A", "10.0.0.2"),
+        record("alias.example.com", "CNAME", "host.example.com."),
+        record("example.com", "MX", "10 mail.example.com."),
+        record("example.com", "TXT", "hello world"),
+    ];
+
+    // A dry run writes nothing
+    api.frontend(API_KEY)
+        .set_records(records.clone(), true)
+        .unwrap();
+    assert!(api.writes().is_empty());
+
+    api.frontend(API_KEY)
+        .set_records(records.clone(), false)
+        .unwrap();
+
+    let writes = api.writes();
+    let actions: Vec<(&str, &str)> = writes
+        .iter()
+        .map(|(action, params)| (action.as_str(), params["domain"].as_str()))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            ("delete", "old.example.com"),
+            ("update", "host.example.com"),
+            ("add", "alias.example.com"),
+            ("add", "example.com"),
+            ("add", "example.com"),
+        ]
+    );
+    assert_eq!(writes[1].1["ipAddress"], "10.0.0.1");
+    assert_eq!(writes[1].1["newIpAddress"], "10.0.0.2");
+
+    let host = api.find_record("host.example.com", "A").unwrap();
+    assert_eq!(host["rData"]["ipAddress"], "10.0.0.2");
+    assert_eq!(host["ttl"], 3600);
+    assert_eq!(
+        host["comments"],
+        format!("NAS in the loft {}", managed(INSTANCE_ID))
+    );
+
+    let alias = api.find_record("alias.example.com", "CNAME").unwrap();
+    assert_eq!(alias["rData"]["cname"], "host.example.com");
+    assert_eq!(alias["ttl"], 300);
+    assert_eq!(alias["comments"], managed(INSTANCE_ID));
+
+    let mx = api.find_record("example.com", "MX").unwrap();
+    assert_eq!(
+        mx["rData"],
+        json!({"preference": 10, "exchange": "mail.example.com"})
+    );
+    assert_eq!(
+        api.find_record("example.com", "TXT").unwrap()["rData"]["text"],
+        "hello world"
+    );
+    assert!(api.find_record("old.example.com", "A").is_none());
+    assert!(api.find_record("example.com", "SOA").is_some());
+
+    // Syncing again is a no-op now that the formats agree
+    api.frontend(API_KEY).set_records(records, false).unwrap();
+    assert_eq!(api.writes().len(), 5);
+}
+
+#[test]
+fn only_deletes_owned_records() {
+    let api = FakeApi::start();
+    api.add_record(
+        "manual.example.com",
+        "A",
+        json!({"ipAddress": "10.0.0.1"}),
+        "",
+    );
+    api.add_record(
+        "theirs.example.com",
+        "A",
+        json!({"ipAddress": "10.0.0.1"}),
+        &managed("other"),
+    );
+
+    api.frontend(API_KEY)
+        .set_records(vec![record("manual.example.com", "A", "10.0.0.2")], false)
+        .unwrap();
+
+    assert!(api.writes().is_empty());
+    assert!(api.find_record("theirs.example.com", "A").is_some());
+    assert_eq!(
+        api.find_record("manual.example.com", "A").unwrap()["rData"]["ipAddress"],
+        "10.0.0.1"
+    );
+}
+
+#[test]
+fn check_requires_primary_zone() {
+    let api = FakeApi::start();
+    api.frontend(API_KEY).check().unwrap();
+
+    api.state.lock().unwrap().zone_type = "Secondary".into();
+    let err = api.frontend(API_KEY).check().unwrap_err();
+    assert!(err.to_string().contains("is a Secondary zone"));
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let api = FakeApi::start();
+
+    let err = api.frontend("wrong").check().unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("failed with status invalid-token: Invalid token or session expired."));
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use dnssync_rs::common::Frontend;
+use dnssync_rs::unbound::{Config, Unbound};
+
+use common::record;
+
+fn frontend(path: &Path, control_command: Option<String>) -> Unbound {
+    Unbound::from(Config {
+        domain: "example.com".into(),
+        path: path.to_path_buf(),
+        live: control_command.is_some(),
+        control_command,
+        reload_command: None,
+        ttl: None,
+    })
+}
+
+/// Writes a stand-in for unbound-control which logs its arguments.
+fn fake_control(dir: &Path) -> (String, std::path::PathBuf) {
+    let log = dir.join("control.log");
+    let script = dir.join("unbound-control");
+    std::fs::write(
+        &script,
+        format!("#!/bin/sh\necho \"$@\" >> {}\necho ok\n", log.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    (script.display().to_string(), log)
+}
+
+#[test]
+fn writes_local_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dnssync.conf");
+
+    frontend(&path, None)
+        .set_records(
+            vec![
+                record("host.example.com", "A", "10.0.0.1"),
+                record("info.example.com", "TXT", "hello \"world\""),
+            ],
+            false,
+        )
+        .unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        text.lines().skip(1).collect::<Vec<&str>>(),
+        [
+            "local-data: 'host.example.com.\t300\tIN\tA\t10.0.0.1'",
+            "local-data: 'info.example.com.\t300\tIN\tTXT\t\"hello \\\"world\\\"\"'",
+        ]
+    );
+}
+
+#[test]
+fn applies_minimal_changes_live() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dnssync.conf");
+    let (control, log) = fake_control(dir.path());
+
+    frontend(&path, Some(control.clone()))
+        .set_records(
+            vec![
+                record("host.example.com", "A", "10.0.0.1"),
+                record("host.example.com", "AAAA", "fd00::1"),
+                record("other.example.com", "A", "10.0.0.9"),
+            ],
+            false,
+        )
+        .unwrap();
+    std::fs::remove_file(&log).unwrap();
+
+    frontend(&path, Some(control))
+        .set_records(
+            vec![
+                record("host.example.com", "A", "10.0.0.2"),
+                record("host.example.com", "AAAA", "fd00::1"),
+                record("other.example.com", "A", "10.0.0.9"),
+            ],
+            false,
+        )
+        .unwrap();
+
+    // Only the changed name is touched, keeping its other kinds
+    assert_eq!(
+        std::fs::read_to_string(&log).unwrap(),
+        "local_data_remove host.example.com\n\
+         local_data host.example.com.\t300\tIN\tA\t10.0.0.2\n\
+         local_data host.example.com.\t300\tIN\tAAAA\tfd00::1\n"
+    );
+}
+
+#[test]
+fn dry_run_makes_no_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dnssync.conf");
+    let (control, log) = fake_control(dir.path());
+
+    frontend(&path, Some(control))
+        .set_records(vec![record("host.example.com", "A", "10.0.0.1")], true)
+        .unwrap();
+
+    assert!(!path.exists());
+    assert!(!log.exists());
+}